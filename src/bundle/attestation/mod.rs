@@ -0,0 +1,259 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for [in-toto attestations] carried in DSSE envelopes.
+//!
+//! [in-toto attestations]: https://github.com/in-toto/attestation/blob/main/spec/README.md
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::dsse::{DssePayload, IN_TOTO_PAYLOAD_TYPE};
+
+pub mod slsa;
+
+/// The `_type` of in-toto v0.1 statements.
+pub const STATEMENT_TYPE_V0_1: &str = "https://in-toto.io/Statement/v0.1";
+
+/// The `_type` of in-toto v1 statements.
+pub const STATEMENT_TYPE_V1: &str = "https://in-toto.io/Statement/v1";
+
+#[derive(Error, Debug)]
+pub enum AttestationError {
+    #[error("unsupported DSSE payload type {0}")]
+    PayloadTypeUnsupported(String),
+
+    #[error("unsupported in-toto statement type {0}")]
+    StatementTypeUnsupported(String),
+
+    #[error("in-toto statement is malformed")]
+    Malformed(#[source] serde_json::Error),
+
+    #[error("no subject of the in-toto statement matches the artifact's {algorithm} digest")]
+    SubjectMismatch { algorithm: String },
+
+    #[error("predicate type {actual} does not match expected predicate type {expected}")]
+    PredicateTypeMismatch { actual: String, expected: String },
+
+    #[error("predicate is malformed")]
+    PredicateMalformed(#[source] serde_json::Error),
+}
+
+/// An in-toto Statement: a claim (the predicate) about one or more software artifacts (the
+/// subjects).
+///
+/// Both v0.1 and v1 statements are supported; their layouts are identical for the fields
+/// modelled here.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    #[serde(rename = "_type")]
+    pub statement_type: String,
+    pub subject: Vec<Subject>,
+    pub predicate_type: String,
+    #[serde(default)]
+    pub predicate: serde_json::Value,
+}
+
+/// A software artifact an in-toto statement is about.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subject {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Digests of the artifact, keyed by algorithm name (e.g. `sha256`), hex-encoded.
+    pub digest: BTreeMap<String, String>,
+}
+
+/// A predicate type that can be decoded from an in-toto statement.
+pub trait Predicate: Sized {
+    /// The `predicateType` URIs this predicate is decoded from.
+    const PREDICATE_TYPES: &'static [&'static str];
+
+    /// Decodes the predicate of a statement whose `predicateType` is `predicate_type`, one of
+    /// [`Predicate::PREDICATE_TYPES`].
+    fn from_predicate(
+        predicate_type: &str,
+        predicate: &serde_json::Value,
+    ) -> Result<Self, serde_json::Error>;
+}
+
+impl Statement {
+    /// Decodes an in-toto statement from a DSSE payload.
+    pub fn from_payload(payload: &DssePayload) -> Result<Self, AttestationError> {
+        if payload.payload_type != IN_TOTO_PAYLOAD_TYPE {
+            return Err(AttestationError::PayloadTypeUnsupported(
+                payload.payload_type.clone(),
+            ));
+        }
+
+        let statement: Statement =
+            serde_json::from_slice(&payload.payload).map_err(AttestationError::Malformed)?;
+
+        match statement.statement_type.as_str() {
+            STATEMENT_TYPE_V0_1 | STATEMENT_TYPE_V1 => Ok(statement),
+            other => Err(AttestationError::StatementTypeUnsupported(other.to_owned())),
+        }
+    }
+
+    /// Returns the first subject with a digest for `algorithm` equal to `digest`.
+    pub fn subject_for_digest(&self, algorithm: &str, digest: &[u8]) -> Option<&Subject> {
+        self.subject.iter().find(|subject| {
+            subject
+                .digest
+                .get(algorithm)
+                .and_then(|value| hex::decode(value).ok())
+                .is_some_and(|value| value == digest)
+        })
+    }
+
+    /// Checks that one of the statement's subjects has a digest for `algorithm` equal to `digest`.
+    pub fn verify_subject(&self, algorithm: &str, digest: &[u8]) -> Result<(), AttestationError> {
+        self.subject_for_digest(algorithm, digest)
+            .map(|_| ())
+            .ok_or_else(|| AttestationError::SubjectMismatch {
+                algorithm: algorithm.to_owned(),
+            })
+    }
+
+    /// Decodes the statement's predicate as `P`, checking that the predicate type matches.
+    pub fn predicate<P: Predicate>(&self) -> Result<P, AttestationError> {
+        if !P::PREDICATE_TYPES.contains(&self.predicate_type.as_str()) {
+            return Err(AttestationError::PredicateTypeMismatch {
+                actual: self.predicate_type.clone(),
+                expected: P::PREDICATE_TYPES.join(" or "),
+            });
+        }
+
+        P::from_predicate(&self.predicate_type, &self.predicate)
+            .map_err(AttestationError::PredicateMalformed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DIGEST: &str = "4bc453b53cb3d914b45f4b250294236adba2c0e09ff6f03793949e7e39fd4cc1";
+
+    fn payload(statement: serde_json::Value) -> DssePayload {
+        DssePayload {
+            payload_type: IN_TOTO_PAYLOAD_TYPE.to_owned(),
+            payload: serde_json::to_vec(&statement).unwrap(),
+        }
+    }
+
+    fn statement(statement_type: &str) -> serde_json::Value {
+        json!({
+            "_type": statement_type,
+            "subject": [
+                {"name": "other", "digest": {"sha256": "00".repeat(32)}},
+                {"name": "artifact", "digest": {"sha256": DIGEST, "sha512": "00".repeat(64)}},
+            ],
+            "predicateType": "https://example.com/Predicate/v1",
+            "predicate": {"answer": 42},
+        })
+    }
+
+    #[derive(Deserialize)]
+    struct ExamplePredicate {
+        answer: u64,
+    }
+
+    impl Predicate for ExamplePredicate {
+        const PREDICATE_TYPES: &'static [&'static str] = &["https://example.com/Predicate/v1"];
+
+        fn from_predicate(
+            _predicate_type: &str,
+            predicate: &serde_json::Value,
+        ) -> Result<Self, serde_json::Error> {
+            Self::deserialize(predicate)
+        }
+    }
+
+    #[test]
+    fn statement_from_payload() {
+        for statement_type in [STATEMENT_TYPE_V0_1, STATEMENT_TYPE_V1] {
+            let statement = Statement::from_payload(&payload(statement(statement_type))).unwrap();
+
+            let digest = hex::decode(DIGEST).unwrap();
+            assert_eq!(
+                statement
+                    .subject_for_digest("sha256", &digest)
+                    .unwrap()
+                    .name,
+                "artifact"
+            );
+            assert!(statement.verify_subject("sha256", &digest).is_ok());
+            assert_eq!(
+                statement.predicate::<ExamplePredicate>().unwrap().answer,
+                42
+            );
+        }
+    }
+
+    #[test]
+    fn statement_from_payload_unsupported() {
+        let mut wrong_payload_type = payload(statement(STATEMENT_TYPE_V1));
+        wrong_payload_type.payload_type = "text/plain".to_owned();
+        assert!(matches!(
+            Statement::from_payload(&wrong_payload_type),
+            Err(AttestationError::PayloadTypeUnsupported(_))
+        ));
+
+        assert!(matches!(
+            Statement::from_payload(&payload(statement("https://in-toto.io/Statement/v2"))),
+            Err(AttestationError::StatementTypeUnsupported(_))
+        ));
+
+        assert!(matches!(
+            Statement::from_payload(&payload(json!({"_type": STATEMENT_TYPE_V1}))),
+            Err(AttestationError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn statement_subject_mismatch() {
+        let statement = Statement::from_payload(&payload(statement(STATEMENT_TYPE_V1))).unwrap();
+
+        assert!(matches!(
+            statement.verify_subject("sha256", &[0xff; 32]),
+            Err(AttestationError::SubjectMismatch { .. })
+        ));
+        assert!(matches!(
+            statement.verify_subject("sha384", &hex::decode(DIGEST).unwrap()),
+            Err(AttestationError::SubjectMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn statement_predicate_mismatch() {
+        let mut statement =
+            Statement::from_payload(&payload(statement(STATEMENT_TYPE_V1))).unwrap();
+
+        statement.predicate = json!({"answer": "forty-two"});
+        assert!(matches!(
+            statement.predicate::<ExamplePredicate>(),
+            Err(AttestationError::PredicateMalformed(_))
+        ));
+
+        statement.predicate_type = "https://example.com/Predicate/v2".to_owned();
+        assert!(matches!(
+            statement.predicate::<ExamplePredicate>(),
+            Err(AttestationError::PredicateTypeMismatch { .. })
+        ));
+    }
+}
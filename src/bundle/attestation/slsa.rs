@@ -0,0 +1,442 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for [SLSA Provenance] predicates.
+//!
+//! Only the fields relevant to verification policies are modelled; the remaining fields are
+//! kept as raw JSON values where they are freeform.
+//!
+//! [SLSA Provenance]: https://slsa.dev/provenance
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::Predicate;
+
+/// The `predicateType` of SLSA Provenance v0.2 predicates.
+pub const PREDICATE_TYPE_V0_2: &str = "https://slsa.dev/provenance/v0.2";
+
+/// The `predicateType` of SLSA Provenance v1 predicates.
+pub const PREDICATE_TYPE_V1: &str = "https://slsa.dev/provenance/v1";
+
+/// A SLSA Provenance predicate of either supported version.
+///
+/// The accessors normalize the differences between versions, so that policies can be written
+/// once for both. The version is determined by the statement's `predicateType`, see
+/// [`Statement::predicate`](super::Statement::predicate).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Provenance {
+    V1(ProvenanceV1),
+    V0_2(ProvenanceV0_2),
+}
+
+impl Predicate for Provenance {
+    const PREDICATE_TYPES: &'static [&'static str] = &[PREDICATE_TYPE_V0_2, PREDICATE_TYPE_V1];
+
+    fn from_predicate(
+        predicate_type: &str,
+        predicate: &serde_json::Value,
+    ) -> Result<Self, serde_json::Error> {
+        match predicate_type {
+            PREDICATE_TYPE_V0_2 => ProvenanceV0_2::deserialize(predicate).map(Provenance::V0_2),
+            PREDICATE_TYPE_V1 => ProvenanceV1::deserialize(predicate).map(Provenance::V1),
+            _ => Err(serde::de::Error::custom(format!(
+                "unsupported predicate type {predicate_type}"
+            ))),
+        }
+    }
+}
+
+/// The source a build was started from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildSource {
+    /// The repository URI, e.g. `https://github.com/sigstore/sigstore-rs`.
+    pub repository: String,
+    /// The git ref, e.g. `refs/heads/main`, if known.
+    pub git_ref: Option<String>,
+}
+
+impl Provenance {
+    /// Returns the ID of the builder that produced the provenance.
+    pub fn builder_id(&self) -> &str {
+        match self {
+            Provenance::V1(p) => &p.run_details.builder.id,
+            Provenance::V0_2(p) => &p.builder.id,
+        }
+    }
+
+    /// Returns the build type, which determines how the build parameters are interpreted.
+    pub fn build_type(&self) -> &str {
+        match self {
+            Provenance::V1(p) => &p.build_definition.build_type,
+            Provenance::V0_2(p) => &p.build_type,
+        }
+    }
+
+    /// Returns the source repository and ref the build was started from, if they can be
+    /// determined.
+    ///
+    /// For v1 provenance, the GitHub Actions `workflow` external parameters are preferred, then
+    /// the first `git+` resolved dependency. For v0.2 provenance, the invocation's config source
+    /// is used.
+    pub fn source(&self) -> Option<BuildSource> {
+        match self {
+            Provenance::V1(p) => {
+                let workflow = &p.build_definition.external_parameters["workflow"];
+                if let Some(repository) = workflow["repository"].as_str() {
+                    return Some(BuildSource {
+                        repository: repository.to_owned(),
+                        git_ref: workflow["ref"].as_str().map(str::to_owned),
+                    });
+                }
+
+                p.build_definition
+                    .resolved_dependencies
+                    .iter()
+                    .find_map(|dependency| parse_git_uri(&dependency.uri))
+            }
+            Provenance::V0_2(p) => p
+                .invocation
+                .as_ref()
+                .and_then(|invocation| invocation.config_source.as_ref())
+                .and_then(|source| parse_git_uri(&source.uri)),
+        }
+    }
+
+    /// Returns the artifacts the build depended on: v1's resolved dependencies, or v0.2's
+    /// materials.
+    pub fn resolved_dependencies(&self) -> &[ResourceDescriptor] {
+        match self {
+            Provenance::V1(p) => &p.build_definition.resolved_dependencies,
+            Provenance::V0_2(p) => &p.materials,
+        }
+    }
+}
+
+/// Parses a `git+<repository>@<ref>` URI, as used by SLSA builders to record sources.
+fn parse_git_uri(uri: &str) -> Option<BuildSource> {
+    let uri = uri.strip_prefix("git+")?;
+    let (repository, git_ref) = match uri.rsplit_once('@') {
+        // Don't mistake the `user@` part of an SSH-style URI for a ref.
+        Some((repository, git_ref)) if !git_ref.contains(':') => {
+            (repository, Some(git_ref.to_owned()))
+        }
+        _ => (uri, None),
+    };
+
+    Some(BuildSource {
+        repository: repository.to_owned(),
+        git_ref,
+    })
+}
+
+/// A reference to an artifact, such as a source repository or a dependency.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDescriptor {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub uri: String,
+    /// Digests of the artifact, keyed by algorithm name (e.g. `sha1`), hex-encoded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub digest: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+}
+
+/// A SLSA Provenance v1 predicate.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceV1 {
+    pub build_definition: BuildDefinition,
+    pub run_details: RunDetails,
+}
+
+/// The inputs of a SLSA Provenance v1 build.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDefinition {
+    pub build_type: String,
+    pub external_parameters: serde_json::Value,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub internal_parameters: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resolved_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// The details of a SLSA Provenance v1 build's execution.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunDetails {
+    pub builder: BuilderV1,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BuildMetadataV1>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub byproducts: Vec<ResourceDescriptor>,
+}
+
+/// The entity that executed a SLSA Provenance v1 build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuilderV1 {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub version: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub builder_dependencies: Vec<ResourceDescriptor>,
+}
+
+/// Metadata about a SLSA Provenance v1 build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMetadataV1 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_on: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_on: Option<String>,
+}
+
+/// A SLSA Provenance v0.2 predicate.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenanceV0_2 {
+    pub builder: BuilderV0_2,
+    pub build_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invocation: Option<Invocation>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub build_config: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BuildMetadataV0_2>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub materials: Vec<ResourceDescriptor>,
+}
+
+/// The entity that executed a SLSA Provenance v0.2 build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderV0_2 {
+    pub id: String,
+}
+
+/// How a SLSA Provenance v0.2 build was started.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Invocation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_source: Option<ConfigSource>,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub parameters: serde_json::Value,
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub environment: serde_json::Value,
+}
+
+/// The source of the top-level build configuration of a SLSA Provenance v0.2 build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSource {
+    #[serde(default)]
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub digest: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub entry_point: String,
+}
+
+/// Metadata about a SLSA Provenance v0.2 build.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildMetadataV0_2 {
+    #[serde(
+        default,
+        alias = "buildInvocationID",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub build_invocation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_started_on: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_finished_on: Option<String>,
+    #[serde(default)]
+    pub reproducible: bool,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::bundle::attestation::{AttestationError, Statement};
+    use serde_json::json;
+
+    pub(crate) fn provenance_v1() -> Provenance {
+        Provenance::from_predicate(PREDICATE_TYPE_V1, &json!({
+            "buildDefinition": {
+                "buildType": "https://actions.github.io/buildtypes/workflow/v1",
+                "externalParameters": {
+                    "workflow": {
+                        "ref": "refs/heads/main",
+                        "repository": "https://github.com/sigstore/sigstore-rs",
+                        "path": ".github/workflows/release.yml"
+                    }
+                },
+                "internalParameters": {"github": {"event_name": "push"}},
+                "resolvedDependencies": [{
+                    "uri": "git+https://github.com/sigstore/sigstore-rs@refs/heads/main",
+                    "digest": {"gitCommit": "6ad5d5b4a9a0ed0e3cc8b1d9d5cbd3d6d1f2c9b8"}
+                }]
+            },
+            "runDetails": {
+                "builder": {"id": "https://github.com/actions/runner/github-hosted"},
+                "metadata": {
+                    "invocationId": "https://github.com/sigstore/sigstore-rs/actions/runs/1/attempts/1"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    pub(crate) fn provenance_v0_2() -> Provenance {
+        Provenance::from_predicate(PREDICATE_TYPE_V0_2, &json!({
+            "builder": {
+                "id": "https://github.com/slsa-framework/slsa-github-generator/.github/workflows/generator_generic_slsa3.yml@refs/tags/v1.9.0"
+            },
+            "buildType": "https://github.com/slsa-framework/slsa-github-generator/generic@v1",
+            "invocation": {
+                "configSource": {
+                    "uri": "git+https://github.com/sigstore/sigstore-rs@refs/tags/v0.9.0",
+                    "digest": {"sha1": "6ad5d5b4a9a0ed0e3cc8b1d9d5cbd3d6d1f2c9b8"},
+                    "entryPoint": ".github/workflows/release.yml"
+                },
+                "parameters": {},
+                "environment": {"github_event_name": "push"}
+            },
+            "metadata": {
+                "buildInvocationID": "1-1",
+                "completeness": {"parameters": true, "environment": false, "materials": false},
+                "reproducible": false
+            },
+            "materials": [{
+                "uri": "git+https://github.com/sigstore/sigstore-rs@refs/tags/v0.9.0",
+                "digest": {"sha1": "6ad5d5b4a9a0ed0e3cc8b1d9d5cbd3d6d1f2c9b8"}
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn provenance_v1_accessors() {
+        let provenance = provenance_v1();
+        assert!(matches!(provenance, Provenance::V1(_)));
+
+        assert_eq!(
+            provenance.builder_id(),
+            "https://github.com/actions/runner/github-hosted"
+        );
+        assert_eq!(
+            provenance.build_type(),
+            "https://actions.github.io/buildtypes/workflow/v1"
+        );
+        assert_eq!(
+            provenance.source(),
+            Some(BuildSource {
+                repository: "https://github.com/sigstore/sigstore-rs".into(),
+                git_ref: Some("refs/heads/main".into()),
+            })
+        );
+        assert_eq!(provenance.resolved_dependencies().len(), 1);
+    }
+
+    #[test]
+    fn provenance_v0_2_accessors() {
+        let provenance = provenance_v0_2();
+        assert!(matches!(provenance, Provenance::V0_2(_)));
+
+        assert!(provenance
+            .builder_id()
+            .starts_with("https://github.com/slsa-framework/slsa-github-generator/"));
+        assert_eq!(
+            provenance.build_type(),
+            "https://github.com/slsa-framework/slsa-github-generator/generic@v1"
+        );
+        assert_eq!(
+            provenance.source(),
+            Some(BuildSource {
+                repository: "https://github.com/sigstore/sigstore-rs".into(),
+                git_ref: Some("refs/tags/v0.9.0".into()),
+            })
+        );
+        assert_eq!(
+            provenance.resolved_dependencies()[0].digest["sha1"],
+            "6ad5d5b4a9a0ed0e3cc8b1d9d5cbd3d6d1f2c9b8"
+        );
+    }
+
+    #[test]
+    fn provenance_from_statement() {
+        let statement = Statement {
+            statement_type: crate::bundle::attestation::STATEMENT_TYPE_V1.into(),
+            subject: vec![],
+            predicate_type: PREDICATE_TYPE_V1.into(),
+            predicate: serde_json::to_value(provenance_v1()).unwrap(),
+        };
+        assert_eq!(
+            statement.predicate::<Provenance>().unwrap(),
+            provenance_v1()
+        );
+    }
+
+    #[test]
+    fn provenance_version_follows_predicate_type() {
+        let v1 = serde_json::to_value(provenance_v1()).unwrap();
+        let statement = |predicate_type: &str| Statement {
+            statement_type: crate::bundle::attestation::STATEMENT_TYPE_V1.into(),
+            subject: vec![],
+            predicate_type: predicate_type.into(),
+            predicate: v1.clone(),
+        };
+
+        // A v1 body under the v0.2 predicate type is not silently accepted as v1.
+        assert!(matches!(
+            statement(PREDICATE_TYPE_V0_2).predicate::<Provenance>(),
+            Err(AttestationError::PredicateMalformed(_))
+        ));
+        assert!(matches!(
+            statement(PREDICATE_TYPE_V1).predicate::<Provenance>(),
+            Ok(Provenance::V1(_))
+        ));
+    }
+
+    #[test]
+    fn parse_git_uris() {
+        assert_eq!(
+            parse_git_uri("git+https://github.com/sigstore/sigstore-rs"),
+            Some(BuildSource {
+                repository: "https://github.com/sigstore/sigstore-rs".into(),
+                git_ref: None,
+            })
+        );
+        assert_eq!(
+            parse_git_uri("git+ssh://git@github.com:sigstore/sigstore-rs"),
+            Some(BuildSource {
+                repository: "ssh://git@github.com:sigstore/sigstore-rs".into(),
+                git_ref: None,
+            })
+        );
+        assert_eq!(
+            parse_git_uri("https://github.com/sigstore/sigstore-rs"),
+            None
+        );
+    }
+}
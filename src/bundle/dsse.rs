@@ -0,0 +1,73 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for [Dead Simple Signing Envelopes] (DSSE).
+//!
+//! [Dead Simple Signing Envelopes]: https://github.com/secure-systems-lab/dsse
+
+pub use sigstore_protobuf_specs::io::intoto::{Envelope, Signature as EnvelopeSignature};
+
+/// The payload type of in-toto attestations.
+pub const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// A DSSE payload whose signature has been verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DssePayload {
+    pub payload_type: String,
+    pub payload: Vec<u8>,
+}
+
+impl From<Envelope> for DssePayload {
+    fn from(envelope: Envelope) -> Self {
+        Self {
+            payload_type: envelope.payload_type,
+            payload: envelope.payload,
+        }
+    }
+}
+
+/// Computes the [pre-authentication encoding] (PAE) of a payload: the message that is actually
+/// signed in a DSSE envelope.
+///
+/// [pre-authentication encoding]: https://github.com/secure-systems-lab/dsse/blob/v1.0.0/protocol.md#signature-definition
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut pae = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    pae.extend_from_slice(payload);
+    pae
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pae_matches_spec() {
+        // Test vector from the DSSE specification.
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world"
+        );
+    }
+
+    #[test]
+    fn pae_empty() {
+        assert_eq!(pae("", b""), b"DSSEv1 0  0 ");
+    }
+}
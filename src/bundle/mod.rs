@@ -16,7 +16,12 @@
 
 pub use sigstore_protobuf_specs::dev::sigstore::bundle::v1::Bundle;
 
+pub mod attestation;
+
+pub mod dsse;
+
 mod models;
+pub use models::{DigestAlgorithm, Version};
 
 #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
 #[cfg(feature = "sign")]
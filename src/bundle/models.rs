@@ -5,19 +5,22 @@ use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
 use json_syntax::Print;
 
 use sigstore_protobuf_specs::dev::sigstore::{
-    common::v1::LogId,
+    common::v1::{HashAlgorithm, LogId},
     rekor::v1::{Checkpoint, InclusionPromise, InclusionProof, KindVersion, TransparencyLogEntry},
 };
 
 use crate::rekor::models::{
-    log_entry::InclusionProof as RekorInclusionProof, LogEntry as RekorLogEntry,
+    hashedrekord::AlgorithmKind, log_entry::InclusionProof as RekorInclusionProof,
+    LogEntry as RekorLogEntry,
 };
 
-// Known Sigstore bundle media types.
-#[derive(Clone, Copy, Debug)]
+/// Known Sigstore bundle media types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Version {
     Bundle0_1,
     Bundle0_2,
+    /// Bundles whose verification material is a single certificate rather than a chain.
+    Bundle0_3,
 }
 
 impl Display for Version {
@@ -25,6 +28,7 @@ impl Display for Version {
         f.write_str(match &self {
             Version::Bundle0_1 => "application/vnd.dev.sigstore.bundle+json;version=0.1",
             Version::Bundle0_2 => "application/vnd.dev.sigstore.bundle+json;version=0.2",
+            Version::Bundle0_3 => "application/vnd.dev.sigstore.bundle.v0.3+json",
         })
     }
 }
@@ -36,6 +40,46 @@ impl FromStr for Version {
         match s {
             "application/vnd.dev.sigstore.bundle+json;version=0.1" => Ok(Version::Bundle0_1),
             "application/vnd.dev.sigstore.bundle+json;version=0.2" => Ok(Version::Bundle0_2),
+            // Some clients emitted v0.3 bundles with the older media type format.
+            "application/vnd.dev.sigstore.bundle.v0.3+json"
+            | "application/vnd.dev.sigstore.bundle+json;version=0.3" => Ok(Version::Bundle0_3),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Digest algorithms supported for artifacts signed in bundles.
+pub use crate::crypto::DigestAlgorithm;
+
+impl DigestAlgorithm {
+    /// The algorithm's identifier in `hashedrekord` entries.
+    pub(crate) fn rekor_kind(self) -> AlgorithmKind {
+        match self {
+            Self::Sha256 => AlgorithmKind::sha256,
+            Self::Sha384 => AlgorithmKind::sha384,
+            Self::Sha512 => AlgorithmKind::sha512,
+        }
+    }
+}
+
+impl From<DigestAlgorithm> for HashAlgorithm {
+    fn from(value: DigestAlgorithm) -> Self {
+        match value {
+            DigestAlgorithm::Sha256 => HashAlgorithm::Sha2256,
+            DigestAlgorithm::Sha384 => HashAlgorithm::Sha2384,
+            DigestAlgorithm::Sha512 => HashAlgorithm::Sha2512,
+        }
+    }
+}
+
+impl TryFrom<HashAlgorithm> for DigestAlgorithm {
+    type Error = ();
+
+    fn try_from(value: HashAlgorithm) -> Result<Self, Self::Error> {
+        match value {
+            HashAlgorithm::Sha2256 => Ok(DigestAlgorithm::Sha256),
+            HashAlgorithm::Sha2384 => Ok(DigestAlgorithm::Sha384),
+            HashAlgorithm::Sha2512 => Ok(DigestAlgorithm::Sha512),
             _ => Err(()),
         }
     }
@@ -73,11 +117,25 @@ impl TryFrom<RekorLogEntry> for TransparencyLogEntry {
     type Error = ();
 
     fn try_from(value: RekorLogEntry) -> Result<Self, Self::Error> {
-        let canonicalized_body = {
-            let mut body = json_syntax::to_value(value.body).or(Err(()))?;
-            body.canonicalize();
-            body.compact_print().to_string().into_bytes()
+        let mut body = json_syntax::to_value(value.body).or(Err(()))?;
+        body.canonicalize();
+        let kind_version = {
+            let object = body.as_object().ok_or(())?;
+            let field = |key: &str| {
+                object
+                    .get_unique(key)
+                    .ok()
+                    .flatten()
+                    .and_then(|value| value.as_str())
+                    .map(str::to_owned)
+                    .ok_or(())
+            };
+            KindVersion {
+                kind: field("kind")?,
+                version: field("apiVersion")?,
+            }
         };
+        let canonicalized_body = body.compact_print().to_string().into_bytes();
         let inclusion_promise = Some(InclusionPromise {
             signed_entry_timestamp: base64
                 .decode(value.verification.signed_entry_timestamp)
@@ -94,10 +152,7 @@ impl TryFrom<RekorLogEntry> for TransparencyLogEntry {
             inclusion_promise,
             inclusion_proof,
             integrated_time: value.integrated_time,
-            kind_version: Some(KindVersion {
-                kind: "hashedrekord".to_owned(),
-                version: "0.0.1".to_owned(),
-            }),
+            kind_version: Some(kind_version),
             log_id: Some(LogId {
                 key_id: decode_hex(value.log_i_d)?,
             }),
@@ -105,3 +160,38 @@ impl TryFrom<RekorLogEntry> for TransparencyLogEntry {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dsse_log_entry() {
+        let body = concat!(
+            r#"{"apiVersion":"0.0.1","kind":"dsse","spec":{"#,
+            r#""envelopeHash":{"algorithm":"sha256","value":"aa"},"#,
+            r#""payloadHash":{"algorithm":"sha256","value":"bb"},"#,
+            r#""signatures":[{"signature":"c2ln","verifier":"dmVyaWZpZXI="}]}}"#
+        );
+        let entry = json!({
+            "uuid": "00",
+            "body": base64.encode(body),
+            "integratedTime": 1,
+            "logID": "00",
+            "logIndex": 1,
+            "verification": { "signedEntryTimestamp": "c2V0" },
+        });
+
+        let entry: RekorLogEntry = entry.to_string().parse().unwrap();
+        let entry = TransparencyLogEntry::try_from(entry).unwrap();
+        assert_eq!(
+            entry.kind_version,
+            Some(KindVersion {
+                kind: "dsse".to_owned(),
+                version: "0.0.1".to_owned(),
+            })
+        );
+        assert_eq!(entry.canonicalized_body, body.as_bytes());
+    }
+}
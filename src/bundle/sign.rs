@@ -18,38 +18,47 @@ use std::io::{self, Read};
 use std::time::SystemTime;
 
 use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use const_oid::AssociatedOid;
 use hex;
-use p256::NistP256;
-use pkcs8::der::{Encode, EncodePem};
-use sha2::{Digest, Sha256};
-use signature::DigestSigner;
+use pkcs8::der::{Decode, Encode, EncodePem};
+use pkcs8::spki::SubjectPublicKeyInfoOwned;
+use serde_json::json;
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use sigstore_protobuf_specs::dev::sigstore::bundle::v1::bundle;
 use sigstore_protobuf_specs::dev::sigstore::bundle::v1::{
-    verification_material, Bundle, VerificationMaterial,
+    verification_material, Bundle, TimestampVerificationData, VerificationMaterial,
 };
 use sigstore_protobuf_specs::dev::sigstore::common::v1::{
-    HashAlgorithm, HashOutput, MessageSignature, X509Certificate, X509CertificateChain,
+    HashAlgorithm, HashOutput, MessageSignature, PublicKeyIdentifier, Rfc3161SignedTimestamp,
+    X509Certificate, X509CertificateChain,
 };
 use sigstore_protobuf_specs::dev::sigstore::rekor::v1::TransparencyLogEntry;
 use tokio::io::AsyncRead;
 use tokio_util::io::SyncIoBridge;
 use url::Url;
-use x509_cert::attr::{AttributeTypeAndValue, AttributeValue};
-use x509_cert::builder::{Builder, RequestBuilder as CertRequestBuilder};
-use x509_cert::ext::pkix as x509_ext;
 
-use crate::bundle::models::Version;
+use crate::bundle::attestation::Statement;
+use crate::bundle::dsse::{self, Envelope, EnvelopeSignature, IN_TOTO_PAYLOAD_TYPE};
+use crate::bundle::{DigestAlgorithm, Version};
 use crate::crypto::keyring::Keyring;
+use crate::crypto::signer::{AsyncSigner, SoftwareSigner};
+use crate::crypto::signing_key::{SigStoreKeyPair, SigStoreSigner};
 use crate::crypto::transparency::{verify_sct, CertificateEmbeddedSCT};
+use crate::crypto::SigningScheme;
 use crate::errors::{Result as SigstoreResult, SigstoreError};
 use crate::fulcio::oauth::OauthTokenProvider;
 use crate::fulcio::{self, FulcioClient, FULCIO_ROOT};
 use crate::oauth::IdentityToken;
 use crate::rekor::apis::configuration::Configuration as RekorConfiguration;
 use crate::rekor::apis::entries_api::create_log_entry;
-use crate::rekor::models::{hashedrekord, proposed_entry::ProposedEntry as ProposedLogEntry};
+use crate::rekor::models::{
+    dsse as dsse_entry, hashedrekord, proposed_entry::ProposedEntry as ProposedLogEntry,
+};
 use crate::trust::TrustRoot;
+use crate::tsa::TimestampAuthority;
 
+#[cfg(feature = "verify")]
+use crate::bundle::verify::{policy::PolicyResult, VerificationPolicy, Verifier};
 #[cfg(feature = "sigstore-trust-root")]
 use crate::trust::sigstore::SigstoreTrustRoot;
 
@@ -57,96 +66,122 @@ use crate::trust::sigstore::SigstoreTrustRoot;
 ///
 /// Sessions hold a provided user identity and key materials tied to that identity. A single
 /// session may be used to sign multiple items. For more information, see [`SigningSession::sign`].
+/// Sessions of contexts holding a long-lived key sign with that key instead.
 ///
 /// This signing session operates asynchronously. To construct a synchronous [`blocking::SigningSession`],
 /// use [`SigningContext::blocking_signer()`].
 pub struct SigningSession<'ctx> {
     context: &'ctx SigningContext,
-    identity_token: IdentityToken,
-    private_key: ecdsa::SigningKey<NistP256>,
-    certs: fulcio::CertificateResponse,
+    materials: SessionMaterials<'ctx>,
+}
+
+/// The key materials of a [`SigningSession`].
+#[allow(clippy::large_enum_variant)]
+enum SessionMaterials<'ctx> {
+    /// An ephemeral key, certified by Fulcio for the session's identity.
+    Keyless {
+        identity_token: IdentityToken,
+        private_key: Box<dyn AsyncSigner>,
+        certs: fulcio::CertificateResponse,
+        ctfe_keyring: &'ctx Keyring,
+    },
+    /// The context's long-lived key.
+    Key(&'ctx LongLivedKey),
 }
 
 impl<'ctx> SigningSession<'ctx> {
     async fn new(
         context: &'ctx SigningContext,
         identity_token: IdentityToken,
+        private_key: Box<dyn AsyncSigner>,
     ) -> SigstoreResult<SigningSession<'ctx>> {
-        let (private_key, certs) = Self::materials(&context.fulcio, &identity_token).await?;
+        let SigningIdentity::Fulcio {
+            fulcio,
+            ctfe_keyring,
+        } = &context.identity
+        else {
+            return Err(SigstoreError::SigningContextMismatchError(
+                "keyless signing",
+            ));
+        };
+
+        let certs = fulcio
+            .request_cert_with_signer(private_key.as_ref(), &identity_token)
+            .await?;
         Ok(Self {
             context,
-            identity_token,
-            private_key,
-            certs,
+            materials: SessionMaterials::Keyless {
+                identity_token,
+                private_key,
+                certs,
+                ctfe_keyring,
+            },
         })
     }
 
-    async fn materials(
-        fulcio: &FulcioClient,
-        token: &IdentityToken,
-    ) -> SigstoreResult<(ecdsa::SigningKey<NistP256>, fulcio::CertificateResponse)> {
-        let subject =
-                // SEQUENCE OF RelativeDistinguishedName
-                vec![
-                    // SET OF AttributeTypeAndValue
-                    vec![
-                        // AttributeTypeAndValue, `emailAddress=...`
-                        AttributeTypeAndValue {
-                            oid: const_oid::db::rfc3280::EMAIL_ADDRESS,
-                            value: AttributeValue::new(
-                                pkcs8::der::Tag::Utf8String,
-                                token.unverified_claims().email.as_ref(),
-                            )?,
-                        }
-                    ].try_into()?
-                ].into();
-
-        let mut rng = rand::thread_rng();
-        let private_key = ecdsa::SigningKey::from(p256::SecretKey::random(&mut rng));
-        let mut builder = CertRequestBuilder::new(subject, &private_key)?;
-        builder.add_extension(&x509_ext::BasicConstraints {
-            ca: false,
-            path_len_constraint: None,
-        })?;
-
-        let cert_req = builder.build::<p256::ecdsa::DerSignature>()?;
-        Ok((private_key, fulcio.request_cert_v2(cert_req, token).await?))
+    fn with_key(context: &'ctx SigningContext) -> SigstoreResult<SigningSession<'ctx>> {
+        let SigningIdentity::Key(key) = &context.identity else {
+            return Err(SigstoreError::SigningContextMismatchError(
+                "signing with a long-lived key",
+            ));
+        };
+
+        Ok(Self {
+            context,
+            materials: SessionMaterials::Key(key),
+        })
     }
 
     /// Check if the session's identity token or key material is expired.
     ///
     /// If the session is expired, it cannot be used for signing operations, and a new session
-    /// must be created with a fresh identity token.
+    /// must be created with a fresh identity token. Sessions signing with a long-lived key never
+    /// expire.
     pub fn is_expired(&self) -> bool {
-        let not_after = self
-            .certs
+        let SessionMaterials::Keyless {
+            identity_token,
+            certs,
+            ..
+        } = &self.materials
+        else {
+            return false;
+        };
+
+        let not_after = certs
             .cert
             .tbs_certificate
             .validity
             .not_after
             .to_system_time();
-
-        !self.identity_token.in_validity_period() || SystemTime::now() > not_after
+        !identity_token.in_validity_period() || SystemTime::now() > not_after
     }
 
-    async fn sign_digest(&self, hasher: Sha256) -> SigstoreResult<SigningArtifact> {
-        if self.is_expired() {
-            return Err(SigstoreError::ExpiredSigningSession());
+    /// Returns the session's private key.
+    fn private_key(&self) -> &dyn AsyncSigner {
+        match &self.materials {
+            SessionMaterials::Keyless { private_key, .. } => private_key.as_ref(),
+            SessionMaterials::Key(key) => key.private_key.as_ref(),
         }
+    }
 
-        if let Some(detached_sct) = &self.certs.detached_sct {
-            verify_sct(detached_sct, &self.context.ctfe_keyring)?;
-        } else {
-            let sct = CertificateEmbeddedSCT::new(&self.certs.cert, &self.certs.chain)?;
-            verify_sct(&sct, &self.context.ctfe_keyring)?;
-        }
+    /// Signs for the digest computed by `hasher` with the session's identity. The digest
+    /// algorithm, which must be SHA-256, SHA-384 or SHA-512, is recorded in the log entry and
+    /// the bundle. If the identity is expired, [`SigstoreError::ExpiredSigningSession`] is
+    /// returned.
+    pub async fn sign_digest<D>(&self, hasher: D) -> SigstoreResult<SigningArtifact>
+    where
+        D: Digest + AssociatedOid,
+    {
+        self.check_materials()?;
+        let digest_algorithm = DigestAlgorithm::from_oid(D::OID)
+            .ok_or_else(|| SigstoreError::DigestUnsupportedAlgorithmError(D::OID.to_string()))?;
 
         // Sign artifact.
-        let input_hash: &[u8] = &hasher.clone().finalize();
-        let artifact_signature: p256::ecdsa::Signature = self.private_key.sign_digest(hasher);
-        let signature_bytes = artifact_signature.to_der().as_bytes().to_owned();
-
-        let cert = &self.certs.cert;
+        let input_hash: &[u8] = &hasher.finalize();
+        let signature_bytes = self
+            .private_key()
+            .sign_digest(digest_algorithm, input_hash)
+            .await?;
 
         // Create the transparency log entry.
         let proposed_entry = ProposedLogEntry::Hashedrekord {
@@ -154,40 +189,181 @@ impl<'ctx> SigningSession<'ctx> {
             spec: hashedrekord::Spec {
                 signature: hashedrekord::Signature {
                     content: base64.encode(&signature_bytes),
-                    public_key: hashedrekord::PublicKey::new(
-                        base64.encode(cert.to_pem(pkcs8::LineEnding::LF)?),
-                    ),
+                    public_key: hashedrekord::PublicKey::new(self.encoded_verifier()?),
                 },
                 data: hashedrekord::Data {
                     hash: hashedrekord::Hash {
-                        algorithm: hashedrekord::AlgorithmKind::sha256,
+                        algorithm: digest_algorithm.rekor_kind(),
                         value: hex::encode(input_hash),
                     },
                 },
             },
         };
 
-        let log_entry = create_log_entry(&self.context.rekor_config, proposed_entry)
+        let content = bundle::Content::MessageSignature(MessageSignature {
+            message_digest: Some(HashOutput {
+                algorithm: HashAlgorithm::from(digest_algorithm).into(),
+                digest: input_hash.to_owned(),
+            }),
+            signature: signature_bytes.clone(),
+        });
+        self.publish(&signature_bytes, proposed_entry, content)
             .await
-            .map_err(|err| SigstoreError::RekorClientError(err.to_string()))?;
-        let log_entry = log_entry
-            .try_into()
-            .or(Err(SigstoreError::RekorClientError(
-                "Rekor returned malformed LogEntry".into(),
-            )))?;
-
-        // TODO(tnytown): Maybe run through the verification flow here? See sigstore-rs#296.
-
-        Ok(SigningArtifact {
-            input_digest: input_hash.to_owned(),
-            cert: cert.to_der()?,
-            signature: signature_bytes,
+    }
+
+    /// Signs for `payload`, of type `payload_type`, with the session's identity, producing a
+    /// [DSSE] envelope. The envelope is recorded in a `dsse` log entry. If the identity is
+    /// expired, [`SigstoreError::ExpiredSigningSession`] is returned.
+    ///
+    /// To sign an in-toto attestation, use [`SigningSession::attest`].
+    ///
+    /// [DSSE]: https://github.com/secure-systems-lab/dsse
+    pub async fn sign_dsse(
+        &self,
+        payload_type: &str,
+        payload: Vec<u8>,
+    ) -> SigstoreResult<SigningArtifact> {
+        self.check_materials()?;
+
+        // Sign the envelope's pre-authentication encoding.
+        let signature = self
+            .private_key()
+            .sign(&dsse::pae(payload_type, &payload))
+            .await?;
+        let envelope = Envelope {
+            payload,
+            payload_type: payload_type.to_owned(),
+            signatures: vec![EnvelopeSignature {
+                sig: signature,
+                keyid: String::new(),
+            }],
+        };
+
+        // Create the transparency log entry. Rekor expects the envelope in its JSON encoding.
+        let envelope_json = json!({
+            "payload": base64.encode(&envelope.payload),
+            "payloadType": envelope.payload_type,
+            "signatures": [{ "sig": base64.encode(&envelope.signatures[0].sig) }],
+        });
+        let proposed_entry = ProposedLogEntry::Dsse {
+            api_version: "0.0.1".to_owned(),
+            spec: dsse_entry::Spec::new(dsse_entry::ProposedContent::new(
+                envelope_json.to_string(),
+                vec![self.encoded_verifier()?],
+            )),
+        };
+
+        let signature = envelope.signatures[0].sig.clone();
+        self.publish(
+            &signature,
+            proposed_entry,
+            bundle::Content::DsseEnvelope(envelope),
+        )
+        .await
+    }
+
+    /// Signs for an in-toto `statement` with the session's identity, producing a DSSE envelope.
+    ///
+    /// See [`SigningSession::sign_dsse`].
+    pub async fn attest(&self, statement: &Statement) -> SigstoreResult<SigningArtifact> {
+        let payload = serde_json::to_vec(statement)?;
+        self.sign_dsse(IN_TOTO_PAYLOAD_TYPE, payload).await
+    }
+
+    /// Checks that the session's key material can be used for signing: the identity must not be
+    /// expired, and the signing certificate's SCT must be valid.
+    fn check_materials(&self) -> SigstoreResult<()> {
+        if self.is_expired() {
+            return Err(SigstoreError::ExpiredSigningSession());
+        }
+
+        let SessionMaterials::Keyless {
+            certs,
+            ctfe_keyring,
+            ..
+        } = &self.materials
+        else {
+            return Ok(());
+        };
+
+        if let Some(detached_sct) = &certs.detached_sct {
+            verify_sct(detached_sct, ctfe_keyring)?;
+        } else {
+            let sct = CertificateEmbeddedSCT::new(&certs.cert, &certs.chain)?;
+            verify_sct(&sct, ctfe_keyring)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the signing certificate or public key as recorded in log entries: base64-encoded
+    /// PEM.
+    fn encoded_verifier(&self) -> SigstoreResult<String> {
+        let pem = match &self.materials {
+            SessionMaterials::Keyless { certs, .. } => certs.cert.to_pem(pkcs8::LineEnding::LF)?,
+            SessionMaterials::Key(key) => key.public_key_pem.clone(),
+        };
+        Ok(base64.encode(pem))
+    }
+
+    /// Timestamps `signature` and uploads `proposed_entry`, the transparency log entry for the
+    /// signed `content`, as configured by the context. Returns the resulting [`SigningArtifact`].
+    async fn publish(
+        &self,
+        signature: &[u8],
+        proposed_entry: ProposedLogEntry,
+        content: bundle::Content,
+    ) -> SigstoreResult<SigningArtifact> {
+        let timestamps = match &self.context.timestamp_authority {
+            Some(tsa) => vec![tsa.timestamp(signature).await?],
+            None => vec![],
+        };
+
+        let log_entry = if self.context.transparency_log {
+            let log_entry = create_log_entry(&self.context.rekor_config, proposed_entry)
+                .await
+                .map_err(|err| SigstoreError::RekorClientError(err.to_string()))?;
+            let log_entry = log_entry
+                .try_into()
+                .or(Err(SigstoreError::RekorClientError(
+                    "Rekor returned malformed LogEntry".into(),
+                )))?;
+            Some(log_entry)
+        } else if timestamps.is_empty()
+            && matches!(self.materials, SessionMaterials::Keyless { .. })
+        {
+            return Err(SigstoreError::TimestampAuthorityError(
+                "keyless signing requires a timestamp authority when not using a transparency log"
+                    .into(),
+            ));
+        } else {
+            None
+        };
+
+        let signer = match &self.materials {
+            SessionMaterials::Keyless { certs, .. } => {
+                SignerMaterial::Certificate(certs.cert.to_der()?)
+            }
+            SessionMaterials::Key(key) => SignerMaterial::PublicKey(key.hint.clone()),
+        };
+        let artifact = SigningArtifact {
+            signer,
+            content,
             log_entry,
-        })
+            timestamps,
+        };
+
+        #[cfg(feature = "verify")]
+        if let Some(verifier) = &self.context.verifier {
+            artifact.verify(verifier).await?;
+        }
+
+        Ok(artifact)
     }
 
-    /// Signs for the input with the session's identity. If the identity is expired,
-    /// [`SigstoreError::ExpiredSigningSession`] is returned.
+    /// Signs for the input with the session's identity, hashing it with the digest algorithm of
+    /// the session's key. If the identity is expired, [`SigstoreError::ExpiredSigningSession`]
+    /// is returned.
     pub async fn sign<R: AsyncRead + Unpin + Send + 'static>(
         &self,
         input: R,
@@ -196,18 +372,33 @@ impl<'ctx> SigningSession<'ctx> {
             return Err(SigstoreError::ExpiredSigningSession());
         }
 
+        match self.private_key().digest_algorithm() {
+            DigestAlgorithm::Sha256 => self.hash_and_sign::<Sha256, _>(input).await,
+            DigestAlgorithm::Sha384 => self.hash_and_sign::<Sha384, _>(input).await,
+            DigestAlgorithm::Sha512 => self.hash_and_sign::<Sha512, _>(input).await,
+        }
+    }
+
+    /// Hashes the input with `D` on a blocking thread, then signs for its digest.
+    async fn hash_and_sign<D, R>(&self, input: R) -> SigstoreResult<SigningArtifact>
+    where
+        D: Digest + AssociatedOid + io::Write + Send + 'static,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
         let mut sync_input = SyncIoBridge::new(input);
-        let hasher = tokio::task::spawn_blocking(move || -> SigstoreResult<_> {
-            let mut hasher = Sha256::new();
-            io::copy(&mut sync_input, &mut hasher)?;
-            Ok(hasher)
-        })
-        .await??;
+        let hasher = tokio::task::spawn_blocking(move || hash::<D>(&mut sync_input)).await??;
 
         self.sign_digest(hasher).await
     }
 }
 
+/// Hashes `input` with `D`.
+fn hash<D: Digest + io::Write>(input: &mut impl Read) -> io::Result<D> {
+    let mut hasher = D::new();
+    io::copy(input, &mut hasher)?;
+    Ok(hasher)
+}
+
 pub mod blocking {
     use super::{SigningSession as AsyncSigningSession, *};
 
@@ -224,11 +415,23 @@ pub mod blocking {
     }
 
     impl<'ctx> SigningSession<'ctx> {
-        pub(crate) fn new(ctx: &'ctx SigningContext, token: IdentityToken) -> SigstoreResult<Self> {
+        pub(crate) fn new(
+            ctx: &'ctx SigningContext,
+            token: IdentityToken,
+            private_key: Box<dyn AsyncSigner>,
+        ) -> SigstoreResult<Self> {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()?;
-            let inner = rt.block_on(AsyncSigningSession::new(ctx, token))?;
+            let inner = rt.block_on(AsyncSigningSession::new(ctx, token, private_key))?;
+            Ok(Self { inner, rt })
+        }
+
+        pub(crate) fn with_key(ctx: &'ctx SigningContext) -> SigstoreResult<Self> {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            let inner = AsyncSigningSession::with_key(ctx)?;
             Ok(Self { inner, rt })
         }
 
@@ -240,25 +443,67 @@ pub mod blocking {
             self.inner.is_expired()
         }
 
-        /// Signs for the input with the session's identity. If the identity is expired,
+        /// Signs for the input with the session's identity, hashing it with the digest algorithm
+        /// of the session's key. If the identity is expired,
         /// [`SigstoreError::ExpiredSigningSession`] is returned.
         pub fn sign<R: Read>(&self, mut input: R) -> SigstoreResult<SigningArtifact> {
-            let mut hasher = Sha256::new();
-            io::copy(&mut input, &mut hasher)?;
+            match self.inner.private_key().digest_algorithm() {
+                DigestAlgorithm::Sha256 => self.sign_digest(hash::<Sha256>(&mut input)?),
+                DigestAlgorithm::Sha384 => self.sign_digest(hash::<Sha384>(&mut input)?),
+                DigestAlgorithm::Sha512 => self.sign_digest(hash::<Sha512>(&mut input)?),
+            }
+        }
+
+        /// Signs for the digest computed by `hasher` with the session's identity.
+        ///
+        /// See [`AsyncSigningSession::sign_digest`].
+        pub fn sign_digest<D>(&self, hasher: D) -> SigstoreResult<SigningArtifact>
+        where
+            D: Digest + AssociatedOid,
+        {
             self.rt.block_on(self.inner.sign_digest(hasher))
         }
+
+        /// Signs for `payload`, of type `payload_type`, with the session's identity, producing a
+        /// DSSE envelope.
+        ///
+        /// See [`AsyncSigningSession::sign_dsse`].
+        pub fn sign_dsse(
+            &self,
+            payload_type: &str,
+            payload: Vec<u8>,
+        ) -> SigstoreResult<SigningArtifact> {
+            self.rt
+                .block_on(self.inner.sign_dsse(payload_type, payload))
+        }
+
+        /// Signs for an in-toto `statement` with the session's identity, producing a DSSE
+        /// envelope.
+        ///
+        /// See [`AsyncSigningSession::attest`].
+        pub fn attest(&self, statement: &Statement) -> SigstoreResult<SigningArtifact> {
+            self.rt.block_on(self.inner.attest(statement))
+        }
     }
 }
 
 /// A Sigstore signing context.
 ///
 /// Contexts hold Fulcio (CA) and Rekor (CT) configurations which signing sessions can be
-/// constructed against. Use [`SigningContext::production`] to create a context against
-/// the public-good Sigstore infrastructure.
+/// constructed against, and optionally a timestamp authority (TSA). Use
+/// [`SigningContext::production`] to create a context against the public-good Sigstore
+/// infrastructure.
+///
+/// Contexts may hold a long-lived key in place of a Fulcio configuration: see
+/// [`SigningContext::from_signer`].
 pub struct SigningContext {
-    fulcio: FulcioClient,
+    identity: SigningIdentity,
     rekor_config: RekorConfiguration,
-    ctfe_keyring: Keyring,
+    timestamp_authority: Option<Box<dyn TimestampAuthority>>,
+    transparency_log: bool,
+    signing_scheme: SigningScheme,
+    #[cfg(feature = "verify")]
+    verifier: Option<Verifier>,
 }
 
 impl SigningContext {
@@ -268,13 +513,145 @@ impl SigningContext {
         rekor_config: RekorConfiguration,
         ctfe_keyring: Keyring,
     ) -> Self {
+        Self::with_identity(
+            SigningIdentity::Fulcio {
+                fulcio,
+                ctfe_keyring,
+            },
+            rekor_config,
+        )
+    }
+
+    /// Constructs a [`SigningContext`] that signs with a long-lived key instead of Fulcio
+    /// certificates, so that no identity provider is needed. Use [`SigningContext::key_signer`]
+    /// to create its sessions.
+    ///
+    /// Bundles identify the key by `hint`, under which verifiers must trust its public key. Log
+    /// entries record the public key itself. RSA-PSS signers are not supported, and Ed25519
+    /// signers can only sign DSSE envelopes, since Ed25519 signs whole messages rather than
+    /// digests.
+    pub fn from_signer(
+        signer: &SigStoreSigner,
+        hint: String,
+        rekor_config: RekorConfiguration,
+    ) -> SigstoreResult<Self> {
+        let key = LongLivedKey {
+            private_key: Box::new(SoftwareSigner::try_from(signer)?),
+            public_key_pem: signer.to_sigstore_keypair()?.public_key_to_pem()?,
+            hint,
+        };
+        Ok(Self::with_identity(SigningIdentity::Key(key), rekor_config))
+    }
+
+    /// Constructs a [`SigningContext`] that signs with a long-lived key held by `signer`, such as
+    /// a key in an HSM or a cloud KMS.
+    ///
+    /// See [`SigningContext::from_signer`].
+    pub async fn from_async_signer<S>(
+        signer: S,
+        hint: String,
+        rekor_config: RekorConfiguration,
+    ) -> SigstoreResult<Self>
+    where
+        S: AsyncSigner + 'static,
+    {
+        let public_key = SubjectPublicKeyInfoOwned::from_der(&signer.public_key().await?)?;
+        let key = LongLivedKey {
+            private_key: Box::new(signer),
+            public_key_pem: public_key.to_pem(pkcs8::LineEnding::LF)?,
+            hint,
+        };
+        Ok(Self::with_identity(SigningIdentity::Key(key), rekor_config))
+    }
+
+    /// Constructs a [`SigningContext`] that signs with `key_pair`, using `signing_scheme`.
+    ///
+    /// See [`SigningContext::from_signer`].
+    pub fn from_key_pair(
+        key_pair: &SigStoreKeyPair,
+        signing_scheme: &SigningScheme,
+        hint: String,
+        rekor_config: RekorConfiguration,
+    ) -> SigstoreResult<Self> {
+        let signer = key_pair.to_sigstore_signer(signing_scheme)?;
+        Self::from_signer(&signer, hint, rekor_config)
+    }
+
+    fn with_identity(identity: SigningIdentity, rekor_config: RekorConfiguration) -> Self {
         Self {
-            fulcio,
+            identity,
             rekor_config,
-            ctfe_keyring,
+            timestamp_authority: None,
+            transparency_log: true,
+            signing_scheme: SigningScheme::default(),
+            #[cfg(feature = "verify")]
+            verifier: None,
         }
     }
 
+    /// Generates each keyless session's ephemeral key with `signing_scheme`, rather than the
+    /// default ECDSA P-256 with SHA-256. Fulcio certifies the key as usual.
+    ///
+    /// RSA keys sign with PKCS#1 v1.5 padding, and the key size carried by the scheme. Signatures
+    /// over artifact digests use the artifact's digest algorithm, while DSSE envelopes are hashed
+    /// with SHA-256, as verifiers assume for certified RSA keys: schemes with other RSA digest
+    /// algorithms are rejected. So are RSA-PSS schemes, since Rekor verifies RSA signatures with
+    /// PKCS#1 v1.5.
+    ///
+    /// Ed25519 keys sign whole messages rather than digests: their sessions can only sign DSSE
+    /// envelopes.
+    pub fn with_signing_scheme(mut self, signing_scheme: SigningScheme) -> SigstoreResult<Self> {
+        match signing_scheme {
+            SigningScheme::RSA_PKCS1_SHA384(_)
+            | SigningScheme::RSA_PKCS1_SHA512(_)
+            | SigningScheme::RSA_PSS_SHA256(_)
+            | SigningScheme::RSA_PSS_SHA384(_)
+            | SigningScheme::RSA_PSS_SHA512(_) => Err(
+                SigstoreError::SigningSchemeUnsupportedError(signing_scheme.to_string()),
+            ),
+            _ => {
+                self.signing_scheme = signing_scheme;
+                Ok(self)
+            }
+        }
+    }
+
+    /// Requests an RFC 3161 timestamp over each signature from `tsa`, and records it in the
+    /// signature's bundle. Use [`TimestampClient`](crate::tsa::TimestampClient) for a timestamp
+    /// authority reachable over HTTP.
+    pub fn with_timestamp_authority<T>(mut self, tsa: T) -> Self
+    where
+        T: TimestampAuthority + 'static,
+    {
+        self.timestamp_authority = Some(Box::new(tsa));
+        self
+    }
+
+    /// Does not record signatures in Rekor, relying on the timestamp authority alone to attest to
+    /// the time of signing. Keyless signing fails unless a timestamp authority is configured with
+    /// [`SigningContext::with_timestamp_authority`], since signing certificates are only valid
+    /// for a short time. Contexts holding a long-lived key may sign without either.
+    ///
+    /// This suits private deployments without a transparency log. The resulting bundles carry no
+    /// transparency log entry.
+    pub fn without_transparency_log(mut self) -> Self {
+        self.transparency_log = false;
+        self
+    }
+
+    /// Verifies each signature with `verifier` before returning it, guarding against Fulcio or
+    /// Rekor returning materials that do not match what was signed.
+    ///
+    /// The log entry returned by Rekor is checked as a bundle's would be: its body must be
+    /// consistent with the signature, and its signed entry timestamp and inclusion proof must be
+    /// valid. The signing certificate must chain up to the verifier's trust root.
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    #[cfg(feature = "verify")]
+    pub fn with_self_verification(mut self, verifier: Verifier) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
     /// Returns a [`SigningContext`] configured against the public-good production Sigstore
     /// infrastructure.
     #[cfg_attr(docsrs, doc(cfg(feature = "sigstore-trust-root")))]
@@ -306,8 +683,25 @@ impl SigningContext {
     }
 
     /// Configures and returns a [`SigningSession`] with the held context.
+    ///
+    /// Contexts holding a long-lived key must use [`SigningContext::key_signer`].
     pub async fn signer(&self, identity_token: IdentityToken) -> SigstoreResult<SigningSession> {
-        SigningSession::new(self, identity_token).await
+        let private_key = SoftwareSigner::generate(self.signing_scheme)?;
+        SigningSession::new(self, identity_token, Box::new(private_key)).await
+    }
+
+    /// Configures and returns a [`SigningSession`] whose certificate Fulcio issues for
+    /// `private_key`, rather than for a freshly generated key. The key may live outside of the
+    /// process, e.g. in an HSM or a cloud KMS.
+    pub async fn signer_with_key<S>(
+        &self,
+        identity_token: IdentityToken,
+        private_key: S,
+    ) -> SigstoreResult<SigningSession<'_>>
+    where
+        S: AsyncSigner + 'static,
+    {
+        SigningSession::new(self, identity_token, Box::new(private_key)).await
     }
 
     /// Configures and returns a [`blocking::SigningSession`] with the held context.
@@ -317,52 +711,698 @@ impl SigningContext {
         &self,
         identity_token: IdentityToken,
     ) -> SigstoreResult<blocking::SigningSession> {
-        blocking::SigningSession::new(self, identity_token)
+        let private_key = SoftwareSigner::generate(self.signing_scheme)?;
+        blocking::SigningSession::new(self, identity_token, Box::new(private_key))
     }
+
+    /// Configures and returns a [`SigningSession`] signing with the context's long-lived key.
+    ///
+    /// Only contexts constructed with [`SigningContext::from_signer`] or
+    /// [`SigningContext::from_key_pair`] hold such a key.
+    pub fn key_signer(&self) -> SigstoreResult<SigningSession<'_>> {
+        SigningSession::with_key(self)
+    }
+
+    /// Configures and returns a [`blocking::SigningSession`] signing with the context's
+    /// long-lived key.
+    ///
+    /// See [`SigningContext::key_signer`].
+    pub fn blocking_key_signer(&self) -> SigstoreResult<blocking::SigningSession<'_>> {
+        blocking::SigningSession::with_key(self)
+    }
+}
+
+/// How a [`SigningContext`] binds signatures to a signer.
+#[allow(clippy::large_enum_variant)]
+enum SigningIdentity {
+    /// Ephemeral keys certified by Fulcio, whose SCTs are verified against `ctfe_keyring`.
+    Fulcio {
+        fulcio: FulcioClient,
+        ctfe_keyring: Keyring,
+    },
+    /// A long-lived key.
+    Key(LongLivedKey),
+}
+
+/// A long-lived signing key, identified in bundles by `hint`.
+struct LongLivedKey {
+    private_key: Box<dyn AsyncSigner>,
+    public_key_pem: String,
+    hint: String,
 }
 
 /// A signature and its associated metadata.
+#[derive(Clone)]
 pub struct SigningArtifact {
-    input_digest: Vec<u8>,
-    cert: Vec<u8>,
-    signature: Vec<u8>,
-    log_entry: TransparencyLogEntry,
+    signer: SignerMaterial,
+    content: bundle::Content,
+    log_entry: Option<TransparencyLogEntry>,
+    /// DER-encoded RFC 3161 `TimeStampResp`s over the signature.
+    timestamps: Vec<Vec<u8>>,
 }
 
 impl SigningArtifact {
+    /// Verifies the signature and the materials returned by Fulcio and Rekor, as a verifier of the
+    /// resulting bundle would.
+    #[cfg(feature = "verify")]
+    async fn verify(&self, verifier: &Verifier) -> SigstoreResult<()> {
+        // Rekor returns inclusion proofs for new entries, which verification would otherwise
+        // skip.
+        if self
+            .log_entry
+            .as_ref()
+            .is_some_and(|entry| entry.inclusion_proof.is_none())
+        {
+            return Err(SigstoreError::RekorClientError(
+                "Rekor returned no inclusion proof".into(),
+            ));
+        }
+
+        let bundle = self.clone().to_bundle();
+        let result = match &self.content {
+            bundle::Content::MessageSignature(MessageSignature {
+                message_digest: Some(message_digest),
+                ..
+            }) => {
+                let algorithm = message_digest.algorithm();
+                let digest_algorithm = DigestAlgorithm::try_from(algorithm).or(Err(
+                    SigstoreError::DigestUnsupportedAlgorithmError(
+                        algorithm.as_str_name().to_owned(),
+                    ),
+                ))?;
+                verifier
+                    .verify_digest_bytes(
+                        digest_algorithm,
+                        &message_digest.digest,
+                        bundle,
+                        &SessionIdentity,
+                        true,
+                    )
+                    .await
+                    .map(|_| ())
+            }
+            // Envelopes are the only other content signed by sessions.
+            _ => verifier
+                .verify_dsse(bundle, &SessionIdentity, true)
+                .await
+                .map(|_| ()),
+        };
+
+        result.map_err(|err| SigstoreError::SelfVerificationError(Box::new(err)))
+    }
+
     /// Consumes the signing artifact and produces a Sigstore [`Bundle`].
     ///
     /// The resulting bundle can be serialized with [`serde_json`].
+    ///
+    /// Bundles are produced in the v0.2 format. To select another version, use
+    /// [`SigningArtifact::to_bundle_with_version`].
     pub fn to_bundle(self) -> Bundle {
+        self.to_bundle_with_version(Version::Bundle0_2)
+    }
+
+    /// Consumes the signing artifact and produces a Sigstore [`Bundle`] of the given [`Version`].
+    ///
+    /// The resulting bundle can be serialized with [`serde_json`].
+    pub fn to_bundle_with_version(self, version: Version) -> Bundle {
         // NOTE: We explicitly only include the leaf certificate in the bundle's "chain"
         // here: the specs explicitly forbid the inclusion of the root certificate,
         // and discourage inclusion of any intermediates (since they're in the root of
-        // trust already).
-        let x509_certificate_chain = X509CertificateChain {
-            certificates: vec![X509Certificate {
-                raw_bytes: self.cert,
-            }],
+        // trust already). v0.3 bundles drop the chain altogether.
+        let content = match (self.signer, version) {
+            (SignerMaterial::Certificate(raw_bytes), Version::Bundle0_1 | Version::Bundle0_2) => {
+                verification_material::Content::X509CertificateChain(X509CertificateChain {
+                    certificates: vec![X509Certificate { raw_bytes }],
+                })
+            }
+            (SignerMaterial::Certificate(raw_bytes), Version::Bundle0_3) => {
+                verification_material::Content::Certificate(X509Certificate { raw_bytes })
+            }
+            (SignerMaterial::PublicKey(hint), _) => {
+                verification_material::Content::PublicKey(PublicKeyIdentifier { hint })
+            }
         };
 
+        let timestamp_verification_data =
+            (!self.timestamps.is_empty()).then(|| TimestampVerificationData {
+                rfc3161_timestamps: self
+                    .timestamps
+                    .into_iter()
+                    .map(|signed_timestamp| Rfc3161SignedTimestamp { signed_timestamp })
+                    .collect(),
+            });
         let verification_material = Some(VerificationMaterial {
-            timestamp_verification_data: None,
-            tlog_entries: vec![self.log_entry],
-            content: Some(verification_material::Content::X509CertificateChain(
-                x509_certificate_chain,
-            )),
+            timestamp_verification_data,
+            tlog_entries: self.log_entry.into_iter().collect(),
+            content: Some(content),
         });
 
-        let message_signature = MessageSignature {
-            message_digest: Some(HashOutput {
-                algorithm: HashAlgorithm::Sha2256.into(),
-                digest: self.input_digest,
-            }),
-            signature: self.signature,
-        };
         Bundle {
-            media_type: Version::Bundle0_2.to_string(),
+            media_type: version.to_string(),
             verification_material,
-            content: Some(bundle::Content::MessageSignature(message_signature)),
+            content: Some(self.content),
+        }
+    }
+}
+
+/// The signer of a [`SigningArtifact`], as recorded in its bundle.
+#[derive(Clone)]
+enum SignerMaterial {
+    /// A DER-encoded signing certificate.
+    Certificate(Vec<u8>),
+    /// The hint identifying a long-lived key.
+    PublicKey(String),
+}
+
+/// Accepts the session's own signing certificate: self-verification checks the materials returned
+/// by Fulcio and Rekor, not the signer's identity.
+#[cfg(feature = "verify")]
+struct SessionIdentity;
+
+#[cfg(feature = "verify")]
+impl VerificationPolicy for SessionIdentity {
+    fn verify(&self, _cert: &x509_cert::Certificate) -> PolicyResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "verify")]
+mod tests {
+    use super::*;
+    use crate::bundle::verify::models::CheckedBundle;
+    use crate::bundle::verify::verifier::tests::{
+        promised_log_entry, signed_checkpoint, threshold_verifier,
+    };
+    use crate::bundle::verify::VerifierConfig;
+    use crate::crypto::merkle;
+    use crate::crypto::tests::{generate_certificate, CertGenerationOptions};
+    use crate::crypto::timestamp::{tests::TestTsa, TimestampVerifier};
+    use crate::crypto::{CosignVerificationKey, Signature};
+    use crate::trust::ManualTrustRoot;
+    use openssl::pkey::PKey;
+    use p256::ecdsa::SigningKey;
+    use pkcs8::{EncodePrivateKey, EncodePublicKey};
+    use sigstore_protobuf_specs::dev::sigstore::rekor::v1::{Checkpoint, InclusionProof};
+    use webpki::types::CertificateDer;
+
+    /// Returns a context signing with `signer` under the hint "trusted", timestamped by `tsa` and
+    /// without a transparency log.
+    fn trusted_key_context(signer: &SigningKey, tsa: TestTsa) -> SigningContext {
+        let pem = signer.to_pkcs8_pem(pkcs8::LineEnding::LF).unwrap();
+        let key_pair = SigStoreKeyPair::from_pem(pem.as_bytes()).unwrap();
+        SigningContext::from_key_pair(
+            &key_pair,
+            &SigningScheme::ECDSA_P256_SHA256_ASN1,
+            "trusted".into(),
+            Default::default(),
+        )
+        .unwrap()
+        .with_timestamp_authority(tsa)
+        .without_transparency_log()
+    }
+
+    /// Returns the body of the `hashedrekord` entry recording `signature`, made by `signer` over
+    /// `digest`.
+    fn hashedrekord_body(
+        signer: &SigningKey,
+        digest: &[u8],
+        signature: &[u8],
+    ) -> serde_json::Value {
+        json!({
+            "apiVersion": "0.0.1",
+            "kind": "hashedrekord",
+            "spec": {
+                "data": {"hash": {"algorithm": "sha256", "value": hex::encode(digest)}},
+                "signature": {
+                    "content": base64.encode(signature),
+                    "publicKey": {"content": encoded_public_key(signer)},
+                },
+            },
+        })
+    }
+
+    /// Returns `signer`'s public key as recorded in log entries: base64-encoded PEM.
+    fn encoded_public_key(signer: &SigningKey) -> String {
+        let pem = signer
+            .verifying_key()
+            .to_public_key_pem(pkcs8::LineEnding::LF)
+            .unwrap();
+        base64.encode(pem)
+    }
+
+    /// Returns the log entry Rekor would return for `body`: promised by `log_signer`, and with an
+    /// inclusion proof up to a checkpoint it signed.
+    fn rekor_log_entry(body: &serde_json::Value, log_signer: &SigningKey) -> TransparencyLogEntry {
+        let mut entry = promised_log_entry(body, log_signer);
+        let root_hash = merkle::hash_leaf(&entry.canonicalized_body);
+        entry.inclusion_proof = Some(InclusionProof {
+            log_index: entry.log_index,
+            root_hash: root_hash.to_vec(),
+            tree_size: 1,
+            hashes: vec![],
+            checkpoint: Some(Checkpoint {
+                envelope: signed_checkpoint(log_signer, 1, root_hash).to_string(),
+            }),
+        });
+        entry
+    }
+
+    fn signature(artifact: &SigningArtifact) -> &[u8] {
+        let bundle::Content::MessageSignature(MessageSignature { signature, .. }) =
+            &artifact.content
+        else {
+            panic!("expected a message signature");
+        };
+        signature
+    }
+
+    fn timestamp_only() -> VerifierConfig {
+        VerifierConfig {
+            tlog_threshold: 0,
+            timestamp_threshold: 1,
+            ..Default::default()
         }
     }
+
+    #[tokio::test]
+    async fn self_verification_accepts_matching_signature() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let verifier = threshold_verifier(&[], &signer, &tsa, timestamp_only());
+        let context = trusted_key_context(&signer, tsa).with_self_verification(verifier);
+
+        let artifact = context
+            .key_signer()
+            .unwrap()
+            .sign_digest(Sha256::new().chain_update(b"artifact"))
+            .await
+            .unwrap();
+        assert_eq!(artifact.timestamps.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn self_verification_rejects_mismatched_signer() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        // The verifier trusts another key under the session's hint.
+        let other = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = threshold_verifier(&[], &other, &tsa, timestamp_only());
+        let context = trusted_key_context(&signer, tsa).with_self_verification(verifier);
+
+        let result = context
+            .key_signer()
+            .unwrap()
+            .sign_digest(Sha256::new().chain_update(b"artifact"))
+            .await;
+        assert!(matches!(
+            result,
+            Err(SigstoreError::SelfVerificationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn self_verification_checks_log_entry() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let verifier = threshold_verifier(&[&log_signer], &signer, &tsa, Default::default());
+
+        let context = trusted_key_context(&signer, tsa);
+        let mut artifact = context
+            .key_signer()
+            .unwrap()
+            .sign_digest(Sha256::new().chain_update(b"artifact"))
+            .await
+            .unwrap();
+        let digest = Sha256::digest(b"artifact");
+
+        // The entry recording the signature.
+        let body = hashedrekord_body(&signer, &digest, signature(&artifact));
+        let entry = rekor_log_entry(&body, &log_signer);
+        artifact.log_entry = Some(entry.clone());
+        artifact.verify(&verifier).await.unwrap();
+
+        // An entry recording another artifact.
+        let mut tampered = artifact.clone();
+        let body = hashedrekord_body(
+            &signer,
+            &Sha256::digest(b"another artifact"),
+            signature(&artifact),
+        );
+        tampered.log_entry = Some(rekor_log_entry(&body, &log_signer));
+        assert!(matches!(
+            tampered.verify(&verifier).await,
+            Err(SigstoreError::SelfVerificationError(_))
+        ));
+
+        // An entry promised by an untrusted log.
+        let mut untrusted = artifact.clone();
+        let body = hashedrekord_body(&signer, &digest, signature(&artifact));
+        untrusted.log_entry = Some(rekor_log_entry(
+            &body,
+            &SigningKey::random(&mut rand::rngs::OsRng),
+        ));
+        assert!(matches!(
+            untrusted.verify(&verifier).await,
+            Err(SigstoreError::SelfVerificationError(_))
+        ));
+
+        // An entry without an inclusion proof.
+        let mut unproven = artifact.clone();
+        unproven.log_entry = Some(TransparencyLogEntry {
+            inclusion_proof: None,
+            ..entry
+        });
+        assert!(matches!(
+            unproven.verify(&verifier).await,
+            Err(SigstoreError::RekorClientError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sign_dsse_round_trip() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let verifier = threshold_verifier(&[&log_signer], &signer, &tsa, Default::default());
+
+        let context = trusted_key_context(&signer, tsa);
+        let payload = br#"{"_type":"https://in-toto.io/Statement/v1"}"#.to_vec();
+        let mut artifact = context
+            .key_signer()
+            .unwrap()
+            .sign_dsse(IN_TOTO_PAYLOAD_TYPE, payload.clone())
+            .await
+            .unwrap();
+        let bundle::Content::DsseEnvelope(envelope) = &artifact.content else {
+            panic!("expected a DSSE envelope");
+        };
+
+        // The entry Rekor canonicalizes from the proposed envelope.
+        let body = json!({
+            "apiVersion": "0.0.1",
+            "kind": "dsse",
+            "spec": {
+                "envelopeHash": {"algorithm": "sha256", "value": hex::encode([0; 32])},
+                "payloadHash": {"algorithm": "sha256", "value": hex::encode(Sha256::digest(&payload))},
+                "signatures": [{
+                    "signature": base64.encode(&envelope.signatures[0].sig),
+                    "verifier": encoded_public_key(&signer),
+                }],
+            },
+        });
+        artifact.log_entry = Some(rekor_log_entry(&body, &log_signer));
+        let bundle = artifact.to_bundle();
+
+        let public_key = signer.verifying_key().to_public_key_der().unwrap();
+        let checked: CheckedBundle = bundle.clone().try_into().unwrap();
+        assert_eq!(
+            checked
+                .dsse_tlog_entries(public_key.as_bytes())
+                .map(|entries| entries.len()),
+            Some(1)
+        );
+
+        let verified = verifier
+            .verify_dsse(bundle, &SessionIdentity, true)
+            .await
+            .unwrap();
+        assert_eq!(verified.content.payload, payload);
+        assert_eq!(verified.outcome.log_entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn to_bundle_with_timestamps() {
+        let tsa = TestTsa::new();
+        let signature = b"signature".to_vec();
+        let timestamp = TimestampAuthority::timestamp(&tsa, &signature)
+            .await
+            .unwrap();
+
+        // Signed without a transparency log.
+        let artifact = SigningArtifact {
+            signer: SignerMaterial::Certificate(vec![]),
+            content: bundle::Content::MessageSignature(MessageSignature {
+                message_digest: None,
+                signature: signature.clone(),
+            }),
+            log_entry: None,
+            timestamps: vec![timestamp],
+        };
+
+        let material = artifact.to_bundle().verification_material.unwrap();
+        assert!(material.tlog_entries.is_empty());
+
+        let timestamps = material
+            .timestamp_verification_data
+            .unwrap()
+            .rfc3161_timestamps;
+        let [timestamp] = &timestamps[..] else {
+            panic!("expected a single timestamp, got {}", timestamps.len());
+        };
+        let verifier = TimestampVerifier::new(tsa.certificates()).unwrap();
+        assert!(verifier
+            .verify(&timestamp.signed_timestamp, &signature)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn key_signer_without_transparency_log() {
+        let signer = SigningScheme::ECDSA_P256_SHA256_ASN1
+            .create_signer()
+            .unwrap();
+        let context = SigningContext::from_signer(&signer, "build-farm".into(), Default::default())
+            .unwrap()
+            .without_transparency_log();
+        let session = context.key_signer().unwrap();
+        assert!(!session.is_expired());
+
+        let hasher = Sha256::new().chain_update(b"artifact");
+        let digest = hasher.clone().finalize();
+        let bundle = session.sign_digest(hasher).await.unwrap().to_bundle();
+
+        let material = bundle.verification_material.unwrap();
+        assert!(material.tlog_entries.is_empty());
+        assert!(matches!(
+            material.content,
+            Some(verification_material::Content::PublicKey(PublicKeyIdentifier { hint }))
+                if hint == "build-farm"
+        ));
+
+        let Some(bundle::Content::MessageSignature(MessageSignature { signature, .. })) =
+            bundle.content
+        else {
+            panic!("expected a message signature");
+        };
+        assert!(signer
+            .to_verification_key()
+            .unwrap()
+            .verify_prehash(Signature::Raw(&signature), &digest)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn keyless_rsa_signature_verifies() {
+        let private_key = SoftwareSigner::generate(SigningScheme::RSA_PKCS1_SHA256(2048)).unwrap();
+        let ca = generate_certificate(None, CertGenerationOptions::default()).unwrap();
+        let cert = generate_certificate(
+            Some(&ca),
+            CertGenerationOptions {
+                public_key: PKey::public_key_from_der(&private_key.public_key_der().unwrap())
+                    .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let cert_pem = cert.cert.to_pem().unwrap();
+
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let log_spki = log_signer.verifying_key().to_public_key_der().unwrap();
+        let trust_root = ManualTrustRoot::new(
+            vec![CertificateDer::from(ca.cert.to_der().unwrap())],
+            vec![log_spki.as_bytes().to_vec()],
+            vec![],
+        );
+        let verifier = Verifier::new(Default::default(), trust_root)
+            .unwrap()
+            .with_config(VerifierConfig {
+                sct_threshold: 0,
+                ..Default::default()
+            });
+
+        // Artifact digests of any algorithm are signed with that algorithm.
+        for digest_algorithm in [
+            DigestAlgorithm::Sha256,
+            DigestAlgorithm::Sha384,
+            DigestAlgorithm::Sha512,
+        ] {
+            let digest = digest_algorithm.digest(b"artifact");
+            let signature = private_key
+                .sign_digest(digest_algorithm, &digest)
+                .await
+                .unwrap();
+            let body = json!({
+                "apiVersion": "0.0.1",
+                "kind": "hashedrekord",
+                "spec": {
+                    "data": {"hash": {"algorithm": digest_algorithm.name(), "value": hex::encode(&digest)}},
+                    "signature": {
+                        "content": base64.encode(&signature),
+                        "publicKey": {"content": base64.encode(&cert_pem)},
+                    },
+                },
+            });
+            let artifact = SigningArtifact {
+                signer: SignerMaterial::Certificate(cert.cert.to_der().unwrap()),
+                content: bundle::Content::MessageSignature(MessageSignature {
+                    message_digest: Some(HashOutput {
+                        algorithm: HashAlgorithm::from(digest_algorithm).into(),
+                        digest,
+                    }),
+                    signature,
+                }),
+                log_entry: Some(rekor_log_entry(&body, &log_signer)),
+                timestamps: vec![],
+            };
+
+            artifact.verify(&verifier).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn key_signer_requires_key() {
+        let context = SigningContext::new(
+            FulcioClient::new(
+                Url::parse(FULCIO_ROOT).unwrap(),
+                crate::fulcio::TokenProvider::Oauth(OauthTokenProvider::default()),
+            ),
+            Default::default(),
+            Keyring::new(std::iter::empty()).unwrap(),
+        );
+
+        assert!(matches!(
+            context.key_signer(),
+            Err(SigstoreError::SigningContextMismatchError(_))
+        ));
+    }
+
+    #[test]
+    fn keyless_context_rejects_unsupported_schemes() {
+        let context = || {
+            SigningContext::new(
+                FulcioClient::new(
+                    Url::parse(FULCIO_ROOT).unwrap(),
+                    crate::fulcio::TokenProvider::Oauth(OauthTokenProvider::default()),
+                ),
+                Default::default(),
+                Keyring::new(std::iter::empty()).unwrap(),
+            )
+        };
+
+        for scheme in [
+            SigningScheme::RSA_PKCS1_SHA384(2048),
+            SigningScheme::RSA_PSS_SHA256(2048),
+        ] {
+            assert!(matches!(
+                context().with_signing_scheme(scheme),
+                Err(SigstoreError::SigningSchemeUnsupportedError(_))
+            ));
+        }
+        for scheme in [
+            SigningScheme::ECDSA_P384_SHA384_ASN1,
+            SigningScheme::ED25519,
+            SigningScheme::RSA_PKCS1_SHA256(2048),
+        ] {
+            assert!(context().with_signing_scheme(scheme).is_ok());
+        }
+    }
+
+    #[test]
+    fn key_context_rejects_rsa_pss() {
+        let key_pair = SigningScheme::RSA_PSS_SHA256(2048)
+            .create_signer()
+            .unwrap()
+            .to_sigstore_keypair()
+            .unwrap();
+
+        assert!(matches!(
+            SigningContext::from_key_pair(
+                &key_pair,
+                &SigningScheme::RSA_PSS_SHA256(2048),
+                "build-farm".into(),
+                Default::default(),
+            ),
+            Err(SigstoreError::SigningSchemeUnsupportedError(_))
+        ));
+        assert!(SigningContext::from_key_pair(
+            &key_pair,
+            &SigningScheme::RSA_PKCS1_SHA256(2048),
+            "build-farm".into(),
+            Default::default(),
+        )
+        .and_then(|context| context.key_signer().map(|_| ()))
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn key_signer_with_async_signer() {
+        let signer = SoftwareSigner::generate(SigningScheme::ED25519).unwrap();
+        let public_key = signer.public_key_der().unwrap();
+        let context =
+            SigningContext::from_async_signer(signer, "kms-key".into(), Default::default())
+                .await
+                .unwrap()
+                .without_transparency_log();
+
+        let artifact = context
+            .key_signer()
+            .unwrap()
+            .sign_dsse("text/plain", b"payload".to_vec())
+            .await
+            .unwrap();
+        let Some(bundle::Content::DsseEnvelope(envelope)) = artifact.to_bundle().content else {
+            panic!("expected a DSSE envelope");
+        };
+
+        let key = CosignVerificationKey::from_der(&public_key, &SigningScheme::ED25519).unwrap();
+        assert!(key
+            .verify_signature(
+                Signature::Raw(&envelope.signatures[0].sig),
+                &dsse::pae("text/plain", b"payload"),
+            )
+            .is_ok());
+
+        // Ed25519 keys cannot sign for artifact digests.
+        assert!(matches!(
+            context.key_signer().unwrap().sign(&b"artifact"[..]).await,
+            Err(SigstoreError::SigningSchemeUnsupportedError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn sign_hashes_with_key_digest_algorithm() {
+        let signer = SigningScheme::ECDSA_P384_SHA384_ASN1
+            .create_signer()
+            .unwrap();
+        let context = SigningContext::from_signer(&signer, "build-farm".into(), Default::default())
+            .unwrap()
+            .without_transparency_log();
+
+        let artifact = context
+            .key_signer()
+            .unwrap()
+            .sign(&b"artifact"[..])
+            .await
+            .unwrap();
+        let bundle::Content::MessageSignature(MessageSignature {
+            message_digest: Some(message_digest),
+            ..
+        }) = &artifact.content
+        else {
+            panic!("expected a message signature with a digest");
+        };
+        assert_eq!(message_digest.algorithm(), HashAlgorithm::Sha2384);
+        assert_eq!(message_digest.digest, Sha384::digest(b"artifact").to_vec());
+    }
 }
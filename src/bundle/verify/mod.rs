@@ -15,12 +15,21 @@
 
 //! Types for verifying Sigstore bundles with policies.
 
-mod models;
+pub(crate) mod models;
 
-pub use models::{VerificationError, VerificationResult};
+pub use models::{
+    CertificateIdentity, SignerIdentity, SigningTime, SigningTimeSource, VerificationError,
+    VerificationOutcome, VerificationResult, Verified, VerifiedLogEntry,
+};
 
 pub mod policy;
 pub use policy::{PolicyError, VerificationPolicy};
 
-mod verifier;
+pub mod policy_document;
+pub use policy_document::PolicyDocument;
+
+pub mod provenance_policy;
+pub use provenance_policy::ProvenancePolicy;
+
+pub(crate) mod verifier;
 pub use verifier::*;
@@ -13,27 +13,37 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 
 use crate::{
-    bundle::{models::Version as BundleVersion, Bundle},
-    crypto::certificate::{is_leaf, is_root_ca, CertificateValidationError},
+    bundle::{
+        attestation::AttestationError, dsse::Envelope, models::Version as BundleVersion, Bundle,
+        DigestAlgorithm,
+    },
+    crypto::{
+        certificate::{is_leaf, is_root_ca, CertificateValidationError},
+        keyring::KeyringError,
+        merkle::MerkleProofError,
+        timestamp::TimestampError,
+        FulcioCertificateExtensions,
+    },
     rekor::models as rekor,
 };
 
 use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use sigstore_protobuf_specs::dev::sigstore::{
     bundle::v1::{bundle, verification_material},
+    common::v1::{MessageSignature, X509Certificate},
     rekor::v1::{InclusionProof, TransparencyLogEntry},
 };
 use thiserror::Error;
-use tracing::{debug, error, warn};
-use x509_cert::{
-    der::{Decode, EncodePem},
-    Certificate,
-};
+use tracing::{debug, error};
+use x509_cert::{der::Decode, Certificate};
 
-use super::policy::PolicyError;
+use super::policy::{san_identities, PolicyError};
 
 #[derive(Error, Debug)]
 pub enum Bundle01ProfileErrorKind {
@@ -50,6 +60,12 @@ pub enum Bundle02ProfileErrorKind {
     CheckpointMissing,
 }
 
+#[derive(Error, Debug)]
+pub enum Bundle03ProfileErrorKind {
+    #[error("bundle must contain a single certificate, not a certificate chain")]
+    CertificateChainUnsupported,
+}
+
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub enum BundleProfileErrorKind {
@@ -57,6 +73,8 @@ pub enum BundleProfileErrorKind {
 
     Bundle02Profile(#[from] Bundle02ProfileErrorKind),
 
+    Bundle03Profile(#[from] Bundle03ProfileErrorKind),
+
     #[error("unknown bundle profile {0}")]
     Unknown(String),
 }
@@ -84,11 +102,14 @@ pub enum BundleErrorKind {
     #[error("bundle does not contain signature")]
     SignatureMissing,
 
-    #[error("bundle includes unsupported DSSE signature")]
-    DsseUnsupported,
+    #[error("bundle contains a DSSE envelope, not a message signature")]
+    DsseUnexpected,
 
-    #[error("bundle needs 1 tlog entry, got {0}")]
-    TlogEntry(usize),
+    #[error("bundle contains a message signature, not a DSSE envelope")]
+    DsseMissing,
+
+    #[error("bundle's DSSE envelope needs 1 signature, got {0}")]
+    DsseSignatureCount(usize),
 
     #[error(transparent)]
     BundleProfile(#[from] BundleProfileErrorKind),
@@ -119,6 +140,45 @@ pub enum SignatureErrorKind {
 
     #[error("signature transparency materials are inconsistent")]
     Transparency,
+
+    #[error("bundle's public key {0} is not trusted")]
+    KeyUntrusted(String),
+
+    #[error("unsupported digest algorithm {0}")]
+    DigestAlgorithmUnsupported(String),
+
+    #[error("bundle's message digest does not match the input digest")]
+    DigestMismatch,
+
+    #[error("input digest is not a valid {0} digest")]
+    DigestMalformed(DigestAlgorithm),
+}
+
+#[derive(Error, Debug)]
+pub enum TransparencyErrorKind {
+    #[error("transparency log entry is malformed")]
+    EntryMalformed,
+
+    #[error("inclusion proof verification failed")]
+    InclusionProof(#[source] MerkleProofError),
+
+    #[error("signed entry timestamp verification failed")]
+    SignedEntryTimestamp(#[source] KeyringError),
+
+    #[error("checkpoint is invalid")]
+    Checkpoint(#[from] rekor::checkpoint::CheckpointError),
+
+    #[error("checkpoint signature verification failed")]
+    CheckpointSignature(#[source] KeyringError),
+
+    #[error("log's current checkpoint is inconsistent with the inclusion proof's")]
+    Consistency(#[source] MerkleProofError),
+
+    #[error("transparency log entry not found in Rekor")]
+    EntryNotFound,
+
+    #[error("Rekor request failed: {0}")]
+    Rekor(String),
 }
 
 #[derive(Error, Debug)]
@@ -133,81 +193,267 @@ pub enum VerificationError {
 
     Signature(#[from] SignatureErrorKind),
 
+    Transparency(#[from] TransparencyErrorKind),
+
+    Timestamp(#[from] TimestampError),
+
+    Attestation(#[from] AttestationError),
+
     Policy(#[from] PolicyError),
+
+    ProvenancePolicy(PolicyError),
+
+    #[error("verified {actual} {evidence}, {required} required")]
+    InsufficientEvidence {
+        evidence: &'static str,
+        required: usize,
+        actual: usize,
+    },
+}
+
+pub type VerificationResult = Result<VerificationOutcome, VerificationError>;
+
+/// Evidence counted by [`check_threshold`].
+pub(crate) const DISTINCT_LOGS: &str = "transparency log entries from distinct logs";
+pub(crate) const SIGNED_TIMESTAMPS: &str = "signed timestamps";
+pub(crate) const SIGNING_TIMES: &str = "signing times";
+
+/// Checks that `actual` pieces of verified `evidence` meet the `required` threshold.
+pub(crate) fn check_threshold(
+    evidence: &'static str,
+    required: usize,
+    actual: usize,
+) -> Result<(), VerificationError> {
+    if actual < required {
+        return Err(VerificationError::InsufficientEvidence {
+            evidence,
+            required,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/// Counts the distinct transparency logs that recorded `entries`: several entries in a single log
+/// are no more evidence than one.
+pub(crate) fn distinct_logs(entries: &[VerifiedLogEntry]) -> usize {
+    entries
+        .iter()
+        .map(|entry| &entry.log_id)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// The outcome of a successful verification: who signed, when, and on what grounds.
+#[derive(Clone, Debug)]
+pub struct VerificationOutcome {
+    /// The verified signer.
+    pub signer: SignerIdentity,
+    /// The trusted times at which the signature is known to have existed, each of which falls
+    /// within the signing certificate's validity period.
+    pub signing_times: Vec<SigningTime>,
+    /// The verified transparency log entries that recorded the signature.
+    pub log_entries: Vec<VerifiedLogEntry>,
+}
+
+/// Content verified from a bundle, such as a DSSE envelope's payload or an attestation decoded
+/// from it, together with the outcome of its verification.
+#[derive(Clone, Debug)]
+pub struct Verified<T> {
+    /// Who signed the content, when, and on what grounds.
+    pub outcome: VerificationOutcome,
+    /// The verified content.
+    pub content: T,
+}
+
+/// A transparency log entry that recorded a signature, once verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedLogEntry {
+    /// The index of the entry in the transparency log.
+    pub log_index: i64,
+    /// The ID of the transparency log: the SHA-256 digest of its public key.
+    pub log_id: Vec<u8>,
+}
+
+/// A verified signer.
+#[derive(Clone, Debug)]
+pub enum SignerIdentity {
+    /// A signer identified by a certificate that chains to a trusted certificate authority.
+    Certificate(Box<CertificateIdentity>),
+    /// A signer identified by one of the verifier's trusted public keys.
+    PublicKey {
+        /// The hint under which the key is trusted.
+        hint: String,
+    },
+}
+
+/// The identity of a signer, as recorded in its verified signing certificate.
+#[derive(Clone, Debug)]
+pub struct CertificateIdentity {
+    /// The verified signing (leaf) certificate.
+    pub certificate: Certificate,
+    /// The identity in the certificate's SubjectAltName extension.
+    pub identity: Option<String>,
+    /// The certificate's Fulcio extensions, such as the OIDC issuer that authenticated the
+    /// identity. Repeated or malformed extensions are left unset.
+    pub extensions: FulcioCertificateExtensions,
+    /// The ID of the issuing certificate authority: the SHA-256 digest of its public key.
+    pub ca_key_id: [u8; 32],
+    /// The IDs of the certificate transparency logs whose SCTs for the certificate were
+    /// verified: the SHA-256 digests of their public keys.
+    pub ct_log_ids: Vec<[u8; 32]>,
+}
+
+impl CertificateIdentity {
+    pub(crate) fn new(
+        certificate: Certificate,
+        ca_key_id: [u8; 32],
+        ct_log_ids: Vec<[u8; 32]>,
+    ) -> Self {
+        let identity = san_identities(&certificate).and_then(|names| names.into_iter().next());
+
+        let extensions = FulcioCertificateExtensions::from_certificate_lossy(&certificate);
+
+        Self {
+            certificate,
+            identity,
+            extensions,
+            ca_key_id,
+            ct_log_ids,
+        }
+    }
+}
+
+/// A trusted time at which a signature is known to have existed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningTime {
+    pub time: DateTime<Utc>,
+    pub source: SigningTimeSource,
+}
+
+/// The source of a [`SigningTime`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SigningTimeSource {
+    /// The time at which the signature was integrated into the transparency log.
+    TransparencyLog,
+    /// The time attested by an RFC 3161 signed timestamp.
+    TimestampAuthority,
+}
+
+/// The signed content of a bundle.
+pub(crate) enum SignedContent {
+    /// A signature over an artifact's digest, and the digest if the bundle records it.
+    MessageSignature(MessageSignature),
+    /// A DSSE envelope carrying exactly one signature.
+    DsseEnvelope(Envelope),
 }
 
-pub type VerificationResult = Result<(), VerificationError>;
+impl SignedContent {
+    /// The signature bytes, as covered by RFC 3161 signed timestamps.
+    pub(crate) fn signature(&self) -> &[u8] {
+        match self {
+            SignedContent::MessageSignature(signature) => &signature.signature,
+            SignedContent::DsseEnvelope(envelope) => &envelope.signatures[0].sig,
+        }
+    }
+}
+
+/// The material identifying a bundle's signer.
+pub(crate) enum SignerMaterial {
+    /// A signing certificate, which must chain to a trusted certificate authority.
+    Certificate(Box<Certificate>),
+    /// A hint identifying a long-lived public key, which the verifier must already trust.
+    PublicKey(String),
+}
 
 pub struct CheckedBundle {
-    pub(crate) certificate: Certificate,
-    pub(crate) signature: Vec<u8>,
+    pub(crate) signer: SignerMaterial,
+    pub(crate) content: SignedContent,
+    /// DER-encoded RFC 3161 signed timestamps over the signature.
+    pub(crate) signed_timestamps: Vec<Vec<u8>>,
 
-    tlog_entry: TransparencyLogEntry,
+    tlog_entries: Vec<TransparencyLogEntry>,
+}
+
+/// Parses a bundle's certificates and returns the leaf. The first entry in the chain MUST be a
+/// leaf certificate, and the rest of the chain MUST NOT include a root CA or any intermediate CAs
+/// that appear in an independent root of trust.
+fn leaf_certificate(certs: &[X509Certificate]) -> Result<Certificate, BundleErrorKind> {
+    let certs = certs
+        .iter()
+        .map(|c| c.raw_bytes.as_slice())
+        .map(Certificate::from_der)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(BundleErrorKind::CertificateMalformed)?;
+
+    let [leaf_cert, chain_certs @ ..] = &certs[..] else {
+        return Err(BundleErrorKind::CertificatesMissing);
+    };
+
+    is_leaf(leaf_cert).map_err(BundleErrorKind::NoLeaf)?;
+
+    for chain_cert in chain_certs {
+        if is_root_ca(chain_cert).is_ok() {
+            return Err(BundleErrorKind::RootInChain);
+        }
+    }
+
+    Ok(leaf_cert.clone())
 }
 
 impl TryFrom<Bundle> for CheckedBundle {
     type Error = BundleErrorKind;
 
     fn try_from(input: Bundle) -> Result<Self, Self::Error> {
-        let (content, mut tlog_entries) = match input.verification_material {
-            Some(m) => (m.content, m.tlog_entries),
+        let (content, tlog_entries, timestamp_data) = match input.verification_material {
+            Some(m) => (m.content, m.tlog_entries, m.timestamp_verification_data),
             _ => return Err(BundleErrorKind::VerificationMaterialMissing),
         };
 
-        // Parse the certificates. The first entry in the chain MUST be a leaf certificate, and the
-        // rest of the chain MUST NOT include a root CA or any intermediate CAs that appear in an
-        // independent root of trust.
-        let certs = match content {
-            Some(verification_material::Content::X509CertificateChain(ch)) => ch.certificates,
+        let is_chain = matches!(
+            content,
+            Some(verification_material::Content::X509CertificateChain(_))
+        );
+        let signer = match content {
+            Some(verification_material::Content::X509CertificateChain(ch)) => {
+                SignerMaterial::Certificate(Box::new(leaf_certificate(&ch.certificates)?))
+            }
             Some(verification_material::Content::Certificate(cert)) => {
-                vec![cert]
+                SignerMaterial::Certificate(Box::new(leaf_certificate(&[cert])?))
             }
-            _ => return Err(BundleErrorKind::VerificationMaterialContentUnsupported),
-        };
-        let certs = certs
-            .iter()
-            .map(|c| c.raw_bytes.as_slice())
-            .map(Certificate::from_der)
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(BundleErrorKind::CertificateMalformed)?;
-
-        let [leaf_cert, chain_certs @ ..] = &certs[..] else {
-            return Err(BundleErrorKind::CertificatesMissing);
+            Some(verification_material::Content::PublicKey(key)) => {
+                SignerMaterial::PublicKey(key.hint)
+            }
+            None => return Err(BundleErrorKind::VerificationMaterialContentUnsupported),
         };
 
-        is_leaf(leaf_cert).map_err(BundleErrorKind::NoLeaf)?;
-
-        for chain_cert in chain_certs {
-            if is_root_ca(chain_cert).is_ok() {
-                return Err(BundleErrorKind::RootInChain);
+        let content = match input.content.ok_or(BundleErrorKind::SignatureMissing)? {
+            bundle::Content::MessageSignature(s) => SignedContent::MessageSignature(s),
+            bundle::Content::DsseEnvelope(envelope) => {
+                if envelope.signatures.len() != 1 {
+                    return Err(BundleErrorKind::DsseSignatureCount(
+                        envelope.signatures.len(),
+                    ));
+                }
+                SignedContent::DsseEnvelope(envelope)
             }
-        }
-
-        let signature = match input.content.ok_or(BundleErrorKind::SignatureMissing)? {
-            bundle::Content::MessageSignature(s) => s.signature,
-            _ => return Err(BundleErrorKind::DsseUnsupported),
         };
 
-        if tlog_entries.len() != 1 {
-            return Err(BundleErrorKind::TlogEntry(tlog_entries.len()));
-        }
-        let tlog_entry = tlog_entries.remove(0);
-
-        let (inclusion_promise, inclusion_proof) =
-            (&tlog_entry.inclusion_promise, &tlog_entry.inclusion_proof);
-
+        // How many entries are needed is up to the verifier's configuration.
+        //
         // `inclusion_proof` is a required field in the current protobuf spec,
         // but older versions of Rekor didn't provide it. Check invariants
         // here and selectively allow for this case.
         //
         // https://github.com/sigstore/sigstore-python/pull/634#discussion_r1182769140
-        let check_01_bundle = || -> Result<(), BundleProfileErrorKind> {
-            if inclusion_promise.is_none() {
+        let check_01_bundle = |entry: &TransparencyLogEntry| -> Result<(), BundleProfileErrorKind> {
+            if entry.inclusion_promise.is_none() {
                 return Err(Bundle01ProfileErrorKind::InclusionPromiseMissing)?;
             }
 
             if matches!(
-                inclusion_proof,
+                entry.inclusion_proof,
                 Some(InclusionProof {
                     checkpoint: None,
                     ..
@@ -218,14 +464,14 @@ impl TryFrom<Bundle> for CheckedBundle {
 
             Ok(())
         };
-        let check_02_bundle = || -> Result<(), BundleProfileErrorKind> {
-            if inclusion_proof.is_none() {
+        let check_02_bundle = |entry: &TransparencyLogEntry| -> Result<(), BundleProfileErrorKind> {
+            if entry.inclusion_proof.is_none() {
                 error!("bundle must contain inclusion proof");
                 return Err(Bundle02ProfileErrorKind::InclusionProofMissing)?;
             }
 
             if matches!(
-                inclusion_proof,
+                entry.inclusion_proof,
                 Some(InclusionProof {
                     checkpoint: None,
                     ..
@@ -237,58 +483,495 @@ impl TryFrom<Bundle> for CheckedBundle {
 
             Ok(())
         };
-        match BundleVersion::from_str(&input.media_type) {
-            Ok(BundleVersion::Bundle0_1) => check_01_bundle()?,
-            Ok(BundleVersion::Bundle0_2) => check_02_bundle()?,
+        // v0.3 bundles have the same transparency requirements as v0.2 bundles, but replace the
+        // certificate chain with the leaf certificate alone.
+        let check_03_bundle = || -> Result<(), BundleProfileErrorKind> {
+            if is_chain {
+                error!("bundle must contain a single certificate");
+                return Err(Bundle03ProfileErrorKind::CertificateChainUnsupported)?;
+            }
+
+            Ok(())
+        };
+        let check_entry = match BundleVersion::from_str(&input.media_type) {
+            Ok(BundleVersion::Bundle0_1) => check_01_bundle,
+            Ok(BundleVersion::Bundle0_2) => check_02_bundle,
+            Ok(BundleVersion::Bundle0_3) => {
+                check_03_bundle()?;
+                check_02_bundle
+            }
             Err(_) => return Err(BundleProfileErrorKind::Unknown(input.media_type))?,
+        };
+        for entry in &tlog_entries {
+            check_entry(entry)?;
         }
 
+        let signed_timestamps = timestamp_data
+            .map(|data| data.rfc3161_timestamps)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|timestamp| timestamp.signed_timestamp)
+            .collect();
+
         Ok(Self {
-            certificate: leaf_cert.clone(),
-            signature,
-            tlog_entry,
+            signer,
+            content,
+            signed_timestamps,
+            tlog_entries,
         })
     }
 }
 
 impl CheckedBundle {
-    /// Retrieves and checks consistency of the bundle's [TransparencyLogEntry].
-    pub fn tlog_entry(&self, offline: bool, input_digest: &[u8]) -> Option<&TransparencyLogEntry> {
-        let base64_pem_certificate =
-            base64.encode(self.certificate.to_pem(pkcs8::LineEnding::LF).ok()?);
+    /// Retrieves and checks consistency of the bundle's [TransparencyLogEntry]s against a message
+    /// signature over `input_digest`, computed with `digest_algorithm`.
+    ///
+    /// `signer` is the DER encoding of the signing certificate or public key, which each entry
+    /// must record. Returns `None` if any entry is inconsistent.
+    pub(crate) fn tlog_entries(
+        &self,
+        digest_algorithm: DigestAlgorithm,
+        input_digest: &[u8],
+        signer: &[u8],
+    ) -> Option<Vec<&TransparencyLogEntry>> {
+        let SignedContent::MessageSignature(signature) = &self.content else {
+            return None;
+        };
 
+        // The signer is compared separately: its PEM encoding is not canonical.
         let expected_entry = rekor::Hashedrekord {
             kind: "hashedrekord".to_owned(),
             api_version: "0.0.1".to_owned(),
             spec: rekor::hashedrekord::Spec {
                 signature: rekor::hashedrekord::Signature {
-                    content: base64.encode(&self.signature),
-                    public_key: rekor::hashedrekord::PublicKey::new(base64_pem_certificate),
+                    content: base64.encode(&signature.signature),
+                    public_key: rekor::hashedrekord::PublicKey::new(String::new()),
                 },
                 data: rekor::hashedrekord::Data {
                     hash: rekor::hashedrekord::Hash {
-                        algorithm: rekor::hashedrekord::AlgorithmKind::sha256,
+                        algorithm: digest_algorithm.rekor_kind(),
                         value: hex::encode(input_digest),
                     },
                 },
             },
         };
 
-        let entry = if !offline && self.tlog_entry.inclusion_proof.is_none() {
-            warn!("online rekor fetching is not implemented yet, but is necessary for this bundle");
+        let mut expected: serde_json::Value = serde_json::to_value(expected_entry).ok()?;
+        expected["spec"]["signature"]["publicKey"]["content"].take();
+
+        self.tlog_entries
+            .iter()
+            .map(|entry| {
+                let mut actual: serde_json::Value =
+                    serde_json::from_slice(&entry.canonicalized_body).ok()?;
+                let actual_signer = actual["spec"]["signature"]["publicKey"]["content"].take();
+
+                (actual == expected && encodes_signer(&actual_signer, signer)).then_some(entry)
+            })
+            .collect()
+    }
+
+    /// Retrieves and checks consistency of the bundle's [TransparencyLogEntry]s against its DSSE
+    /// envelope.
+    ///
+    /// Both `dsse` (v0.0.1) and `intoto` (v0.0.2) entries are supported. The hash of the whole
+    /// envelope recorded in these entries is not checked, as it depends on the envelope's
+    /// serialization at upload time; the payload hash and signatures are.
+    ///
+    /// `signer` is the DER encoding of the signing certificate or public key, which each entry
+    /// must record. Returns `None` if any entry is inconsistent.
+    pub fn dsse_tlog_entries(&self, signer: &[u8]) -> Option<Vec<&TransparencyLogEntry>> {
+        let SignedContent::DsseEnvelope(envelope) = &self.content else {
+            return None;
+        };
+        let [signature] = &envelope.signatures[..] else {
             return None;
+        };
+        let payload_hash = json!({
+            "algorithm": "sha256",
+            "value": hex::encode(Sha256::digest(&envelope.payload)),
+        });
+
+        self.tlog_entries
+            .iter()
+            .map(|entry| {
+                let actual: serde_json::Value =
+                    serde_json::from_slice(&entry.canonicalized_body).ok()?;
+                let consistent = match (actual["kind"].as_str()?, actual["apiVersion"].as_str()?) {
+                    ("dsse", "0.0.1") => {
+                        let spec = &actual["spec"];
+                        let [entry_signature] = spec["signatures"].as_array()?.as_slice() else {
+                            return None;
+                        };
+
+                        spec["payloadHash"] == payload_hash
+                            && entry_signature["signature"] == base64.encode(&signature.sig)
+                            && encodes_signer(&entry_signature["verifier"], signer)
+                    }
+                    ("intoto", "0.0.2") => {
+                        let content = &actual["spec"]["content"];
+                        let [entry_signature] =
+                            content["envelope"]["signatures"].as_array()?.as_slice()
+                        else {
+                            return None;
+                        };
+
+                        // Rekor stores the envelope's signatures as base64 strings, which are then
+                        // base64-encoded again when the entry is serialized.
+                        content["payloadHash"] == payload_hash
+                            && content["envelope"]["payloadType"] == envelope.payload_type
+                            && entry_signature["sig"]
+                                == base64.encode(base64.encode(&signature.sig))
+                            && encodes_signer(&entry_signature["publicKey"], signer)
+                    }
+                    _ => false,
+                };
+
+                consistent.then_some(entry)
+            })
+            .collect()
+    }
+}
+
+/// Checks whether a log entry's base64-encoded PEM value encodes `signer`, the DER encoding of a
+/// signing certificate or public key.
+fn encodes_signer(value: &serde_json::Value, signer: &[u8]) -> bool {
+    value
+        .as_str()
+        .and_then(|value| base64.decode(value).ok())
+        .and_then(|pem| pem::parse(pem).ok())
+        .is_some_and(|pem| pem.contents() == signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::dsse::{EnvelopeSignature, IN_TOTO_PAYLOAD_TYPE};
+    use crate::crypto::tests::{generate_certificate, CertGenerationOptions};
+    use sigstore_protobuf_specs::dev::sigstore::{
+        bundle::v1::VerificationMaterial,
+        common::v1::{MessageSignature, X509Certificate, X509CertificateChain},
+        rekor::v1::Checkpoint,
+    };
+    use x509_cert::der::{DecodePem, Encode, EncodePem};
+
+    const PAYLOAD: &[u8] = br#"{"_type":"https://in-toto.io/Statement/v1"}"#;
+    const SIGNATURE: &[u8] = b"not really a signature";
+
+    fn certificate() -> Certificate {
+        let ca_data = generate_certificate(None, CertGenerationOptions::default()).unwrap();
+        let cert = generate_certificate(Some(&ca_data), CertGenerationOptions::default())
+            .unwrap()
+            .cert;
+        Certificate::from_der(&cert.to_der().unwrap()).unwrap()
+    }
+
+    fn checked_bundle(certificate: &Certificate, body: serde_json::Value) -> CheckedBundle {
+        CheckedBundle {
+            signer: SignerMaterial::Certificate(Box::new(certificate.clone())),
+            content: SignedContent::DsseEnvelope(Envelope {
+                payload: PAYLOAD.to_vec(),
+                payload_type: IN_TOTO_PAYLOAD_TYPE.to_owned(),
+                signatures: vec![EnvelopeSignature {
+                    sig: SIGNATURE.to_vec(),
+                    keyid: String::new(),
+                }],
+            }),
+            signed_timestamps: vec![],
+            tlog_entries: vec![TransparencyLogEntry {
+                canonicalized_body: serde_json::to_vec(&body).unwrap(),
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn base64_pem(certificate: &Certificate) -> String {
+        base64.encode(certificate.to_pem(pkcs8::LineEnding::LF).unwrap())
+    }
+
+    fn payload_hash() -> String {
+        hex::encode(Sha256::digest(PAYLOAD))
+    }
+
+    fn dsse_body(certificate: &Certificate) -> serde_json::Value {
+        json!({
+            "apiVersion": "0.0.1",
+            "kind": "dsse",
+            "spec": {
+                "envelopeHash": {"algorithm": "sha256", "value": "00"},
+                "payloadHash": {"algorithm": "sha256", "value": payload_hash()},
+                "signatures": [{
+                    "signature": base64.encode(SIGNATURE),
+                    "verifier": base64_pem(certificate),
+                }],
+            },
+        })
+    }
+
+    fn intoto_body(certificate: &Certificate) -> serde_json::Value {
+        json!({
+            "apiVersion": "0.0.2",
+            "kind": "intoto",
+            "spec": {
+                "content": {
+                    "envelope": {
+                        "payloadType": IN_TOTO_PAYLOAD_TYPE,
+                        "signatures": [{
+                            "publicKey": base64_pem(certificate),
+                            "sig": base64.encode(base64.encode(SIGNATURE)),
+                        }],
+                    },
+                    "hash": {"algorithm": "sha256", "value": "00"},
+                    "payloadHash": {"algorithm": "sha256", "value": payload_hash()},
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn dsse_tlog_entry_consistent() {
+        let certificate = certificate();
+
+        for body in [dsse_body(&certificate), intoto_body(&certificate)] {
+            let bundle = checked_bundle(&certificate, body);
+            let signer = certificate.to_der().unwrap();
+            assert!(bundle.dsse_tlog_entries(&signer).is_some());
+            assert!(bundle
+                .tlog_entries(DigestAlgorithm::Sha256, &[0; 32], &signer)
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn dsse_tlog_entry_inconsistent() {
+        let certificate = certificate();
+        let other_certificate = self::certificate();
+
+        let mut wrong_payload = dsse_body(&certificate);
+        wrong_payload["spec"]["payloadHash"]["value"] = json!(hex::encode([0; 32]));
+
+        let mut wrong_signature = dsse_body(&certificate);
+        wrong_signature["spec"]["signatures"][0]["signature"] = json!(base64.encode(b"other"));
+
+        let mut extra_signature = dsse_body(&certificate);
+        extra_signature["spec"]["signatures"] = json!([
+            extra_signature["spec"]["signatures"][0],
+            extra_signature["spec"]["signatures"][0],
+        ]);
+
+        let mut wrong_payload_type = intoto_body(&certificate);
+        wrong_payload_type["spec"]["content"]["envelope"]["payloadType"] = json!("text/plain");
+
+        let mut single_encoded_sig = intoto_body(&certificate);
+        single_encoded_sig["spec"]["content"]["envelope"]["signatures"][0]["sig"] =
+            json!(base64.encode(SIGNATURE));
+
+        let mut unknown_version = dsse_body(&certificate);
+        unknown_version["apiVersion"] = json!("0.0.2");
+
+        for body in [
+            wrong_payload,
+            wrong_signature,
+            extra_signature,
+            wrong_payload_type,
+            single_encoded_sig,
+            unknown_version,
+            dsse_body(&other_certificate),
+            intoto_body(&other_certificate),
+        ] {
+            let bundle = checked_bundle(&certificate, body.clone());
+            assert!(
+                bundle
+                    .dsse_tlog_entries(&certificate.to_der().unwrap())
+                    .is_none(),
+                "{body} should not be consistent"
+            );
+        }
+    }
+
+    #[test]
+    fn dsse_tlog_entries_all_consistent() {
+        let certificate = certificate();
+        let signer = certificate.to_der().unwrap();
+
+        let mut bundle = checked_bundle(&certificate, dsse_body(&certificate));
+        bundle.tlog_entries.push(TransparencyLogEntry {
+            canonicalized_body: serde_json::to_vec(&intoto_body(&certificate)).unwrap(),
+            ..Default::default()
+        });
+        assert_eq!(bundle.dsse_tlog_entries(&signer).map(|e| e.len()), Some(2));
+
+        // An entry for another signature spoils the others.
+        let other_certificate = self::certificate();
+        bundle.tlog_entries.push(TransparencyLogEntry {
+            canonicalized_body: serde_json::to_vec(&dsse_body(&other_certificate)).unwrap(),
+            ..Default::default()
+        });
+        assert!(bundle.dsse_tlog_entries(&signer).is_none());
+
+        bundle.tlog_entries.clear();
+        assert_eq!(bundle.dsse_tlog_entries(&signer).map(|e| e.len()), Some(0));
+    }
+
+    fn bundle(
+        version: &str,
+        certificate: &Certificate,
+        chain: bool,
+        inclusion_proof: bool,
+    ) -> Bundle {
+        let certificate = X509Certificate {
+            raw_bytes: certificate.to_der().unwrap(),
+        };
+        let content = if chain {
+            verification_material::Content::X509CertificateChain(X509CertificateChain {
+                certificates: vec![certificate],
+            })
         } else {
-            &self.tlog_entry
+            verification_material::Content::Certificate(certificate)
         };
+        let inclusion_proof = inclusion_proof.then(|| InclusionProof {
+            checkpoint: Some(Checkpoint::default()),
+            ..Default::default()
+        });
+
+        Bundle {
+            media_type: version.to_owned(),
+            verification_material: Some(VerificationMaterial {
+                content: Some(content),
+                tlog_entries: vec![TransparencyLogEntry {
+                    inclusion_proof,
+                    ..Default::default()
+                }],
+                timestamp_verification_data: None,
+            }),
+            content: Some(bundle::Content::MessageSignature(MessageSignature {
+                message_digest: None,
+                signature: SIGNATURE.to_vec(),
+            })),
+        }
+    }
 
-        let actual: serde_json::Value =
-            serde_json::from_slice(&self.tlog_entry.canonicalized_body).ok()?;
-        let expected: serde_json::Value = serde_json::to_value(expected_entry).ok()?;
+    #[test]
+    fn bundle_03_profile() {
+        let certificate = certificate();
+        let v0_3 = BundleVersion::Bundle0_3.to_string();
+
+        assert!(CheckedBundle::try_from(bundle(&v0_3, &certificate, false, true)).is_ok());
+        assert!(CheckedBundle::try_from(bundle(
+            "application/vnd.dev.sigstore.bundle+json;version=0.3",
+            &certificate,
+            false,
+            true
+        ))
+        .is_ok());
+
+        assert!(matches!(
+            CheckedBundle::try_from(bundle(&v0_3, &certificate, true, true)),
+            Err(BundleErrorKind::BundleProfile(
+                BundleProfileErrorKind::Bundle03Profile(
+                    Bundle03ProfileErrorKind::CertificateChainUnsupported
+                )
+            ))
+        ));
+        assert!(matches!(
+            CheckedBundle::try_from(bundle(&v0_3, &certificate, false, false)),
+            Err(BundleErrorKind::BundleProfile(
+                BundleProfileErrorKind::Bundle02Profile(
+                    Bundle02ProfileErrorKind::InclusionProofMissing
+                )
+            ))
+        ));
+    }
 
-        if actual != expected {
-            return None;
+    #[test]
+    fn bundle_tlog_entries() {
+        let certificate = certificate();
+        let v0_3 = BundleVersion::Bundle0_3.to_string();
+
+        // How many entries are needed is up to the verifier.
+        let mut without_entries = bundle(&v0_3, &certificate, false, true);
+        if let Some(material) = &mut without_entries.verification_material {
+            material.tlog_entries.clear();
+        }
+        assert!(CheckedBundle::try_from(without_entries).is_ok());
+
+        // Each entry must meet the profile's requirements.
+        let mut with_entries = bundle(&v0_3, &certificate, false, true);
+        if let Some(material) = &mut with_entries.verification_material {
+            material.tlog_entries.push(TransparencyLogEntry::default());
+        }
+        assert!(matches!(
+            CheckedBundle::try_from(with_entries),
+            Err(BundleErrorKind::BundleProfile(
+                BundleProfileErrorKind::Bundle02Profile(
+                    Bundle02ProfileErrorKind::InclusionProofMissing
+                )
+            ))
+        ));
+
+        let mut chain_without_entries = bundle(&v0_3, &certificate, true, true);
+        if let Some(material) = &mut chain_without_entries.verification_material {
+            material.tlog_entries.clear();
         }
+        assert!(matches!(
+            CheckedBundle::try_from(chain_without_entries),
+            Err(BundleErrorKind::BundleProfile(
+                BundleProfileErrorKind::Bundle03Profile(
+                    Bundle03ProfileErrorKind::CertificateChainUnsupported
+                )
+            ))
+        ));
+    }
+
+    #[test]
+    fn bundle_unknown_profile() {
+        let certificate = certificate();
+
+        assert!(matches!(
+            CheckedBundle::try_from(bundle(
+                "application/vnd.dev.sigstore.bundle.v0.4+json",
+                &certificate,
+                false,
+                true
+            )),
+            Err(BundleErrorKind::BundleProfile(
+                BundleProfileErrorKind::Unknown(_)
+            ))
+        ));
+    }
 
-        Some(entry)
+    #[test]
+    fn certificate_identity() {
+        // A Fulcio certificate with both the original and v2 OIDC issuer extensions.
+        let certificate = Certificate::from_pem(
+            r#"-----BEGIN CERTIFICATE-----
+MIICzDCCAlGgAwIBAgIUF96OLbM9/tDVHKCJliXLTFvnfjAwCgYIKoZIzj0EAwMw
+NzEVMBMGA1UEChMMc2lnc3RvcmUuZGV2MR4wHAYDVQQDExVzaWdzdG9yZS1pbnRl
+cm1lZGlhdGUwHhcNMjMxMjEzMDU1MDU1WhcNMjMxMjEzMDYwMDU1WjAAMFkwEwYH
+KoZIzj0CAQYIKoZIzj0DAQcDQgAEmir+Lah2291zCsLkmREQNLzf99z571BNB+fa
+rerSLGzcwLFK7GRLTGYcO0oStxCYavxRQPMo3JvB8vGtZbn/76OCAXAwggFsMA4G
+A1UdDwEB/wQEAwIHgDATBgNVHSUEDDAKBggrBgEFBQcDAzAdBgNVHQ4EFgQU8U9M
+t9GMrRm8+gifPtc63nlP3OIwHwYDVR0jBBgwFoAU39Ppz1YkEZb5qNjpKFWixi4Y
+ZD8wGwYDVR0RAQH/BBEwD4ENYXNjQHRldHN1by5zaDAsBgorBgEEAYO/MAEBBB5o
+dHRwczovL2dpdGh1Yi5jb20vbG9naW4vb2F1dGgwLgYKKwYBBAGDvzABCAQgDB5o
+dHRwczovL2dpdGh1Yi5jb20vbG9naW4vb2F1dGgwgYkGCisGAQQB1nkCBAIEewR5
+AHcAdQDdPTBqxscRMmMZHhyZZzcCokpeuN48rf+HinKALynujgAAAYxhumYsAAAE
+AwBGMEQCIHRRe20lRrNM4xd07mpjTtgaE6FGS3jjF++zW8ZMnth3AiAd6LVAAeVW
+hSW4T0XJRw9lGU6/EK9+ELZpEjrY03dJ1zAKBggqhkjOPQQDAwNpADBmAjEAiHqK
+W9PQ/5h7VROVIWPaxUo3LhrL2sZanw4bzTDBDY0dRR19ZFzjtAph1RzpQqppAjEA
+plAvxwkAIR2jurboJZ4Zm9rNAx8KvA+A5yQFzNkGgKDLjTJrKmSKoIcWV3j7WfdL
+-----END CERTIFICATE-----"#,
+        )
+        .unwrap();
+
+        let identity = CertificateIdentity::new(certificate, [1; 32], vec![[2; 32]]);
+        assert_eq!(identity.identity.as_deref(), Some("asc@tetsuo.sh"));
+        assert_eq!(
+            identity.extensions,
+            FulcioCertificateExtensions {
+                issuer: Some("https://github.com/login/oauth".into()),
+                ..Default::default()
+            }
+        );
     }
 }
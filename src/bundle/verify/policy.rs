@@ -16,11 +16,17 @@
 //!
 //! <https://github.com/sigstore/fulcio/blob/main/docs/oid-info.md#extension-values>
 
+use std::fmt;
+
+use chrono::{DateTime, Utc};
 use const_oid::ObjectIdentifier;
+use regex::Regex;
 use thiserror::Error;
 use tracing::warn;
 use x509_cert::ext::pkix::{name::GeneralName, SubjectAltName};
 
+use crate::crypto::certificate_extensions::{self as fulcio_ext, extension_value};
+
 macro_rules! oids {
     ($($name:ident = $value:literal),+) => {
         $(const $name: ObjectIdentifier = ObjectIdentifier::new_unwrap($value);)+
@@ -30,22 +36,22 @@ macro_rules! oids {
 macro_rules! impl_policy {
     ($policy:ident, $oid:expr, $doc:literal) => {
         #[doc = $doc]
-        pub struct $policy(pub String);
+        pub struct $policy(pub Matcher);
 
         impl const_oid::AssociatedOid for $policy {
             const OID: ObjectIdentifier = $oid;
         }
 
         impl SingleX509ExtPolicy for $policy {
-            fn new<S: AsRef<str>>(val: S) -> Self {
-                Self(val.as_ref().to_owned())
+            fn with_matcher(matcher: Matcher) -> Self {
+                Self(matcher)
             }
 
             fn name() -> &'static str {
                 stringify!($policy)
             }
 
-            fn value(&self) -> &str {
+            fn matcher(&self) -> &Matcher {
                 &self.0
             }
         }
@@ -53,14 +59,7 @@ macro_rules! impl_policy {
 }
 
 oids! {
-    OIDC_ISSUER_OID = "1.3.6.1.4.1.57264.1.1",
-    OIDC_GITHUB_WORKFLOW_TRIGGER_OID = "1.3.6.1.4.1.57264.1.2",
-    OIDC_GITHUB_WORKFLOW_SHA_OID = "1.3.6.1.4.1.57264.1.3",
-    OIDC_GITHUB_WORKFLOW_NAME_OID = "1.3.6.1.4.1.57264.1.4",
-    OIDC_GITHUB_WORKFLOW_REPOSITORY_OID = "1.3.6.1.4.1.57264.1.5",
-    OIDC_GITHUB_WORKFLOW_REF_OID = "1.3.6.1.4.1.57264.1.6",
     OTHERNAME_OID = "1.3.6.1.4.1.57264.1.7"
-
 }
 
 #[derive(Error, Debug)]
@@ -75,26 +74,195 @@ pub enum PolicyError {
         actual: String,
     },
 
-    #[error("{0} of {total} policies failed: {1}\n- ",
-            errors.len(),
-            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n- ")
-    )]
-    AllOf {
+    #[error("provenance's {field} does not match (got {actual}, expected {expected})")]
+    ProvenanceCheckFailed {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("provenance does not record its {0}")]
+    ProvenanceFieldMissing(String),
+
+    #[error("{} of {total} policies failed:{results}", results.failed.len())]
+    AllOf { total: usize, results: ChildResults },
+
+    #[error("0 of {total} policies succeeded:{results}")]
+    AnyOf { total: usize, results: ChildResults },
+
+    #[error("{} of {total} policies succeeded, {threshold} required:{results}", results.passed.len())]
+    Threshold {
+        threshold: usize,
         total: usize,
-        errors: Vec<PolicyError>,
+        results: ChildResults,
     },
 
-    #[error("0 of {total} policies succeeded")]
-    AnyOf { total: usize },
+    #[error("negated policy succeeded")]
+    Not,
+
+    #[error("signing time {0} is outside of the allowed window")]
+    SigningTimeOutsideWindow(DateTime<Utc>),
 }
 
 pub type PolicyResult = Result<(), PolicyError>;
 
+/// The results of the child policies of a combinator, which are identified by their position.
+#[derive(Debug, Default)]
+pub struct ChildResults {
+    /// The positions of the child policies that succeeded.
+    pub passed: Vec<usize>,
+    /// The positions of the child policies that failed, and why.
+    pub failed: Vec<(usize, PolicyError)>,
+}
+
+impl ChildResults {
+    fn new<I>(results: I) -> Self
+    where
+        I: IntoIterator<Item = PolicyResult>,
+    {
+        let mut child_results = Self::default();
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(()) => child_results.passed.push(index),
+                Err(err) => child_results.failed.push((index, err)),
+            }
+        }
+        child_results
+    }
+}
+
+/// Lists each child policy's result on its own line.
+impl fmt::Display for ChildResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut passed = self.passed.iter().peekable();
+        let mut failed = self.failed.iter().peekable();
+        loop {
+            match (passed.peek(), failed.peek()) {
+                (Some(&&index), Some((failed_index, _))) if index < *failed_index => {
+                    write!(f, "\n- policy #{index} succeeded")?;
+                    passed.next();
+                }
+                (_, Some((index, err))) => {
+                    write!(f, "\n- policy #{index} failed: {err}")?;
+                    failed.next();
+                }
+                (Some(index), None) => {
+                    write!(f, "\n- policy #{index} succeeded")?;
+                    passed.next();
+                }
+                (None, None) => return Ok(()),
+            }
+        }
+    }
+}
+
+/// How a policy matches a textual value of the certificate.
+#[derive(Clone, Debug)]
+pub enum Matcher {
+    /// The value must be equal to the given string.
+    Exact(String),
+    /// The value must start with the given string.
+    Prefix(String),
+    /// The value must match the given glob pattern, built with [`Matcher::glob`].
+    Glob(Glob),
+    /// The value must match the given regular expression. As with cosign's
+    /// `--certificate-identity-regexp`, the expression is not anchored: use `^` and `$` to match
+    /// the whole value.
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Matches values against the glob `pattern`. See [`Glob`] for its syntax.
+    pub fn glob<S: AsRef<str>>(pattern: S) -> Result<Self, regex::Error> {
+        Glob::new(pattern).map(Self::Glob)
+    }
+
+    /// Matches values against the regular expression `pattern`.
+    pub fn regex<S: AsRef<str>>(pattern: S) -> Result<Self, regex::Error> {
+        Regex::new(pattern.as_ref()).map(Self::Regex)
+    }
+
+    /// Returns whether `value` matches.
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            Self::Exact(expected) => value == expected,
+            Self::Prefix(prefix) => value.starts_with(prefix.as_str()),
+            Self::Glob(glob) => glob.regex.is_match(value),
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl From<&str> for Matcher {
+    fn from(value: &str) -> Self {
+        Self::Exact(value.to_owned())
+    }
+}
+
+impl From<String> for Matcher {
+    fn from(value: String) -> Self {
+        Self::Exact(value)
+    }
+}
+
+/// Describes the expected value in mismatch errors.
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(expected) => write!(f, "{expected}"),
+            Self::Prefix(prefix) => write!(f, "prefix {prefix:?}"),
+            Self::Glob(glob) => write!(f, "glob {:?}", glob.pattern),
+            Self::Regex(regex) => write!(f, "regex {:?}", regex.as_str()),
+        }
+    }
+}
+
+/// A glob pattern, matching whole values.
+///
+/// `*` matches any sequence of characters other than `/`, `**` any sequence of characters, and
+/// `?` any single character other than `/`. Other characters match themselves. For instance,
+/// `https://github.com/our-org/*/.github/workflows/release.yml@refs/tags/**` matches the release
+/// workflow of any repository of `our-org`, run for any tag.
+#[derive(Clone, Debug)]
+pub struct Glob {
+    pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    pub fn new<S: AsRef<str>>(pattern: S) -> Result<Self, regex::Error> {
+        let pattern = pattern.as_ref();
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.next_if_eq(&'*').is_some() => regex.push_str(".*"),
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        regex.push('$');
+
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            regex: Regex::new(&regex)?,
+        })
+    }
+}
+
 /// A policy that checks a single textual value against a X.509 extension.
-pub trait SingleX509ExtPolicy {
-    fn new<S: AsRef<str>>(val: S) -> Self;
+pub trait SingleX509ExtPolicy: Sized {
+    /// Creates a policy requiring the extension's value to be exactly `val`.
+    fn new<S: AsRef<str>>(val: S) -> Self {
+        Self::with_matcher(Matcher::Exact(val.as_ref().to_owned()))
+    }
+
+    /// Creates a policy requiring the extension's value to match `matcher`.
+    fn with_matcher(matcher: Matcher) -> Self;
+
     fn name() -> &'static str;
-    fn value(&self) -> &str;
+    fn matcher(&self) -> &Matcher;
 }
 
 impl<T: SingleX509ExtPolicy + const_oid::AssociatedOid> VerificationPolicy for T {
@@ -107,15 +275,14 @@ impl<T: SingleX509ExtPolicy + const_oid::AssociatedOid> VerificationPolicy for T
             return Err(PolicyError::ExtensionNotFound);
         };
 
-        // Parse raw string without DER encoding.
-        let val = std::str::from_utf8(ext.extn_value.as_bytes())
-            .or(Err(PolicyError::ExtensionNotFound))?;
+        // Deprecated extensions hold raw strings, and the others DER-encoded strings.
+        let val = extension_value(ext).or(Err(PolicyError::ExtensionNotFound))?;
 
-        if val != self.value() {
+        if !self.matcher().matches(&val) {
             return Err(PolicyError::ExtensionCheckFailed {
                 extension: T::name().to_owned(),
-                expected: self.value().to_owned(),
-                actual: val.to_owned(),
+                expected: self.matcher().to_string(),
+                actual: val,
             });
         }
 
@@ -125,87 +292,188 @@ impl<T: SingleX509ExtPolicy + const_oid::AssociatedOid> VerificationPolicy for T
 
 impl_policy!(
     OIDCIssuer,
-    OIDC_ISSUER_OID,
+    fulcio_ext::ISSUER,
     "Checks the certificate's OIDC issuer."
 );
 
 impl_policy!(
     GitHubWorkflowTrigger,
-    OIDC_GITHUB_WORKFLOW_TRIGGER_OID,
+    fulcio_ext::GITHUB_WORKFLOW_TRIGGER,
     "Checks the certificate's GitHub Actions workflow trigger."
 );
 
 impl_policy!(
     GitHubWorkflowSHA,
-    OIDC_GITHUB_WORKFLOW_SHA_OID,
+    fulcio_ext::GITHUB_WORKFLOW_SHA,
     "Checks the certificate's GitHub Actions workflow commit SHA."
 );
 
 impl_policy!(
     GitHubWorkflowName,
-    OIDC_GITHUB_WORKFLOW_NAME_OID,
+    fulcio_ext::GITHUB_WORKFLOW_NAME,
     "Checks the certificate's GitHub Actions workflow name."
 );
 
 impl_policy!(
     GitHubWorkflowRepository,
-    OIDC_GITHUB_WORKFLOW_REPOSITORY_OID,
+    fulcio_ext::GITHUB_WORKFLOW_REPOSITORY,
     "Checks the certificate's GitHub Actions workflow repository."
 );
 
 impl_policy!(
     GitHubWorkflowRef,
-    OIDC_GITHUB_WORKFLOW_REF_OID,
+    fulcio_ext::GITHUB_WORKFLOW_REF,
     "Checks the certificate's GitHub Actions workflow ref."
 );
 
+impl_policy!(
+    OIDCIssuerV2,
+    fulcio_ext::ISSUER_V2,
+    "Checks the certificate's OIDC issuer, as recorded in the V2 extension."
+);
+
+impl_policy!(
+    BuildSignerURI,
+    fulcio_ext::BUILD_SIGNER_URI,
+    "Checks the certificate's build signer URI, e.g. a reusable workflow."
+);
+
+impl_policy!(
+    BuildSignerDigest,
+    fulcio_ext::BUILD_SIGNER_DIGEST,
+    "Checks the certificate's build signer digest."
+);
+
+impl_policy!(
+    RunnerEnvironment,
+    fulcio_ext::RUNNER_ENVIRONMENT,
+    "Checks the certificate's runner environment, e.g. `github-hosted`."
+);
+
+impl_policy!(
+    SourceRepositoryURI,
+    fulcio_ext::SOURCE_REPOSITORY_URI,
+    "Checks the certificate's source repository URI."
+);
+
+impl_policy!(
+    SourceRepositoryDigest,
+    fulcio_ext::SOURCE_REPOSITORY_DIGEST,
+    "Checks the certificate's source repository digest, e.g. a commit SHA."
+);
+
+impl_policy!(
+    SourceRepositoryRef,
+    fulcio_ext::SOURCE_REPOSITORY_REF,
+    "Checks the certificate's source repository ref, e.g. `refs/heads/main`."
+);
+
+impl_policy!(
+    SourceRepositoryIdentifier,
+    fulcio_ext::SOURCE_REPOSITORY_IDENTIFIER,
+    "Checks the certificate's source repository identifier."
+);
+
+impl_policy!(
+    SourceRepositoryOwnerURI,
+    fulcio_ext::SOURCE_REPOSITORY_OWNER_URI,
+    "Checks the certificate's source repository owner URI."
+);
+
+impl_policy!(
+    SourceRepositoryOwnerIdentifier,
+    fulcio_ext::SOURCE_REPOSITORY_OWNER_IDENTIFIER,
+    "Checks the certificate's source repository owner identifier."
+);
+
+impl_policy!(
+    BuildConfigURI,
+    fulcio_ext::BUILD_CONFIG_URI,
+    "Checks the certificate's build config URI, e.g. the calling workflow."
+);
+
+impl_policy!(
+    BuildConfigDigest,
+    fulcio_ext::BUILD_CONFIG_DIGEST,
+    "Checks the certificate's build config digest."
+);
+
+impl_policy!(
+    BuildTrigger,
+    fulcio_ext::BUILD_TRIGGER,
+    "Checks the certificate's build trigger, e.g. `push`."
+);
+
+impl_policy!(
+    RunInvocationURI,
+    fulcio_ext::RUN_INVOCATION_URI,
+    "Checks the certificate's run invocation URI."
+);
+
+impl_policy!(
+    SourceRepositoryVisibilityAtSigning,
+    fulcio_ext::SOURCE_REPOSITORY_VISIBILITY_AT_SIGNING,
+    "Checks the certificate's source repository visibility at signing, e.g. `public`."
+);
+
 /// An interface that all policies must conform to.
 pub trait VerificationPolicy {
     fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult;
 }
 
+/// An owned policy, as held by combinators.
+pub type BoxedPolicy = Box<dyn VerificationPolicy + Send + Sync>;
+
 /// The "any of" policy, corresponding to a logical OR between child policies.
 ///
+/// Child policies are certificate policies by default, and may also be
+/// [provenance policies](super::provenance_policy).
+///
 /// An empty list of child policies is considered trivially invalid.
-pub struct AnyOf<'a> {
-    children: Vec<&'a dyn VerificationPolicy>,
+pub struct AnyOf<P: ?Sized = dyn VerificationPolicy + Send + Sync> {
+    children: Vec<Box<P>>,
 }
 
-impl<'a> AnyOf<'a> {
+impl<P: ?Sized> AnyOf<P> {
     pub fn new<I>(policies: I) -> Self
     where
-        I: IntoIterator<Item = &'a dyn VerificationPolicy>,
+        I: IntoIterator<Item = Box<P>>,
     {
         Self {
             children: policies.into_iter().collect(),
         }
     }
+
+    pub(super) fn evaluate(&self, verify: impl Fn(&P) -> PolicyResult) -> PolicyResult {
+        let results = ChildResults::new(self.children.iter().map(|p| verify(p)));
+        if results.passed.is_empty() {
+            return Err(PolicyError::AnyOf {
+                total: self.children.len(),
+                results,
+            });
+        }
+
+        Ok(())
+    }
 }
 
-impl VerificationPolicy for AnyOf<'_> {
+impl<P: VerificationPolicy + ?Sized> VerificationPolicy for AnyOf<P> {
     fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult {
-        self.children
-            .iter()
-            .find(|policy| policy.verify(cert).is_err())
-            .map_or(Ok(()), |_| {
-                Err(PolicyError::AnyOf {
-                    total: self.children.len(),
-                })
-            })
+        self.evaluate(|p| p.verify(cert))
     }
 }
 
 /// The "all of" policy, corresponding to a logical AND between child policies.
 ///
 /// An empty list of child policies is considered trivially invalid.
-pub struct AllOf<'a> {
-    children: Vec<&'a dyn VerificationPolicy>,
+pub struct AllOf<P: ?Sized = dyn VerificationPolicy + Send + Sync> {
+    children: Vec<Box<P>>,
 }
 
-impl<'a> AllOf<'a> {
+impl<P: ?Sized> AllOf<P> {
     pub fn new<I>(policies: I) -> Option<Self>
     where
-        I: IntoIterator<Item = &'a dyn VerificationPolicy>,
+        I: IntoIterator<Item = Box<P>>,
     {
         let children: Vec<_> = policies.into_iter().collect();
 
@@ -219,17 +487,64 @@ impl<'a> AllOf<'a> {
 
         Some(Self { children })
     }
+
+    pub(super) fn evaluate(&self, verify: impl Fn(&P) -> PolicyResult) -> PolicyResult {
+        let results = ChildResults::new(self.children.iter().map(|p| verify(p)));
+        if !results.failed.is_empty() {
+            return Err(PolicyError::AllOf {
+                total: self.children.len(),
+                results,
+            });
+        }
+
+        Ok(())
+    }
 }
 
-impl VerificationPolicy for AllOf<'_> {
+impl<P: VerificationPolicy + ?Sized> VerificationPolicy for AllOf<P> {
     fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult {
-        let results = self.children.iter().map(|policy| policy.verify(cert).err());
-        let failures: Vec<_> = results.flatten().collect();
+        self.evaluate(|p| p.verify(cert))
+    }
+}
 
-        if !failures.is_empty() {
-            return Err(PolicyError::AllOf {
+/// The "k of n" policy, requiring at least `threshold` of its child policies to pass.
+pub struct Threshold<P: ?Sized = dyn VerificationPolicy + Send + Sync> {
+    threshold: usize,
+    children: Vec<Box<P>>,
+}
+
+impl<P: ?Sized> Threshold<P> {
+    /// Returns `None` unless `threshold` is between 1 and the number of child policies.
+    pub fn new<I>(threshold: usize, policies: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Box<P>>,
+    {
+        let children: Vec<_> = policies.into_iter().collect();
+
+        // As with `AllOf`, a threshold that is trivially met or that can never be met is almost
+        // certainly a mistake.
+        if threshold == 0 || threshold > children.len() {
+            warn!(
+                threshold,
+                policies = children.len(),
+                "attempted to construct a Threshold that is trivially met or can never be met"
+            );
+            return None;
+        }
+
+        Some(Self {
+            threshold,
+            children,
+        })
+    }
+
+    pub(super) fn evaluate(&self, verify: impl Fn(&P) -> PolicyResult) -> PolicyResult {
+        let results = ChildResults::new(self.children.iter().map(|p| verify(p)));
+        if results.passed.len() < self.threshold {
+            return Err(PolicyError::Threshold {
+                threshold: self.threshold,
                 total: self.children.len(),
-                errors: failures,
+                results,
             });
         }
 
@@ -237,13 +552,43 @@ impl VerificationPolicy for AllOf<'_> {
     }
 }
 
+impl<P: VerificationPolicy + ?Sized> VerificationPolicy for Threshold<P> {
+    fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult {
+        self.evaluate(|p| p.verify(cert))
+    }
+}
+
+/// The "not" policy, passing only if its child policy fails.
+pub struct Not<P: ?Sized = dyn VerificationPolicy + Send + Sync> {
+    child: Box<P>,
+}
+
+impl<P: ?Sized> Not<P> {
+    pub fn new(policy: Box<P>) -> Self {
+        Self { child: policy }
+    }
+
+    pub(super) fn evaluate(&self, verify: impl Fn(&P) -> PolicyResult) -> PolicyResult {
+        match verify(&self.child) {
+            Ok(()) => Err(PolicyError::Not),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+impl<P: VerificationPolicy + ?Sized> VerificationPolicy for Not<P> {
+    fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult {
+        self.evaluate(|p| p.verify(cert))
+    }
+}
+
 /// Verifies the certificate's "identity", corresponding to the X.509v3 SAN.
 /// Identities are verified modulo an OIDC issuer, so the issuer's URI
 /// is also required.
 ///
 /// Supported SAN types include emails, URIs, and Sigstore-specific "other names".
 pub struct Identity {
-    identity: String,
+    identity: Matcher,
     issuer: OIDCIssuer,
 }
 
@@ -254,38 +599,31 @@ impl Identity {
         B: AsRef<str>,
     {
         Self {
-            identity: identity.as_ref().to_owned(),
+            identity: Matcher::Exact(identity.as_ref().to_owned()),
             issuer: OIDCIssuer::new(issuer),
         }
     }
+
+    /// Creates an identity policy accepting any SAN identity matched by `identity`, issued by an
+    /// issuer matched by `issuer`.
+    pub fn with_matchers(identity: Matcher, issuer: Matcher) -> Self {
+        Self {
+            identity,
+            issuer: OIDCIssuer::with_matcher(issuer),
+        }
+    }
 }
 
 impl VerificationPolicy for Identity {
     fn verify(&self, cert: &x509_cert::Certificate) -> PolicyResult {
         self.issuer.verify(cert)?;
 
-        let (_, san): (bool, SubjectAltName) = match cert.tbs_certificate.get() {
-            Ok(Some(result)) => result,
-            _ => return Err(PolicyError::ExtensionNotFound),
-        };
+        let names = san_identities(cert).ok_or(PolicyError::ExtensionNotFound)?;
 
-        let names: Vec<_> = san
-            .0
-            .iter()
-            .filter_map(|name| match name {
-                GeneralName::Rfc822Name(name) => Some(name.as_str()),
-                GeneralName::UniformResourceIdentifier(name) => Some(name.as_str()),
-                GeneralName::OtherName(name) if name.type_id == OTHERNAME_OID => {
-                    std::str::from_utf8(name.value.value()).ok()
-                }
-                _ => None,
-            })
-            .collect();
-
-        if !names.contains(&self.identity.as_str()) {
+        if !names.iter().any(|name| self.identity.matches(name)) {
             return Err(PolicyError::ExtensionCheckFailed {
                 extension: "SubjectAltName".to_owned(),
-                expected: self.identity.clone(),
+                expected: self.identity.to_string(),
                 actual: names.join(", "),
             });
         }
@@ -293,3 +631,302 @@ impl VerificationPolicy for Identity {
         Ok(())
     }
 }
+
+/// Returns the signer identities in the certificate's SubjectAltName extension: email addresses,
+/// URIs and Fulcio's username other names.
+pub(crate) fn san_identities(cert: &x509_cert::Certificate) -> Option<Vec<String>> {
+    let (_, san): (bool, SubjectAltName) = cert.tbs_certificate.get().ok()??;
+
+    let names = san
+        .0
+        .iter()
+        .filter_map(|name| match name {
+            GeneralName::Rfc822Name(name) => Some(name.to_string()),
+            GeneralName::UniformResourceIdentifier(name) => Some(name.to_string()),
+            GeneralName::OtherName(name) if name.type_id == OTHERNAME_OID => {
+                std::str::from_utf8(name.value.value())
+                    .ok()
+                    .map(str::to_owned)
+            }
+            _ => None,
+        })
+        .collect();
+
+    Some(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::certificate_extensions::tests::{certificate_with, der};
+    use crate::crypto::tests::{generate_certificate, CertGenerationOptions};
+    use x509_cert::der::Decode;
+
+    struct Pass;
+
+    impl VerificationPolicy for Pass {
+        fn verify(&self, _cert: &x509_cert::Certificate) -> PolicyResult {
+            Ok(())
+        }
+    }
+
+    struct Fail;
+
+    impl VerificationPolicy for Fail {
+        fn verify(&self, _cert: &x509_cert::Certificate) -> PolicyResult {
+            Err(PolicyError::ExtensionNotFound)
+        }
+    }
+
+    fn certificate() -> x509_cert::Certificate {
+        let cert = generate_certificate(None, CertGenerationOptions::default()).unwrap();
+        x509_cert::Certificate::from_der(&cert.cert.to_der().unwrap()).unwrap()
+    }
+
+    fn policies(passes: &[bool]) -> Vec<BoxedPolicy> {
+        passes
+            .iter()
+            .map(|&pass| -> BoxedPolicy {
+                if pass {
+                    Box::new(Pass)
+                } else {
+                    Box::new(Fail)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn any_of() {
+        let cert = certificate();
+
+        assert!(AnyOf::new(policies(&[false, true])).verify(&cert).is_ok());
+        assert!(AnyOf::new(policies(&[true, false])).verify(&cert).is_ok());
+        match AnyOf::new(policies(&[false, false])).verify(&cert) {
+            Err(PolicyError::AnyOf { total, results }) => {
+                assert_eq!(total, 2);
+                assert!(results.passed.is_empty());
+                assert_eq!(results.failed.len(), 2);
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+        assert!(AnyOf::new(policies(&[])).verify(&cert).is_err());
+    }
+
+    #[test]
+    fn all_of() {
+        let cert = certificate();
+
+        assert!(AllOf::new(policies(&[])).is_none());
+        assert!(AllOf::new(policies(&[true, true]))
+            .unwrap()
+            .verify(&cert)
+            .is_ok());
+
+        let err = AllOf::new(policies(&[true, false, true]))
+            .unwrap()
+            .verify(&cert)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1 of 3 policies failed:\n\
+             - policy #0 succeeded\n\
+             - policy #1 failed: did not find exactly 1 of the required extension in the certificate\n\
+             - policy #2 succeeded"
+        );
+    }
+
+    #[test]
+    fn threshold() {
+        let cert = certificate();
+
+        assert!(Threshold::new(0, policies(&[true])).is_none());
+        assert!(Threshold::new(2, policies(&[true])).is_none());
+        assert!(Threshold::new(2, policies(&[true, false, true]))
+            .unwrap()
+            .verify(&cert)
+            .is_ok());
+        match Threshold::new(2, policies(&[true, false, false]))
+            .unwrap()
+            .verify(&cert)
+        {
+            Err(PolicyError::Threshold {
+                threshold,
+                total,
+                results,
+            }) => {
+                assert_eq!((threshold, total), (2, 3));
+                assert_eq!(results.passed, [0]);
+                assert_eq!(results.failed.len(), 2);
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn not_and_nesting() {
+        let cert = certificate();
+
+        assert!(Not::new(Box::new(Fail)).verify(&cert).is_ok());
+        assert!(matches!(
+            Not::new(Box::new(Pass)).verify(&cert),
+            Err(PolicyError::Not)
+        ));
+
+        // Combinators nest, and may be stored as owned policies.
+        let policy: BoxedPolicy = Box::new(AnyOf::new(vec![
+            Box::new(Not::new(Box::new(Pass))) as BoxedPolicy,
+            Box::new(AllOf::new(policies(&[true, true])).unwrap()),
+        ]));
+        assert!(policy.verify(&cert).is_ok());
+    }
+
+    #[test]
+    fn matchers() {
+        let identity =
+            "https://github.com/our-org/app/.github/workflows/release.yml@refs/tags/v1.0";
+
+        assert!(Matcher::from(identity).matches(identity));
+        assert!(!Matcher::from("https://github.com/our-org").matches(identity));
+        assert!(Matcher::Prefix("https://github.com/our-org/".into()).matches(identity));
+        assert!(Matcher::regex(r"^https://github\.com/our-org/")
+            .unwrap()
+            .matches(identity));
+        assert!(!Matcher::regex(r"^https://github\.com/other-org/")
+            .unwrap()
+            .matches(identity));
+    }
+
+    #[test]
+    fn glob_matcher() {
+        let glob = Matcher::glob(
+            "https://github.com/our-org/*/.github/workflows/release.yml@refs/tags/**",
+        )
+        .unwrap();
+
+        assert!(glob.matches(
+            "https://github.com/our-org/app/.github/workflows/release.yml@refs/tags/v1.0"
+        ));
+        assert!(glob.matches(
+            "https://github.com/our-org/app/.github/workflows/release.yml@refs/tags/app/v1.0"
+        ));
+        // `*` does not cross path segments, and the pattern must match the whole value.
+        assert!(!glob.matches(
+            "https://github.com/our-org/app/sub/.github/workflows/release.yml@refs/tags/v1.0"
+        ));
+        assert!(!glob.matches(
+            "https://github.com/our-org/app/.github/workflows/release.yml@refs/heads/main"
+        ));
+        // Other characters are literal.
+        assert!(!Matcher::glob("a.c").unwrap().matches("abc"));
+        assert!(Matcher::glob("a?c").unwrap().matches("abc"));
+    }
+
+    const WORKFLOW: &str =
+        "https://github.com/our-org/app/.github/workflows/release.yml@refs/heads/main";
+
+    fn workflow_certificate() -> x509_cert::Certificate {
+        certificate_with(&[
+            (
+                fulcio_ext::ISSUER,
+                b"https://token.actions.githubusercontent.com".to_vec(),
+            ),
+            (fulcio_ext::SOURCE_REPOSITORY_REF, der("refs/heads/main")),
+            (fulcio_ext::BUILD_SIGNER_URI, der(WORKFLOW)),
+        ])
+    }
+
+    fn mismatch(result: PolicyResult) -> String {
+        match result {
+            Err(err @ PolicyError::ExtensionCheckFailed { .. }) => err.to_string(),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn extension_policies_with_matchers() {
+        let cert = workflow_certificate();
+
+        for matcher in [
+            Matcher::from("refs/heads/main"),
+            Matcher::Prefix("refs/heads/".into()),
+            Matcher::glob("refs/heads/*").unwrap(),
+            Matcher::regex(r"^refs/heads/(main|master)$").unwrap(),
+        ] {
+            assert!(SourceRepositoryRef::with_matcher(matcher)
+                .verify(&cert)
+                .is_ok());
+        }
+        assert!(BuildSignerURI::with_matcher(
+            Matcher::glob("https://github.com/our-org/*/**").unwrap()
+        )
+        .verify(&cert)
+        .is_ok());
+
+        assert_eq!(
+            mismatch(SourceRepositoryRef::new("refs/tags/v1.0").verify(&cert)),
+            "certificate's SourceRepositoryRef does not match (got refs/heads/main, expected refs/tags/v1.0)"
+        );
+        assert_eq!(
+            mismatch(SourceRepositoryRef::with_matcher(Matcher::Prefix("refs/tags/".into())).verify(&cert)),
+            "certificate's SourceRepositoryRef does not match (got refs/heads/main, expected prefix \"refs/tags/\")"
+        );
+        assert_eq!(
+            mismatch(SourceRepositoryRef::with_matcher(Matcher::glob("refs/tags/*").unwrap()).verify(&cert)),
+            "certificate's SourceRepositoryRef does not match (got refs/heads/main, expected glob \"refs/tags/*\")"
+        );
+        assert_eq!(
+            mismatch(SourceRepositoryRef::with_matcher(Matcher::regex("^refs/tags/").unwrap()).verify(&cert)),
+            "certificate's SourceRepositoryRef does not match (got refs/heads/main, expected regex \"^refs/tags/\")"
+        );
+        // `*` does not match across path segments.
+        assert!(matches!(
+            BuildSignerURI::with_matcher(Matcher::glob("https://github.com/our-org/*").unwrap())
+                .verify(&cert),
+            Err(PolicyError::ExtensionCheckFailed { .. })
+        ));
+
+        assert!(matches!(
+            GitHubWorkflowTrigger::with_matcher(Matcher::glob("**").unwrap()).verify(&cert),
+            Err(PolicyError::ExtensionNotFound)
+        ));
+    }
+
+    #[test]
+    fn identity_with_matchers() {
+        let cert = workflow_certificate();
+        let issuer = || Matcher::from("https://token.actions.githubusercontent.com");
+
+        for identity in [
+            Matcher::from("tests@sigstore-rs.dev"),
+            Matcher::Prefix("tests@".into()),
+            Matcher::glob("*@sigstore-rs.dev").unwrap(),
+            Matcher::regex(r"@sigstore-rs\.dev$").unwrap(),
+        ] {
+            assert!(Identity::with_matchers(identity, issuer())
+                .verify(&cert)
+                .is_ok());
+        }
+        assert!(Identity::with_matchers(
+            Matcher::from("tests@sigstore-rs.dev"),
+            Matcher::glob("https://*.githubusercontent.com").unwrap(),
+        )
+        .verify(&cert)
+        .is_ok());
+
+        assert_eq!(
+            mismatch(Identity::with_matchers(Matcher::glob("*@example.com").unwrap(), issuer()).verify(&cert)),
+            "certificate's SubjectAltName does not match (got tests@sigstore-rs.dev, expected glob \"*@example.com\")"
+        );
+        assert_eq!(
+            mismatch(
+                Identity::with_matchers(
+                    Matcher::from("tests@sigstore-rs.dev"),
+                    Matcher::Prefix("https://gitlab.com".into()),
+                )
+                .verify(&cert)
+            ),
+            "certificate's OIDCIssuer does not match (got https://token.actions.githubusercontent.com, expected prefix \"https://gitlab.com\")"
+        );
+    }
+}
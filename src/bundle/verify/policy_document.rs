@@ -0,0 +1,577 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative verification policies, loaded from configuration files.
+//!
+//! A [`PolicyDocument`] can be deserialized from any serde format, such as JSON or YAML:
+//!
+//! ```json
+//! {
+//!   "certificate": {
+//!     "all_of": [
+//!       {
+//!         "identity": {
+//!           "san": { "glob": "https://github.com/our-org/*/.github/workflows/release.yml@refs/tags/**" },
+//!           "issuer": "https://token.actions.githubusercontent.com"
+//!         }
+//!       },
+//!       { "extension": { "source_repository_visibility_at_signing": "public" } },
+//!       { "not": { "extension": { "runner_environment": "self-hosted" } } }
+//!     ]
+//!   },
+//!   "min_transparency_log_entries": 1,
+//!   "min_signed_timestamps": 1,
+//!   "signing_time": { "not_before": "2024-01-01T00:00:00Z" }
+//! }
+//! ```
+//!
+//! `certificate` holds a tree of certificate policies, whose nodes are one of:
+//!
+//! * `identity`: the SAN identity (`san`) and OIDC issuer (`issuer`) of the certificate, as with
+//!   [`policy::Identity`].
+//! * `extension`: the value of a Fulcio extension, named after the fields of
+//!   [`FulcioCertificateExtensions`](crate::crypto::FulcioCertificateExtensions), plus
+//!   `issuer_v2`.
+//! * `any_of`, `all_of` and `not`, and `threshold` with a `threshold` count of `policies`, as
+//!   with the [`policy`] combinators.
+//!
+//! Values are matched by a string, for an exact match, or by one of `{"exact": ...}`,
+//! `{"prefix": ...}`, `{"glob": ...}` and `{"regex": ...}` (see [`Matcher`]).
+//!
+//! The other fields are optional requirements over the verification's outcome: the minimum
+//! numbers of distinct transparency logs that recorded the signature and of verified signed
+//! timestamps, and the window in which every verified signing time must fall.
+//!
+//! [`Verifier::verify_with_document`](super::Verifier::verify_with_document) and
+//! [`Verifier::verify_digest_with_document`](super::Verifier::verify_digest_with_document)
+//! enforce the whole document. Other verifications must check the certificate policy returned by
+//! [`PolicyDocument::certificate_policy`], and then the verification's outcome with
+//! [`PolicyDocument::verify_outcome`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use super::models::{
+    check_threshold, distinct_logs, DISTINCT_LOGS, SIGNED_TIMESTAMPS, SIGNING_TIMES,
+};
+use super::policy::{
+    self, AllOf, AnyOf, BoxedPolicy, Matcher, Not, PolicyError, SingleX509ExtPolicy, Threshold,
+    VerificationPolicy,
+};
+use super::{SigningTimeSource, VerificationError, VerificationOutcome};
+
+/// An invalid [`PolicyDocument`].
+#[derive(Error, Debug)]
+#[error("invalid policy document at {path}: {message}")]
+pub struct PolicyDocumentError {
+    /// The path of the offending field in the document, e.g. `certificate.any_of[1].identity`.
+    pub path: String,
+    pub message: String,
+}
+
+/// A verification policy loaded from a declarative document. See the
+/// [module documentation](self) for its format and how to enforce it.
+pub struct PolicyDocument {
+    certificate: BoxedPolicy,
+    min_transparency_log_entries: usize,
+    min_signed_timestamps: usize,
+    signing_time: Option<SigningTimeWindow>,
+}
+
+impl PolicyDocument {
+    /// Parses a JSON policy document.
+    pub fn from_json(json: &str) -> Result<Self, PolicyDocumentError> {
+        Self::from_deserializer(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    /// Parses a policy document with `deserializer`, e.g. a YAML deserializer.
+    pub fn from_deserializer<'de, D>(deserializer: D) -> Result<Self, PolicyDocumentError>
+    where
+        D: Deserializer<'de>,
+    {
+        let spec: DocumentSpec =
+            serde_path_to_error::deserialize(deserializer).map_err(|err| PolicyDocumentError {
+                path: err.path().to_string(),
+                message: err.into_inner().to_string(),
+            })?;
+
+        Ok(Self {
+            certificate: spec.certificate.0,
+            min_transparency_log_entries: spec.min_transparency_log_entries,
+            min_signed_timestamps: spec.min_signed_timestamps,
+            signing_time: spec.signing_time,
+        })
+    }
+
+    /// Returns the document's policy over signing certificates.
+    ///
+    /// The policy does not check the document's other requirements: see
+    /// [`PolicyDocument::verify_outcome`].
+    pub fn certificate_policy(&self) -> &dyn VerificationPolicy {
+        self.certificate.as_ref()
+    }
+
+    /// Checks the document's requirements over the outcome of a successful verification: the
+    /// numbers of distinct transparency logs that recorded the signature and of verified signed
+    /// timestamps, and the signing times.
+    pub fn verify_outcome(&self, outcome: &VerificationOutcome) -> Result<(), VerificationError> {
+        let timestamps = outcome
+            .signing_times
+            .iter()
+            .filter(|signing_time| signing_time.source == SigningTimeSource::TimestampAuthority)
+            .count();
+
+        check_threshold(
+            DISTINCT_LOGS,
+            self.min_transparency_log_entries,
+            distinct_logs(&outcome.log_entries),
+        )?;
+        check_threshold(SIGNED_TIMESTAMPS, self.min_signed_timestamps, timestamps)?;
+
+        if let Some(window) = &self.signing_time {
+            check_threshold(SIGNING_TIMES, 1, outcome.signing_times.len())?;
+            if let Some(signing_time) = outcome
+                .signing_times
+                .iter()
+                .find(|signing_time| !window.contains(signing_time.time))
+            {
+                return Err(PolicyError::SigningTimeOutsideWindow(signing_time.time))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DocumentSpec {
+    certificate: CompiledPolicy,
+    #[serde(default)]
+    min_transparency_log_entries: usize,
+    #[serde(default)]
+    min_signed_timestamps: usize,
+    #[serde(default)]
+    signing_time: Option<SigningTimeWindow>,
+}
+
+/// The window in which signing times must fall. Both bounds are inclusive.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, try_from = "SigningTimeWindowSpec")]
+struct SigningTimeWindow {
+    not_before: Option<DateTime<Utc>>,
+    not_after: Option<DateTime<Utc>>,
+}
+
+impl SigningTimeWindow {
+    fn contains(&self, time: DateTime<Utc>) -> bool {
+        !matches!(self.not_before, Some(not_before) if time < not_before)
+            && !matches!(self.not_after, Some(not_after) if not_after < time)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SigningTimeWindowSpec {
+    #[serde(default)]
+    not_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    not_after: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<SigningTimeWindowSpec> for SigningTimeWindow {
+    type Error = String;
+
+    fn try_from(spec: SigningTimeWindowSpec) -> Result<Self, Self::Error> {
+        if let (Some(not_before), Some(not_after)) = (spec.not_before, spec.not_after) {
+            if not_before > not_after {
+                return Err(format!(
+                    "not_before ({not_before}) is later than not_after ({not_after})"
+                ));
+            }
+        }
+
+        Ok(Self {
+            not_before: spec.not_before,
+            not_after: spec.not_after,
+        })
+    }
+}
+
+/// A certificate policy, compiled as soon as it is deserialized so that invalid policies are
+/// reported at their path in the document.
+#[derive(Deserialize)]
+#[serde(from = "PolicySpec")]
+struct CompiledPolicy(BoxedPolicy);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+enum PolicySpec {
+    Identity {
+        san: CompiledMatcher,
+        issuer: CompiledMatcher,
+    },
+    Extension(ExtensionSpec),
+    AnyOf(CompiledAnyOf),
+    AllOf(CompiledAllOf),
+    Not(Box<CompiledPolicy>),
+    Threshold(CompiledThreshold),
+}
+
+impl From<PolicySpec> for CompiledPolicy {
+    fn from(spec: PolicySpec) -> Self {
+        Self(match spec {
+            PolicySpec::Identity { san, issuer } => {
+                Box::new(policy::Identity::with_matchers(san.0, issuer.0))
+            }
+            PolicySpec::Extension(extension) => extension.compile(),
+            PolicySpec::AnyOf(policy) => Box::new(policy.0),
+            PolicySpec::AllOf(policy) => Box::new(policy.0),
+            PolicySpec::Not(policy) => Box::new(Not::new(policy.0)),
+            PolicySpec::Threshold(policy) => Box::new(policy.0),
+        })
+    }
+}
+
+/// Unwraps compiled policies, for the combinators.
+fn children(policies: Vec<CompiledPolicy>) -> impl Iterator<Item = BoxedPolicy> {
+    policies.into_iter().map(|policy| policy.0)
+}
+
+/// An [`AnyOf`] policy, compiled as soon as it is deserialized.
+#[derive(Deserialize)]
+#[serde(try_from = "Vec<CompiledPolicy>")]
+struct CompiledAnyOf(AnyOf);
+
+impl TryFrom<Vec<CompiledPolicy>> for CompiledAnyOf {
+    type Error = &'static str;
+
+    fn try_from(policies: Vec<CompiledPolicy>) -> Result<Self, Self::Error> {
+        if policies.is_empty() {
+            return Err("any_of requires at least one policy");
+        }
+
+        Ok(Self(AnyOf::new(children(policies))))
+    }
+}
+
+/// An [`AllOf`] policy, compiled as soon as it is deserialized.
+#[derive(Deserialize)]
+#[serde(try_from = "Vec<CompiledPolicy>")]
+struct CompiledAllOf(AllOf);
+
+impl TryFrom<Vec<CompiledPolicy>> for CompiledAllOf {
+    type Error = &'static str;
+
+    fn try_from(policies: Vec<CompiledPolicy>) -> Result<Self, Self::Error> {
+        AllOf::new(children(policies))
+            .map(Self)
+            .ok_or("all_of requires at least one policy")
+    }
+}
+
+/// A [`Threshold`] policy, compiled as soon as it is deserialized.
+#[derive(Deserialize)]
+#[serde(try_from = "ThresholdSpec")]
+struct CompiledThreshold(Threshold);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThresholdSpec {
+    threshold: usize,
+    policies: Vec<CompiledPolicy>,
+}
+
+impl TryFrom<ThresholdSpec> for CompiledThreshold {
+    type Error = String;
+
+    fn try_from(spec: ThresholdSpec) -> Result<Self, Self::Error> {
+        let total = spec.policies.len();
+        Threshold::new(spec.threshold, children(spec.policies))
+            .map(Self)
+            .ok_or_else(|| {
+                format!("threshold must be between 1 and the number of policies ({total})")
+            })
+    }
+}
+
+macro_rules! extension_specs {
+    ($($variant:ident => $policy:ident),+ $(,)?) => {
+        /// A Fulcio extension policy, keyed by the extension's name.
+        #[derive(Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum ExtensionSpec {
+            $($variant(CompiledMatcher)),+
+        }
+
+        impl ExtensionSpec {
+            fn compile(self) -> BoxedPolicy {
+                match self {
+                    $(Self::$variant(matcher) => Box::new(policy::$policy::with_matcher(matcher.0))),+
+                }
+            }
+        }
+    };
+}
+
+extension_specs! {
+    Issuer => OIDCIssuer,
+    IssuerV2 => OIDCIssuerV2,
+    GithubWorkflowTrigger => GitHubWorkflowTrigger,
+    GithubWorkflowSha => GitHubWorkflowSHA,
+    GithubWorkflowName => GitHubWorkflowName,
+    GithubWorkflowRepository => GitHubWorkflowRepository,
+    GithubWorkflowRef => GitHubWorkflowRef,
+    BuildSignerUri => BuildSignerURI,
+    BuildSignerDigest => BuildSignerDigest,
+    RunnerEnvironment => RunnerEnvironment,
+    SourceRepositoryUri => SourceRepositoryURI,
+    SourceRepositoryDigest => SourceRepositoryDigest,
+    SourceRepositoryRef => SourceRepositoryRef,
+    SourceRepositoryIdentifier => SourceRepositoryIdentifier,
+    SourceRepositoryOwnerUri => SourceRepositoryOwnerURI,
+    SourceRepositoryOwnerIdentifier => SourceRepositoryOwnerIdentifier,
+    BuildConfigUri => BuildConfigURI,
+    BuildConfigDigest => BuildConfigDigest,
+    BuildTrigger => BuildTrigger,
+    RunInvocationUri => RunInvocationURI,
+    SourceRepositoryVisibilityAtSigning => SourceRepositoryVisibilityAtSigning,
+}
+
+/// A [`Matcher`], compiled as soon as it is deserialized.
+#[derive(Deserialize)]
+#[serde(try_from = "MatcherSpec")]
+struct CompiledMatcher(Matcher);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MatcherSpec {
+    Exact(String),
+    Pattern(PatternSpec),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+enum PatternSpec {
+    Exact(String),
+    Prefix(String),
+    Glob(String),
+    Regex(String),
+}
+
+impl TryFrom<MatcherSpec> for CompiledMatcher {
+    type Error = regex::Error;
+
+    fn try_from(spec: MatcherSpec) -> Result<Self, Self::Error> {
+        let matcher = match spec {
+            MatcherSpec::Exact(value) | MatcherSpec::Pattern(PatternSpec::Exact(value)) => {
+                Matcher::Exact(value)
+            }
+            MatcherSpec::Pattern(PatternSpec::Prefix(prefix)) => Matcher::Prefix(prefix),
+            MatcherSpec::Pattern(PatternSpec::Glob(pattern)) => Matcher::glob(pattern)?,
+            MatcherSpec::Pattern(PatternSpec::Regex(pattern)) => Matcher::regex(pattern)?,
+        };
+
+        Ok(Self(matcher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::bundle::verify::{SignerIdentity, SigningTime, VerifiedLogEntry};
+
+    const DOCUMENT: &str = r#"{
+        "certificate": {
+            "all_of": [
+                {
+                    "identity": {
+                        "san": { "glob": "https://github.com/our-org/*/.github/workflows/release.yml@refs/tags/**" },
+                        "issuer": "https://token.actions.githubusercontent.com"
+                    }
+                },
+                { "extension": { "source_repository_visibility_at_signing": "public" } },
+                { "not": { "extension": { "runner_environment": "self-hosted" } } },
+                {
+                    "threshold": {
+                        "threshold": 1,
+                        "policies": [
+                            { "extension": { "build_trigger": "push" } },
+                            { "extension": { "source_repository_ref": { "prefix": "refs/tags/" } } }
+                        ]
+                    }
+                }
+            ]
+        },
+        "min_transparency_log_entries": 1,
+        "min_signed_timestamps": 1,
+        "signing_time": { "not_before": "2024-01-01T00:00:00Z" }
+    }"#;
+
+    /// Returns an outcome with the given signing times, and an entry in each of the logs
+    /// identified by `log_ids`.
+    fn outcome(signing_times: &[(i64, SigningTimeSource)], log_ids: &[u8]) -> VerificationOutcome {
+        VerificationOutcome {
+            signer: SignerIdentity::PublicKey { hint: "key".into() },
+            signing_times: signing_times
+                .iter()
+                .map(|&(time, source)| SigningTime {
+                    time: Utc.timestamp_opt(time, 0).unwrap(),
+                    source,
+                })
+                .collect(),
+            log_entries: log_ids
+                .iter()
+                .enumerate()
+                .map(|(log_index, &log_id)| VerifiedLogEntry {
+                    log_index: log_index as i64,
+                    log_id: vec![log_id; 32],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn from_json() {
+        let document = PolicyDocument::from_json(DOCUMENT).unwrap();
+        assert_eq!(document.min_transparency_log_entries, 1);
+        assert_eq!(document.min_signed_timestamps, 1);
+        assert!(document.signing_time.is_some());
+    }
+
+    #[test]
+    fn verify_outcome() {
+        let document = PolicyDocument::from_json(DOCUMENT).unwrap();
+        // 2024-06-01T00:00:00Z, and 2023-06-01T00:00:00Z.
+        let (in_window, before_window) = (1717200000, 1685577600);
+
+        assert!(document
+            .verify_outcome(&outcome(
+                &[
+                    (in_window, SigningTimeSource::TimestampAuthority),
+                    (in_window, SigningTimeSource::TransparencyLog),
+                ],
+                &[1],
+            ))
+            .is_ok());
+        assert!(matches!(
+            document.verify_outcome(&outcome(
+                &[(in_window, SigningTimeSource::TransparencyLog)],
+                &[1]
+            )),
+            Err(VerificationError::InsufficientEvidence {
+                required: 1,
+                actual: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            document.verify_outcome(&outcome(
+                &[
+                    (in_window, SigningTimeSource::TimestampAuthority),
+                    (before_window, SigningTimeSource::TransparencyLog),
+                ],
+                &[1],
+            )),
+            Err(VerificationError::Policy(
+                PolicyError::SigningTimeOutsideWindow(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_outcome_counts_distinct_logs() {
+        let document = PolicyDocument::from_json(
+            r#"{
+                "certificate": { "extension": { "build_trigger": "push" } },
+                "min_transparency_log_entries": 2
+            }"#,
+        )
+        .unwrap();
+
+        assert!(document.verify_outcome(&outcome(&[], &[1, 2])).is_ok());
+        // Several entries in a single log, or signing times without log entries, do not count.
+        for outcome in [
+            outcome(&[], &[1, 1]),
+            outcome(
+                &[
+                    (1717200000, SigningTimeSource::TransparencyLog),
+                    (1717200000, SigningTimeSource::TransparencyLog),
+                ],
+                &[1],
+            ),
+        ] {
+            assert!(matches!(
+                document.verify_outcome(&outcome),
+                Err(VerificationError::InsufficientEvidence {
+                    required: 2,
+                    actual: 1,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn errors_point_at_field() {
+        let error = |json: &str| {
+            let err = PolicyDocument::from_json(json).err().unwrap();
+            (err.path, err.message)
+        };
+
+        let (path, message) = error(
+            r#"{ "certificate": { "any_of": [
+                { "extension": { "build_trigger": "push" } },
+                { "identity": { "san": { "regex": "(" }, "issuer": "https://example.com" } }
+            ] } }"#,
+        );
+        assert_eq!(path, "certificate.any_of[1].identity.san");
+        assert!(message.contains("regex"), "{message}");
+
+        let (path, _) = error(r#"{ "certificate": { "extension": { "build_triger": "push" } } }"#);
+        assert_eq!(path, "certificate.extension");
+
+        let (path, message) = error(
+            r#"{ "certificate": { "not": { "threshold": {
+                "threshold": 2,
+                "policies": [{ "extension": { "build_trigger": "push" } }]
+            } } } }"#,
+        );
+        assert_eq!(path, "certificate.not.threshold");
+        assert!(message.contains("threshold"), "{message}");
+
+        let (path, _) = error(
+            r#"{
+                "certificate": { "all_of": [] },
+                "min_signed_timestamps": 1
+            }"#,
+        );
+        assert_eq!(path, "certificate.all_of");
+
+        let (path, message) = error(r#"{ "certificate": { "not": { "any_of": [] } } }"#);
+        assert_eq!(path, "certificate.not.any_of");
+        assert!(message.contains("any_of"), "{message}");
+
+        let (path, _) = error(
+            r#"{
+                "certificate": { "extension": { "build_trigger": "push" } },
+                "signing_time": { "not_before": "2024-01-01T00:00:00Z", "not_after": "2023-01-01T00:00:00Z" }
+            }"#,
+        );
+        assert_eq!(path, "signing_time");
+    }
+}
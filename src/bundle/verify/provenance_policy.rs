@@ -0,0 +1,361 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification policies over SLSA Provenance predicates.
+//!
+//! These nest in the [`super::policy`] combinators, and are checked by
+//! [`Verifier::verify_provenance`](super::Verifier::verify_provenance) once the attestation is
+//! verified.
+
+use crate::bundle::attestation::slsa::Provenance;
+
+use super::policy::{AllOf, AnyOf, Matcher, Not, PolicyError, PolicyResult, Threshold};
+
+/// A check over a verified SLSA Provenance predicate.
+pub trait ProvenancePolicy {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult;
+}
+
+/// An owned provenance policy, as held by combinators.
+pub type BoxedProvenancePolicy = Box<dyn ProvenancePolicy + Send + Sync>;
+
+impl<P: ProvenancePolicy + ?Sized> ProvenancePolicy for AnyOf<P> {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        self.evaluate(|p| p.verify(provenance))
+    }
+}
+
+impl<P: ProvenancePolicy + ?Sized> ProvenancePolicy for AllOf<P> {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        self.evaluate(|p| p.verify(provenance))
+    }
+}
+
+impl<P: ProvenancePolicy + ?Sized> ProvenancePolicy for Threshold<P> {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        self.evaluate(|p| p.verify(provenance))
+    }
+}
+
+impl<P: ProvenancePolicy + ?Sized> ProvenancePolicy for Not<P> {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        self.evaluate(|p| p.verify(provenance))
+    }
+}
+
+fn check(field: &str, matcher: &Matcher, actual: &str) -> PolicyResult {
+    if !matcher.matches(actual) {
+        return Err(PolicyError::ProvenanceCheckFailed {
+            field: field.to_owned(),
+            expected: matcher.to_string(),
+            actual: actual.to_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Normalizes a repository URI for comparison, e.g. `git+https://github.com/org/repo.git` and
+/// `https://github.com/org/repo` both become `github.com/org/repo`.
+fn normalize_repository(repository: &str) -> &str {
+    let repository = repository.strip_prefix("git+").unwrap_or(repository);
+    let repository = repository
+        .split_once("://")
+        .map_or(repository, |(_, rest)| rest);
+    let repository = repository.trim_end_matches('/');
+
+    repository.strip_suffix(".git").unwrap_or(repository)
+}
+
+/// Checks the provenance's builder ID.
+pub struct BuilderId(pub Matcher);
+
+impl BuilderId {
+    /// Requires the builder ID to be exactly `id`.
+    pub fn new<S: AsRef<str>>(id: S) -> Self {
+        Self(Matcher::Exact(id.as_ref().to_owned()))
+    }
+
+    /// Requires the builder ID to match `matcher`.
+    pub fn with_matcher(matcher: Matcher) -> Self {
+        Self(matcher)
+    }
+}
+
+impl ProvenancePolicy for BuilderId {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        check("builder ID", &self.0, provenance.builder_id())
+    }
+}
+
+/// Checks the provenance's build type.
+pub struct BuildType(pub Matcher);
+
+impl BuildType {
+    /// Requires the build type to be exactly `build_type`.
+    pub fn new<S: AsRef<str>>(build_type: S) -> Self {
+        Self(Matcher::Exact(build_type.as_ref().to_owned()))
+    }
+
+    /// Requires the build type to match `matcher`.
+    pub fn with_matcher(matcher: Matcher) -> Self {
+        Self(matcher)
+    }
+}
+
+impl ProvenancePolicy for BuildType {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        check("build type", &self.0, provenance.build_type())
+    }
+}
+
+/// Checks the repository the build was started from.
+///
+/// The scheme, any `git+` prefix and any `.git` suffix are ignored, so that
+/// `github.com/org/repo` matches `https://github.com/org/repo`.
+pub struct SourceRepository(pub Matcher);
+
+impl SourceRepository {
+    /// Requires the repository to be `repository`, once both are normalized.
+    pub fn new<S: AsRef<str>>(repository: S) -> Self {
+        Self(Matcher::Exact(
+            normalize_repository(repository.as_ref()).to_owned(),
+        ))
+    }
+
+    /// Requires the normalized repository, e.g. `github.com/org/repo`, to match `matcher`.
+    pub fn with_matcher(matcher: Matcher) -> Self {
+        Self(matcher)
+    }
+}
+
+impl ProvenancePolicy for SourceRepository {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        let source = provenance
+            .source()
+            .ok_or_else(|| PolicyError::ProvenanceFieldMissing("source repository".to_owned()))?;
+
+        check(
+            "source repository",
+            &self.0,
+            normalize_repository(&source.repository),
+        )
+    }
+}
+
+/// Checks the git ref the build was started from, e.g. `refs/heads/main`.
+pub struct SourceRef(pub Matcher);
+
+impl SourceRef {
+    /// Requires the git ref to be exactly `git_ref`.
+    pub fn new<S: AsRef<str>>(git_ref: S) -> Self {
+        Self(Matcher::Exact(git_ref.as_ref().to_owned()))
+    }
+
+    /// Requires the git ref to match `matcher`.
+    pub fn with_matcher(matcher: Matcher) -> Self {
+        Self(matcher)
+    }
+}
+
+impl ProvenancePolicy for SourceRef {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        let git_ref = provenance
+            .source()
+            .and_then(|source| source.git_ref)
+            .ok_or_else(|| PolicyError::ProvenanceFieldMissing("source ref".to_owned()))?;
+
+        check("source ref", &self.0, &git_ref)
+    }
+}
+
+/// Requires the build to have resolved a dependency with the given URI, and optionally with the
+/// given digest.
+pub struct ResolvedDependency {
+    uri: String,
+    digest: Option<(String, String)>,
+}
+
+impl ResolvedDependency {
+    pub fn new<S: AsRef<str>>(uri: S) -> Self {
+        Self {
+            uri: uri.as_ref().to_owned(),
+            digest: None,
+        }
+    }
+
+    /// Additionally requires the dependency's `algorithm` digest to equal the hex-encoded
+    /// `digest`.
+    pub fn with_digest<A: AsRef<str>, D: AsRef<str>>(mut self, algorithm: A, digest: D) -> Self {
+        self.digest = Some((algorithm.as_ref().to_owned(), digest.as_ref().to_owned()));
+        self
+    }
+}
+
+impl ProvenancePolicy for ResolvedDependency {
+    fn verify(&self, provenance: &Provenance) -> PolicyResult {
+        let found = provenance
+            .resolved_dependencies()
+            .iter()
+            .filter(|dependency| dependency.uri == self.uri)
+            .any(|dependency| match &self.digest {
+                Some((algorithm, digest)) => dependency
+                    .digest
+                    .get(algorithm)
+                    .is_some_and(|actual| actual.eq_ignore_ascii_case(digest)),
+                None => true,
+            });
+
+        if !found {
+            let expected = match &self.digest {
+                Some((algorithm, digest)) => format!("{} ({algorithm}:{digest})", self.uri),
+                None => self.uri.clone(),
+            };
+            return Err(PolicyError::ProvenanceCheckFailed {
+                field: "resolved dependencies".to_owned(),
+                expected,
+                actual: provenance
+                    .resolved_dependencies()
+                    .iter()
+                    .map(|dependency| dependency.uri.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::attestation::slsa::tests::{provenance_v0_2, provenance_v1};
+
+    fn all_of(policies: Vec<BoxedProvenancePolicy>) -> AllOf<dyn ProvenancePolicy + Send + Sync> {
+        AllOf::new(policies).unwrap()
+    }
+
+    #[test]
+    fn provenance_policies_pass() {
+        let provenance = provenance_v1();
+        let policy = all_of(vec![
+            Box::new(BuilderId::new(
+                "https://github.com/actions/runner/github-hosted",
+            )),
+            Box::new(BuildType::new(
+                "https://actions.github.io/buildtypes/workflow/v1",
+            )),
+            Box::new(SourceRepository::new("github.com/sigstore/sigstore-rs")),
+            Box::new(SourceRef::new("refs/heads/main")),
+            Box::new(
+                ResolvedDependency::new(
+                    "git+https://github.com/sigstore/sigstore-rs@refs/heads/main",
+                )
+                .with_digest("gitCommit", "6AD5D5B4A9A0ED0E3CC8B1D9D5CBD3D6D1F2C9B8"),
+            ),
+        ]);
+        assert!(policy.verify(&provenance).is_ok());
+
+        let provenance = provenance_v0_2();
+        let policy = all_of(vec![
+            Box::new(SourceRepository::new(
+                "https://github.com/sigstore/sigstore-rs.git",
+            )),
+            Box::new(SourceRef::new("refs/tags/v0.9.0")),
+        ]);
+        assert!(policy.verify(&provenance).is_ok());
+    }
+
+    #[test]
+    fn provenance_policies_fail() {
+        let provenance = provenance_v1();
+        let policy = all_of(vec![
+            Box::new(BuilderId::new("https://example.com/builder")),
+            Box::new(SourceRepository::new("github.com/sigstore/sigstore-python")),
+            Box::new(SourceRef::new("refs/heads/main")),
+            Box::new(
+                ResolvedDependency::new(
+                    "git+https://github.com/sigstore/sigstore-rs@refs/heads/main",
+                )
+                .with_digest("gitCommit", "00"),
+            ),
+        ]);
+
+        match policy.verify(&provenance) {
+            Err(PolicyError::AllOf { total, results }) => {
+                assert_eq!(total, 4);
+                assert_eq!(results.failed.len(), 3);
+                assert!(matches!(
+                    results.failed[0],
+                    (0, PolicyError::ProvenanceCheckFailed { .. })
+                ));
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[test]
+    fn provenance_policies_compose() {
+        let provenance = provenance_v1();
+
+        assert!(BuilderId::with_matcher(
+            Matcher::glob("https://github.com/actions/runner/*").unwrap()
+        )
+        .verify(&provenance)
+        .is_ok());
+        assert!(
+            SourceRepository::with_matcher(Matcher::Prefix("github.com/sigstore/".into()))
+                .verify(&provenance)
+                .is_ok()
+        );
+        assert!(matches!(
+            SourceRepository::with_matcher(Matcher::Prefix("github.com/other-org/".into()))
+                .verify(&provenance),
+            Err(PolicyError::ProvenanceCheckFailed { .. })
+        ));
+
+        let policy = AnyOf::new(vec![
+            Box::new(Not::new(Box::new(SourceRef::new("refs/heads/main"))))
+                as BoxedProvenancePolicy,
+            Box::new(
+                Threshold::new(
+                    1,
+                    vec![
+                        Box::new(BuilderId::new("https://example.com/builder"))
+                            as BoxedProvenancePolicy,
+                        Box::new(SourceRef::with_matcher(
+                            Matcher::regex("^refs/heads/").unwrap(),
+                        )),
+                    ],
+                )
+                .unwrap(),
+            ),
+        ]);
+        assert!(policy.verify(&provenance).is_ok());
+    }
+
+    #[test]
+    fn provenance_field_missing() {
+        let mut provenance = provenance_v0_2();
+        if let Provenance::V0_2(p) = &mut provenance {
+            p.invocation = None;
+        }
+
+        assert!(matches!(
+            SourceRepository::new("github.com/sigstore/sigstore-rs").verify(&provenance),
+            Err(PolicyError::ProvenanceFieldMissing(_))
+        ));
+    }
+}
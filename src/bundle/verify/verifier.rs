@@ -14,43 +14,118 @@
 
 //! Verifiers: async and blocking.
 
-use std::io::{self, Read};
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    time::Duration,
+};
 
-use sha2::{Digest, Sha256};
+use base64::{engine::general_purpose::STANDARD as base64, Engine as _};
+use chrono::{DateTime, Utc};
+use const_oid::AssociatedOid;
+use json_syntax::Print;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use sigstore_protobuf_specs::dev::sigstore::{
+    bundle::v1::bundle,
+    common::v1::{HashAlgorithm, MessageSignature},
+    rekor::v1::{InclusionProof, TransparencyLogEntry},
+};
 use tokio::io::{AsyncRead, AsyncReadExt};
 use tracing::debug;
-use webpki::types::{CertificateDer, UnixTime};
-use x509_cert::der::Encode;
+use webpki::{
+    types::{CertificateDer, UnixTime},
+    EndEntityCert, VerifiedPath,
+};
+use x509_cert::{der::Encode, Certificate};
 
 use crate::{
-    bundle::Bundle,
+    bundle::{
+        attestation::{slsa::Provenance, Statement},
+        dsse::{self, DssePayload},
+        Bundle, DigestAlgorithm,
+    },
     crypto::{
         keyring::Keyring,
-        transparency::{verify_sct, CertificateEmbeddedSCT},
+        merkle::{self, MerkleProofError},
+        timestamp::TimestampVerifier,
+        transparency::{verified_issuer_id, verify_sct, CertificateEmbeddedSCT},
         CertificatePool, CosignVerificationKey, Signature,
     },
     errors::Result as SigstoreResult,
-    rekor::apis::configuration::Configuration as RekorConfiguration,
-    trust::TrustRoot,
+    rekor::{
+        apis::{configuration::Configuration as RekorConfiguration, index_api, tlog_api},
+        models::{checkpoint::SignedCheckpoint, log_entry::Verification, SearchIndex},
+    },
+    trust::{TrustRoot, ValidityPeriod},
 };
 
 #[cfg(feature = "sigstore-trust-root")]
 use crate::trust::sigstore::SigstoreTrustRoot;
 
 use super::{
-    models::{CertificateErrorKind, CheckedBundle, SignatureErrorKind},
+    models::{
+        check_threshold, distinct_logs, BundleErrorKind, CertificateErrorKind, CheckedBundle,
+        SignatureErrorKind, SignedContent, SignerMaterial, TransparencyErrorKind, DISTINCT_LOGS,
+        SIGNED_TIMESTAMPS, SIGNING_TIMES,
+    },
     policy::VerificationPolicy,
-    VerificationError, VerificationResult,
+    provenance_policy::ProvenancePolicy,
+    CertificateIdentity, PolicyDocument, SignerIdentity, SigningTime, SigningTimeSource,
+    VerificationError, VerificationOutcome, VerificationResult, Verified, VerifiedLogEntry,
 };
 
 /// An asynchronous Sigstore verifier.
 ///
 /// For synchronous usage, see [`Verifier`].
 pub struct Verifier {
-    #[allow(dead_code)]
     rekor_config: RekorConfiguration,
-    cert_pool: CertificatePool,
+    /// Trusted Fulcio certificate authorities, each with the period during which it issued
+    /// certificates.
+    fulcio_authorities: Vec<(ValidityPeriod, CertificatePool)>,
     ctfe_keyring: Keyring,
+    rekor_keyring: Keyring,
+    timestamp_verifier: TimestampVerifier,
+    trusted_keys: HashMap<String, CosignVerificationKey>,
+    config: VerifierConfig,
+}
+
+/// How much transparency evidence a [`Verifier`] requires of a bundle.
+///
+/// The defaults match the checks of other Sigstore clients: one transparency log entry and one
+/// SCT, with no signed timestamps. Raising the thresholds avoids trusting a single log operator.
+/// Evidence that is present in the bundle is always verified, whether or not it is required.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierConfig {
+    /// The number of transparency log entries, from distinct logs, that a bundle must carry.
+    pub tlog_threshold: usize,
+    /// The number of RFC 3161 signed timestamps that a bundle must carry.
+    pub timestamp_threshold: usize,
+    /// The number of valid SCTs, from distinct certificate transparency logs, that a signing
+    /// certificate must embed. SCTs are not checked when this is zero.
+    pub sct_threshold: usize,
+    /// Whether the integrated times of transparency log entries count toward
+    /// `timestamp_threshold`.
+    pub integrated_time_as_timestamp: bool,
+}
+
+impl Default for VerifierConfig {
+    fn default() -> Self {
+        Self {
+            tlog_threshold: 1,
+            timestamp_threshold: 0,
+            sct_threshold: 1,
+            integrated_time_as_timestamp: false,
+        }
+    }
+}
+
+/// A bundle's signer, once verified.
+struct VerifiedSigner {
+    identity: SignerIdentity,
+    key: CosignVerificationKey,
+    /// The DER encoding of the signing certificate or public key, as recorded in the log entry.
+    der: Vec<u8>,
 }
 
 impl Verifier {
@@ -61,30 +136,134 @@ impl Verifier {
         rekor_config: RekorConfiguration,
         trust_repo: R,
     ) -> SigstoreResult<Self> {
-        let cert_pool = CertificatePool::from_certificates(trust_repo.fulcio_certs()?, [])?;
-        let ctfe_keyring = Keyring::new(trust_repo.ctfe_keys()?)?;
+        let fulcio_authorities = trust_repo
+            .fulcio_authorities()?
+            .into_iter()
+            .map(|ca| {
+                Ok((
+                    ca.valid_for,
+                    CertificatePool::from_certificates(ca.certs, [])?,
+                ))
+            })
+            .collect::<SigstoreResult<_>>()?;
+        let ctfe_keyring = Keyring::from_log_keys(trust_repo.ctfe_log_keys()?)?;
+        let rekor_keyring = Keyring::from_log_keys(trust_repo.rekor_log_keys()?)?;
+        let timestamp_verifier = TimestampVerifier::new(trust_repo.tsa_certs()?)?;
 
         Ok(Self {
             rekor_config,
-            cert_pool,
+            fulcio_authorities,
             ctfe_keyring,
+            rekor_keyring,
+            timestamp_verifier,
+            trusted_keys: HashMap::new(),
+            config: VerifierConfig::default(),
         })
     }
 
+    /// Trusts the given long-lived public keys, keyed by the hint that identifies them in
+    /// bundles.
+    ///
+    /// Bundles whose verification material is a public key are verified against these keys,
+    /// including the transparency log checks. A bundle's hint may also be a key's ID, the
+    /// hex-encoded SHA-256 digest of its DER-encoded SPKI, or be empty, in which case the key
+    /// that verifies the signature is used. The [`VerificationPolicy`] passed to verification
+    /// methods applies to signing certificates only, and is not evaluated for such bundles:
+    /// trusting a key is a statement about the signer's identity.
+    pub fn with_trusted_keys<I>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = (String, CosignVerificationKey)>,
+    {
+        self.trusted_keys.extend(keys);
+        self
+    }
+
+    /// Sets the transparency evidence that bundles must carry, in place of the defaults of
+    /// [`VerifierConfig`].
+    pub fn with_config(mut self, config: VerifierConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Verifies an input digest against the given Sigstore Bundle, ensuring conformance to the
     /// provided [`VerificationPolicy`].
-    pub async fn verify_digest<P>(
+    ///
+    /// The digest algorithm must be SHA-256, SHA-384 or SHA-512, and must match the algorithm
+    /// recorded in the bundle and its transparency log entry.
+    pub async fn verify_digest<D, P>(
         &self,
-        input_digest: Sha256,
+        input_digest: D,
         bundle: Bundle,
         policy: &P,
         offline: bool,
     ) -> VerificationResult
     where
-        P: VerificationPolicy,
+        D: Digest + AssociatedOid,
+        P: VerificationPolicy + ?Sized,
+    {
+        let digest_algorithm = DigestAlgorithm::from_oid(D::OID)
+            .ok_or_else(|| SignatureErrorKind::DigestAlgorithmUnsupported(D::OID.to_string()))?;
+
+        self.verify_message_digest(
+            digest_algorithm,
+            &input_digest.finalize(),
+            bundle,
+            policy,
+            offline,
+        )
+        .await
+    }
+
+    /// Verifies a precomputed input digest against the given Sigstore Bundle, ensuring
+    /// conformance to the provided [`VerificationPolicy`].
+    ///
+    /// This runs the same checks as [`Verifier::verify_digest`] when only the finalized digest
+    /// is at hand, such as an OCI layer digest or an entry from a checksum file.
+    pub async fn verify_digest_bytes<P>(
+        &self,
+        digest_algorithm: DigestAlgorithm,
+        input_digest: &[u8],
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> VerificationResult
+    where
+        P: VerificationPolicy + ?Sized,
+    {
+        if input_digest.len() != digest_algorithm.output_size() {
+            return Err(SignatureErrorKind::DigestMalformed(digest_algorithm))?;
+        }
+
+        self.verify_message_digest(digest_algorithm, input_digest, bundle, policy, offline)
+            .await
+    }
+
+    /// Verifies an input digest computed with `digest_algorithm` against the given Sigstore
+    /// Bundle, ensuring conformance to the provided [`VerificationPolicy`].
+    async fn verify_message_digest<P>(
+        &self,
+        digest_algorithm: DigestAlgorithm,
+        input_digest: &[u8],
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> VerificationResult
+    where
+        P: VerificationPolicy + ?Sized,
     {
-        let input_digest = input_digest.finalize();
         let materials: CheckedBundle = bundle.try_into()?;
+        let SignedContent::MessageSignature(signature) = &materials.content else {
+            return Err(BundleErrorKind::DsseUnexpected)?;
+        };
+
+        // The bundle's message digest is optional, but must match the input digest if present.
+        if let Some(message_digest) = &signature.message_digest {
+            if message_digest.algorithm() != HashAlgorithm::from(digest_algorithm)
+                || message_digest.digest != input_digest
+            {
+                return Err(SignatureErrorKind::DigestMismatch)?;
+            }
+        }
 
         // In order to verify an artifact, we need to achieve the following:
         //
@@ -92,24 +271,294 @@ impl Verifier {
         //    chain and that the signing certificate was valid at the time
         //    of signing.
         // 2) Verify that the signing certificate belongs to the signer.
+        //    Bundles signed with a public key skip 1) and 2): the key must
+        //    instead be one of the verifier's trusted keys.
         // 3) Verify that the artifact signature was signed by the public key in the
         //    signing certificate.
         // 4) Verify that the Rekor entry is consistent with the other signing
         //    materials (preventing CVE-2022-36056)
-        // 5) Verify the inclusion proof supplied by Rekor for this artifact,
-        //    if we're doing online verification.
-        // 6) Verify the Signed Entry Timestamp (SET) supplied by Rekor for this
+        // 5) Verify the Signed Entry Timestamp (SET) supplied by Rekor for this
         //    artifact.
+        // 6) Verify the inclusion proof supplied by Rekor for this artifact,
+        //    if one is present.
         // 7) Verify that the signing certificate was valid at the time of
-        //    signing by comparing the expiry against the integrated timestamp.
+        //    signing by comparing the expiry against the integrated timestamp,
+        //    and against any signed timestamps.
+
+        // 1), 2) and 3), where RSA signatures are padded with the input digest's algorithm
+        let signer = self.verify_signer(&materials, policy, |key| {
+            key.with_digest_algorithm(digest_algorithm)
+                .verify_prehash(Signature::Raw(&signature.signature), input_digest)
+        })?;
+        debug!("signature corresponds to public key");
+
+        // 4) Verify that the Rekor entries are consistent with the other signing
+        //    materials
+        let log_entries = materials
+            .tlog_entries(digest_algorithm, input_digest, &signer.der)
+            .ok_or(SignatureErrorKind::Transparency)?;
+        debug!("log entries are consistent with other materials");
+
+        // 5), 6) and 7)
+        let artifact_hash = format!("{digest_algorithm}:{}", hex::encode(input_digest));
+        let outcome = self
+            .verify_transparency(
+                &materials,
+                signer.identity,
+                &log_entries,
+                &artifact_hash,
+                offline,
+            )
+            .await?;
+
+        debug!("successfully verified!");
+        Ok(outcome)
+    }
+
+    /// Verifies a Sigstore Bundle containing a DSSE envelope, ensuring conformance to the provided
+    /// [`VerificationPolicy`].
+    ///
+    /// On success, returns the envelope's payload along with the verification's outcome. The
+    /// payload is not interpreted: callers are responsible for checking its type and contents.
+    pub async fn verify_dsse<P>(
+        &self,
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> Result<Verified<DssePayload>, VerificationError>
+    where
+        P: VerificationPolicy + ?Sized,
+    {
+        let materials: CheckedBundle = bundle.try_into()?;
+        let SignedContent::DsseEnvelope(envelope) = &materials.content else {
+            return Err(BundleErrorKind::DsseMissing)?;
+        };
+
+        // The steps are the same as in `verify_digest`, except that the signature is over the
+        // envelope's pre-authentication encoding and the Rekor entry is a `dsse` or `intoto`
+        // entry.
+
+        // 1), 2) and 3), where the signature is over the envelope's pre-authentication encoding
+        let [envelope_signature] = &envelope.signatures[..] else {
+            return Err(BundleErrorKind::DsseSignatureCount(
+                envelope.signatures.len(),
+            ))?;
+        };
+        let pae = dsse::pae(&envelope.payload_type, &envelope.payload);
+        let signer = self.verify_signer(&materials, policy, |key| {
+            key.verify_signature(Signature::Raw(&envelope_signature.sig), &pae)
+        })?;
+        debug!("envelope signature corresponds to public key");
+
+        // 4) Verify that the Rekor entries are consistent with the envelope
+        let log_entries = materials
+            .dsse_tlog_entries(&signer.der)
+            .ok_or(SignatureErrorKind::Transparency)?;
+        debug!("log entries are consistent with other materials");
+
+        // 5), 6) and 7)
+        let artifact_hash = format!("sha256:{}", hex::encode(Sha256::digest(&envelope.payload)));
+        let outcome = self
+            .verify_transparency(
+                &materials,
+                signer.identity,
+                &log_entries,
+                &artifact_hash,
+                offline,
+            )
+            .await?;
+
+        debug!("successfully verified!");
+        Ok(Verified {
+            outcome,
+            content: envelope.clone().into(),
+        })
+    }
+
+    /// Verifies an input digest against the given Sigstore Bundle containing an in-toto
+    /// attestation, ensuring conformance to the provided [`VerificationPolicy`].
+    ///
+    /// On success, returns the attestation's statement, one of whose subjects is guaranteed to
+    /// match the input digest, along with the verification's outcome. Its predicate is not
+    /// interpreted: callers are responsible for checking the predicate type and contents.
+    pub async fn verify_attestation_digest<D, P>(
+        &self,
+        input_digest: D,
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> Result<Verified<Statement>, VerificationError>
+    where
+        D: Digest + AssociatedOid,
+        P: VerificationPolicy + ?Sized,
+    {
+        let digest_algorithm = DigestAlgorithm::from_oid(D::OID)
+            .ok_or_else(|| SignatureErrorKind::DigestAlgorithmUnsupported(D::OID.to_string()))?;
+        let input_digest = input_digest.finalize();
+        let verified = self.verify_dsse(bundle, policy, offline).await?;
+
+        let statement = Statement::from_payload(&verified.content)?;
+        statement.verify_subject(digest_algorithm.name(), &input_digest)?;
+        debug!("attestation subject matches input digest");
+
+        Ok(Verified {
+            outcome: verified.outcome,
+            content: statement,
+        })
+    }
+
+    /// Verifies an input against the given Sigstore Bundle containing an in-toto attestation,
+    /// ensuring conformance to the provided [`VerificationPolicy`].
+    ///
+    /// See [`Verifier::verify_attestation_digest`].
+    pub async fn verify_attestation<R, P>(
+        &self,
+        input: R,
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> Result<Verified<Statement>, VerificationError>
+    where
+        R: AsyncRead + Unpin + Send,
+        P: VerificationPolicy + ?Sized,
+    {
+        let hasher: Sha256 = hash_input(input).await?;
+
+        self.verify_attestation_digest(hasher, bundle, policy, offline)
+            .await
+    }
+
+    /// Verifies an input digest against the given Sigstore Bundle containing a SLSA Provenance
+    /// attestation, ensuring that the signing certificate conforms to `policy` and the provenance
+    /// conforms to `provenance_policy`.
+    ///
+    /// On success, returns the verified provenance along with the verification's outcome.
+    pub async fn verify_provenance_digest<D, P, Q>(
+        &self,
+        input_digest: D,
+        bundle: Bundle,
+        policy: &P,
+        provenance_policy: &Q,
+        offline: bool,
+    ) -> Result<Verified<Provenance>, VerificationError>
+    where
+        D: Digest + AssociatedOid,
+        P: VerificationPolicy + ?Sized,
+        Q: ProvenancePolicy + ?Sized,
+    {
+        let verified = self
+            .verify_attestation_digest(input_digest, bundle, policy, offline)
+            .await?;
+
+        let provenance = verified.content.predicate::<Provenance>()?;
+        provenance_policy
+            .verify(&provenance)
+            .map_err(VerificationError::ProvenancePolicy)?;
+        debug!("provenance conforms to policy");
+
+        Ok(Verified {
+            outcome: verified.outcome,
+            content: provenance,
+        })
+    }
+
+    /// Verifies an input against the given Sigstore Bundle containing a SLSA Provenance
+    /// attestation.
+    ///
+    /// See [`Verifier::verify_provenance_digest`].
+    pub async fn verify_provenance<R, P, Q>(
+        &self,
+        input: R,
+        bundle: Bundle,
+        policy: &P,
+        provenance_policy: &Q,
+        offline: bool,
+    ) -> Result<Verified<Provenance>, VerificationError>
+    where
+        R: AsyncRead + Unpin + Send,
+        P: VerificationPolicy + ?Sized,
+        Q: ProvenancePolicy + ?Sized,
+    {
+        let hasher: Sha256 = hash_input(input).await?;
+
+        self.verify_provenance_digest(hasher, bundle, policy, provenance_policy, offline)
+            .await
+    }
+
+    /// Verifies the bundle's signer: either a signing certificate, which must conform to `policy`,
+    /// or a trusted public key. The signer's key must pass `verify_signature`.
+    ///
+    /// Trusted keys are looked up by the bundle's key hint, then by key ID. Bundles with an empty
+    /// hint are matched to the trusted key that verifies their signature.
+    fn verify_signer<P, F>(
+        &self,
+        materials: &CheckedBundle,
+        policy: &P,
+        verify_signature: F,
+    ) -> Result<VerifiedSigner, VerificationError>
+    where
+        P: VerificationPolicy + ?Sized,
+        F: Fn(&CosignVerificationKey) -> SigstoreResult<()>,
+    {
+        let (identity, key) = match &materials.signer {
+            SignerMaterial::Certificate(certificate) => {
+                let signer = self.verify_signing_certificate(certificate, policy)?;
+                verify_signature(&signer.key).map_err(SignatureErrorKind::VerificationFailed)?;
+                return Ok(signer);
+            }
+            SignerMaterial::PublicKey(hint) => match self.trusted_key(hint) {
+                Some((name, key)) => {
+                    verify_signature(key).map_err(SignatureErrorKind::VerificationFailed)?;
+                    (name, key)
+                }
+                None if hint.is_empty() => self
+                    .trusted_keys
+                    .iter()
+                    .find(|(_, key)| verify_signature(key).is_ok())
+                    .ok_or_else(|| SignatureErrorKind::KeyUntrusted(hint.clone()))?,
+                None => return Err(SignatureErrorKind::KeyUntrusted(hint.clone()))?,
+            },
+        };
+        debug!("signing key is trusted");
+
+        let der = key.to_der().map_err(SignatureErrorKind::AlgoUnsupported)?;
+        Ok(VerifiedSigner {
+            identity: SignerIdentity::PublicKey {
+                hint: identity.clone(),
+            },
+            key: key.clone(),
+            der,
+        })
+    }
+
+    /// Looks up a trusted key by the hint it was trusted under, or by its key ID: the
+    /// hex-encoded SHA-256 digest of its DER-encoded SPKI.
+    fn trusted_key(&self, hint: &str) -> Option<(&String, &CosignVerificationKey)> {
+        if let Some(found) = self.trusted_keys.get_key_value(hint) {
+            return Some(found);
+        }
+
+        self.trusted_keys.iter().find(|(_, key)| {
+            key.to_der()
+                .is_ok_and(|der| hex::encode(Sha256::digest(der)).eq_ignore_ascii_case(hint))
+        })
+    }
 
+    /// Verifies that a signing certificate chains to a trusted root and conforms to `policy`.
+    fn verify_signing_certificate<P>(
+        &self,
+        certificate: &Certificate,
+        policy: &P,
+    ) -> Result<VerifiedSigner, VerificationError>
+    where
+        P: VerificationPolicy + ?Sized,
+    {
         // 1) Verify that the signing certificate is signed by the certificate
         //    chain and that the signing certificate was valid at the time
         //    of signing.
-        let tbs_certificate = &materials.certificate.tbs_certificate;
+        let tbs_certificate = &certificate.tbs_certificate;
         let issued_at = tbs_certificate.validity.not_before.to_unix_duration();
-        let cert_der: CertificateDer = materials
-            .certificate
+        let cert_der: CertificateDer = certificate
             .to_der()
             .expect("failed to DER-encode constructed Certificate!")
             .into();
@@ -118,174 +567,983 @@ impl Verifier {
             .map_err(CertificateErrorKind::Malformed)?;
 
         let trusted_chain = self
-            .cert_pool
-            .verify_cert_with_time(&ee_cert, UnixTime::since_unix_epoch(issued_at))
+            .verify_certificate_chain(&ee_cert, issued_at)
             .map_err(CertificateErrorKind::VerificationFailed)?;
 
         debug!("signing certificate chains back to trusted root");
 
-        let sct_context =
-            CertificateEmbeddedSCT::new_with_verified_path(&materials.certificate, &trusted_chain)
-                .map_err(CertificateErrorKind::Sct)?;
-        verify_sct(&sct_context, &self.ctfe_keyring).map_err(CertificateErrorKind::Sct)?;
-        debug!("signing certificate's SCT is valid");
+        let ca_key_id = verified_issuer_id(&trusted_chain).map_err(CertificateErrorKind::Sct)?;
+        let ct_log_ids = self.verify_scts(certificate, &trusted_chain)?;
 
         // 2) Verify that the signing certificate belongs to the signer.
-        policy.verify(&materials.certificate)?;
+        policy.verify(certificate)?;
         debug!("signing certificate conforms to policy");
 
-        // 3) Verify that the signature was signed by the public key in the signing certificate
-        let signing_key: CosignVerificationKey = (&tbs_certificate.subject_public_key_info)
+        let key: CosignVerificationKey = (&tbs_certificate.subject_public_key_info)
             .try_into()
             .map_err(SignatureErrorKind::AlgoUnsupported)?;
 
-        let verify_sig =
-            signing_key.verify_prehash(Signature::Raw(&materials.signature), &input_digest);
-        verify_sig.map_err(SignatureErrorKind::VerificationFailed)?;
+        Ok(VerifiedSigner {
+            identity: SignerIdentity::Certificate(Box::new(CertificateIdentity::new(
+                certificate.clone(),
+                ca_key_id,
+                ct_log_ids,
+            ))),
+            key,
+            der: cert_der.to_vec(),
+        })
+    }
 
-        debug!("signature corresponds to public key");
+    /// Verifies the SCTs embedded in a signing certificate, and returns the IDs of the distinct
+    /// certificate transparency logs that issued the valid ones.
+    ///
+    /// Invalid SCTs, such as those of untrusted logs, only fail verification if too few SCTs are
+    /// left to meet [`VerifierConfig::sct_threshold`].
+    fn verify_scts(
+        &self,
+        certificate: &Certificate,
+        trusted_chain: &VerifiedPath,
+    ) -> Result<Vec<[u8; 32]>, VerificationError> {
+        let required = self.config.sct_threshold;
+        if required == 0 {
+            debug!("no SCTs required, skipping SCT check");
+            return Ok(vec![]);
+        }
 
-        // 4) Verify that the Rekor entry is consistent with the other signing
-        //    materials
-        let log_entry = materials
-            .tlog_entry(offline, &input_digest)
-            .ok_or(SignatureErrorKind::Transparency)?;
-        debug!("log entry is consistent with other materials");
+        let scts = CertificateEmbeddedSCT::all_with_verified_path(certificate, trusted_chain)
+            .map_err(CertificateErrorKind::Sct)?;
+        let mut log_ids = vec![];
+        let mut first_error = None;
+        for sct in &scts {
+            match verify_sct(sct, &self.ctfe_keyring) {
+                Ok(()) if !log_ids.contains(&sct.log_id()) => log_ids.push(sct.log_id()),
+                Ok(()) => debug!("skipping SCT from an already counted log"),
+                Err(err) => {
+                    debug!("skipping invalid SCT: {err}");
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
 
-        // 5) Verify the inclusion proof supplied by Rekor for this artifact,
-        //    if we're doing online verification.
-        // TODO(tnytown): Merkle inclusion; sigstore-rs#285
+        if log_ids.len() < required {
+            if let Some(err) = first_error {
+                return Err(CertificateErrorKind::Sct(err))?;
+            }
+        }
+        check_threshold("SCTs from distinct logs", required, log_ids.len())?;
+        debug!("signing certificate's SCTs are valid");
 
-        // 6) Verify the Signed Entry Timestamp (SET) supplied by Rekor for this
-        //    artifact.
-        // TODO(tnytown) SET verification; sigstore-rs#285
+        Ok(log_ids)
+    }
 
-        // 7) Verify that the signing certificate was valid at the time of
-        //    signing by comparing the expiry against the integrated timestamp.
-        let integrated_time = log_entry.integrated_time as u64;
-        let not_before = tbs_certificate
-            .validity
-            .not_before
-            .to_unix_duration()
-            .as_secs();
-        let not_after = tbs_certificate
-            .validity
-            .not_after
-            .to_unix_duration()
-            .as_secs();
-        if integrated_time < not_before || integrated_time > not_after {
-            return Err(CertificateErrorKind::Expired)?;
-        }
-        debug!("data signed during validity period");
+    /// Verifies a signing certificate issued at `issued_at` against the Fulcio certificate
+    /// authorities that were valid at that time.
+    fn verify_certificate_chain<'a>(
+        &'a self,
+        ee_cert: &'a EndEntityCert<'a>,
+        issued_at: Duration,
+    ) -> Result<VerifiedPath<'a>, webpki::Error> {
+        let time = DateTime::from_timestamp(issued_at.as_secs() as i64, 0)
+            .ok_or(webpki::Error::BadDerTime)?;
 
-        debug!("successfully verified!");
-        Ok(())
+        let mut result = Err(webpki::Error::UnknownIssuer);
+        for (_, cert_pool) in self
+            .fulcio_authorities
+            .iter()
+            .filter(|(valid_for, _)| valid_for.contains(time))
+        {
+            result =
+                cert_pool.verify_cert_with_time(ee_cert, UnixTime::since_unix_epoch(issued_at));
+            if result.is_ok() {
+                break;
+            }
+        }
+
+        result
     }
 
-    /// Verifies an input against the given Sigstore Bundle, ensuring conformance to the provided
-    /// [`VerificationPolicy`].
-    pub async fn verify<R, P>(
+    /// Verifies the bundle's transparency log entries and signed timestamps, checks that they meet
+    /// the verifier's [`VerifierConfig`], and that the signing certificate was valid at each of
+    /// the times they attest to.
+    async fn verify_transparency(
         &self,
-        mut input: R,
-        bundle: Bundle,
-        policy: &P,
+        materials: &CheckedBundle,
+        signer: SignerIdentity,
+        log_entries: &[&TransparencyLogEntry],
+        artifact_hash: &str,
         offline: bool,
-    ) -> VerificationResult
-    where
-        R: AsyncRead + Unpin + Send,
-        P: VerificationPolicy,
-    {
-        // arbitrary buffer size, chosen to be a multiple of the digest size.
-        let mut buf = [0u8; 1024];
-        let mut hasher = Sha256::new();
-
-        loop {
-            match input
-                .read(&mut buf)
-                .await
-                .map_err(VerificationError::Input)?
+    ) -> VerificationResult {
+        let mut verified_entries = Vec::with_capacity(log_entries.len());
+        let mut integrated_times = Vec::with_capacity(log_entries.len());
+        for log_entry in log_entries {
+            // Only a verified SET vouches for the entry's integrated time.
+            if let Some(time) = self
+                .verify_log_entry(log_entry, artifact_hash, offline)
+                .await?
             {
-                0 => break,
-                n => hasher.update(&buf[..n]),
+                integrated_times.push(time);
             }
+            verified_entries.push(VerifiedLogEntry {
+                log_index: log_entry.log_index,
+                log_id: log_entry
+                    .log_id
+                    .as_ref()
+                    .map(|id| id.key_id.clone())
+                    .unwrap_or_default(),
+            });
         }
+        check_threshold(
+            DISTINCT_LOGS,
+            self.config.tlog_threshold,
+            distinct_logs(&verified_entries),
+        )?;
 
-        self.verify_digest(hasher, bundle, policy, offline).await
-    }
-}
+        let mut signing_times = self.verify_signed_timestamps(materials)?;
+        let mut timestamps = signing_times.len();
+        if self.config.integrated_time_as_timestamp {
+            timestamps += integrated_times.len();
+        }
+        check_threshold(
+            SIGNED_TIMESTAMPS,
+            self.config.timestamp_threshold,
+            timestamps,
+        )?;
 
-impl Verifier {
-    /// Constructs an [`Verifier`] against the public-good trust root.
-    #[cfg(feature = "sigstore-trust-root")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "sigstore-trust-root")))]
-    pub async fn production() -> SigstoreResult<Verifier> {
-        let updater = SigstoreTrustRoot::new(None).await?;
+        // 7) Verify that the signing certificate was valid at the time of
+        //    signing by comparing the expiry against the integrated timestamps,
+        //    and against the time attested by each signed timestamp.
+        signing_times.extend(integrated_times.into_iter().map(|time| SigningTime {
+            time,
+            source: SigningTimeSource::TransparencyLog,
+        }));
 
-        Verifier::new(Default::default(), updater)
-    }
-}
+        if let SignerMaterial::Certificate(certificate) = &materials.signer {
+            // Trusted public keys do not expire, but certificates must be checked at least once.
+            check_threshold(SIGNING_TIMES, 1, signing_times.len())?;
 
-pub mod blocking {
-    use super::{Verifier as AsyncVerifier, *};
+            let tbs_certificate = &certificate.tbs_certificate;
+            let not_before = tbs_certificate.validity.not_before.to_unix_duration();
+            let not_after = tbs_certificate.validity.not_after.to_unix_duration();
+            if signing_times.iter().any(|signing_time| {
+                let time = signing_time.time.timestamp();
+                time < not_before.as_secs() as i64 || time > not_after.as_secs() as i64
+            }) {
+                return Err(CertificateErrorKind::Expired)?;
+            }
+            debug!("data signed during validity period");
+        }
 
-    /// A synchronous Sigstore verifier.
-    pub struct Verifier {
-        inner: AsyncVerifier,
-        rt: tokio::runtime::Runtime,
+        Ok(VerificationOutcome {
+            signer,
+            signing_times,
+            log_entries: verified_entries,
+        })
     }
 
-    impl Verifier {
-        /// Constructs a synchronous Sigstore verifier.
-        ///
-        /// For verifications against the public-good trust root, use [`Verifier::production()`].
-        pub fn new<R: TrustRoot>(
-            rekor_config: RekorConfiguration,
-            trust_repo: R,
-        ) -> SigstoreResult<Self> {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()?;
-            let inner = AsyncVerifier::new(rekor_config, trust_repo)?;
+    /// Verifies the log entry's proofs of inclusion in the transparency log.
+    ///
+    /// Unless `offline` is set, an inclusion proof missing from the bundle is fetched from Rekor,
+    /// looking the entry up by its UUID or by `artifact_hash`, and the log's current checkpoint is
+    /// checked to be consistent with the one the inclusion proof commits to.
+    ///
+    /// Returns the entry's integrated time if its Signed Entry Timestamp (SET) was verified.
+    /// Without a SET, the integrated time is not signed by the log, and the log's key must be
+    /// valid now for its checkpoint to verify.
+    async fn verify_log_entry(
+        &self,
+        log_entry: &TransparencyLogEntry,
+        artifact_hash: &str,
+        offline: bool,
+    ) -> Result<Option<DateTime<Utc>>, VerificationError> {
+        // 5) Verify the Signed Entry Timestamp (SET) supplied by Rekor for this
+        //    artifact.
+        let integrated_time = if log_entry.inclusion_promise.is_some() {
+            verify_set(log_entry, &self.rekor_keyring)?;
+            debug!("signed entry timestamp is valid");
+            Some(integrated_time(log_entry)?)
+        } else {
+            debug!("log entry has no signed entry timestamp, skipping SET check");
+            None
+        };
 
-            Ok(Self { rt, inner })
+        let mut inclusion_proof = log_entry.inclusion_proof.clone();
+        if inclusion_proof.is_none() && !offline {
+            inclusion_proof = Some(self.fetch_inclusion_proof(log_entry, artifact_hash).await?);
+            debug!("fetched missing inclusion proof from Rekor");
         }
 
-        /// Verifies an input digest against the given Sigstore Bundle, ensuring conformance to the
-        /// provided [`VerificationPolicy`].
-        pub fn verify_digest<P>(
-            &self,
-            input_digest: Sha256,
-            bundle: Bundle,
-            policy: &P,
-            offline: bool,
-        ) -> VerificationResult
-        where
-            P: VerificationPolicy,
-        {
-            self.rt.block_on(
-                self.inner
-                    .verify_digest(input_digest, bundle, policy, offline),
-            )
+        // 6) Verify the inclusion proof supplied by Rekor for this artifact,
+        //    if one is present.
+        if let Some(inclusion_proof) = &inclusion_proof {
+            verify_inclusion(log_entry, inclusion_proof)?;
+            debug!("log entry is included in the transparency log");
+
+            if let Some(checkpoint) = &inclusion_proof.checkpoint {
+                verify_checkpoint(
+                    log_entry,
+                    inclusion_proof,
+                    &checkpoint.envelope,
+                    &self.rekor_keyring,
+                    integrated_time.unwrap_or_else(Utc::now),
+                )?;
+                debug!("inclusion proof is consistent with the log's signed checkpoint");
+
+                if !offline {
+                    self.verify_live_checkpoint(log_entry, &checkpoint.envelope)
+                        .await?;
+                }
+            }
+        } else {
+            debug!("log entry has no inclusion proof, skipping inclusion check");
         }
 
-        /// Verifies an input against the given Sigstore Bundle, ensuring conformance to the provided
-        /// [`VerificationPolicy`].
-        pub fn verify<R, P>(
-            &self,
-            mut input: R,
-            bundle: Bundle,
-            policy: &P,
-            offline: bool,
-        ) -> VerificationResult
-        where
-            R: Read,
-            P: VerificationPolicy,
-        {
-            let mut hasher = Sha256::new();
-            io::copy(&mut input, &mut hasher).map_err(VerificationError::Input)?;
+        Ok(integrated_time)
+    }
+
+    /// Fetches the inclusion proof of a log entry from Rekor.
+    ///
+    /// The entry is looked up by its UUID, the hash of its leaf in the log's Merkle tree. Entries
+    /// that Rekor cannot find this way, such as those of inactive shards, are searched for by
+    /// `artifact_hash`. The proof is only trusted once verified against the entry.
+    async fn fetch_inclusion_proof(
+        &self,
+        entry: &TransparencyLogEntry,
+        artifact_hash: &str,
+    ) -> Result<InclusionProof, TransparencyErrorKind> {
+        let uuid = hex::encode(merkle::hash_leaf(&entry.canonicalized_body));
+        if let Some(proof) = self.fetch_entry_inclusion_proof(entry, &uuid).await? {
+            return Ok(proof);
+        }
 
-            self.verify_digest(hasher, bundle, policy, offline)
+        // Search results are qualified with the ID of the shard that holds the entry.
+        let query = SearchIndex {
+            hash: Some(artifact_hash.to_owned()),
+            ..SearchIndex::new()
+        };
+        let uuids = index_api::search_index(&self.rekor_config, query)
+            .await
+            .map_err(rekor_error)?;
+        for uuid in uuids {
+            if let Some(proof) = self.fetch_entry_inclusion_proof(entry, &uuid).await? {
+                return Ok(proof);
+            }
         }
+
+        Err(TransparencyErrorKind::EntryNotFound)
+    }
+
+    /// Fetches the Rekor entry with the given UUID and returns its inclusion proof, if it is the
+    /// bundle's entry.
+    ///
+    /// Rekor's [`LogEntry`](crate::rekor::models::LogEntry) model is not used, as it does not
+    /// support all the entry kinds that bundles may contain.
+    async fn fetch_entry_inclusion_proof(
+        &self,
+        entry: &TransparencyLogEntry,
+        uuid: &str,
+    ) -> Result<Option<InclusionProof>, TransparencyErrorKind> {
+        let config = &self.rekor_config;
+        let mut request = config
+            .client
+            .get(format!("{}/api/v1/log/entries/{uuid}", config.base_path));
+        if let Some(user_agent) = &config.user_agent {
+            request = request.header(reqwest::header::USER_AGENT, user_agent);
+        }
+
+        let response = request.send().await.map_err(rekor_error)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let fetched: HashMap<String, FetchedLogEntry> = response
+            .error_for_status()
+            .map_err(rekor_error)?
+            .json()
+            .await
+            .map_err(rekor_error)?;
+
+        let Some(proof) = fetched
+            .into_values()
+            .find(|fetched| fetched.log_index == entry.log_index)
+            .and_then(|fetched| fetched.verification.inclusion_proof)
+        else {
+            return Ok(None);
+        };
+
+        proof
+            .try_into()
+            .map(Some)
+            .or(Err(TransparencyErrorKind::Rekor(
+                "inclusion proof is malformed".to_owned(),
+            )))
+    }
+
+    /// Fetches the log's current signed checkpoint from Rekor, and verifies that the log has only
+    /// grown since `envelope`, the checkpoint that the entry's inclusion proof commits to.
+    ///
+    /// Entries of inactive shards are not checked, as their checkpoints are not served anymore.
+    async fn verify_live_checkpoint(
+        &self,
+        entry: &TransparencyLogEntry,
+        envelope: &str,
+    ) -> Result<(), TransparencyErrorKind> {
+        let log_id = log_id(entry)?;
+        let checkpoint: SignedCheckpoint = envelope.parse()?;
+
+        let log_info = tlog_api::get_log_info(&self.rekor_config)
+            .await
+            .map_err(rekor_error)?;
+        let live: SignedCheckpoint = log_info.signed_tree_head.parse()?;
+        if live.note.origin != checkpoint.note.origin {
+            debug!("log entry belongs to an inactive shard, skipping live checkpoint check");
+            return Ok(());
+        }
+
+        let mut proof = vec![];
+        if live.note.size > checkpoint.note.size {
+            let live_size = live
+                .note
+                .size
+                .try_into()
+                .or(Err(TransparencyErrorKind::Rekor(format!(
+                    "tree size {} is out of range",
+                    live.note.size
+                ))))?;
+            let consistency_proof = tlog_api::get_log_proof(
+                &self.rekor_config,
+                live_size,
+                Some(&checkpoint.note.size.to_string()),
+                log_info.tree_id.as_deref(),
+            )
+            .await
+            .map_err(rekor_error)?;
+            proof = consistency_proof
+                .hashes
+                .iter()
+                .map(hex::decode)
+                .collect::<Result<_, _>>()
+                .or(Err(TransparencyErrorKind::Consistency(
+                    MerkleProofError::MalformedHash,
+                )))?;
+        }
+
+        verify_live_checkpoint(
+            &log_id,
+            &checkpoint,
+            &live,
+            &proof,
+            &self.rekor_keyring,
+            Utc::now(),
+        )?;
+        debug!("log's current checkpoint is consistent with the inclusion proof's");
+
+        Ok(())
+    }
+
+    /// Verifies the bundle's RFC 3161 signed timestamps against the trusted timestamp
+    /// authorities, and returns the times they attest to.
+    fn verify_signed_timestamps(
+        &self,
+        materials: &CheckedBundle,
+    ) -> Result<Vec<SigningTime>, VerificationError> {
+        let signature = materials.content.signature();
+        let signing_times = materials
+            .signed_timestamps
+            .iter()
+            .map(|timestamp| {
+                Ok(SigningTime {
+                    time: self.timestamp_verifier.verify(timestamp, signature)?,
+                    source: SigningTimeSource::TimestampAuthority,
+                })
+            })
+            .collect::<Result<Vec<_>, VerificationError>>()?;
+
+        if !signing_times.is_empty() {
+            debug!("{} signed timestamp(s) are valid", signing_times.len());
+        }
+
+        Ok(signing_times)
+    }
+
+    /// Verifies an input against the given Sigstore Bundle, ensuring conformance to the provided
+    /// [`VerificationPolicy`].
+    ///
+    /// The input is hashed with the digest algorithm recorded in the bundle, or SHA-256 if the
+    /// bundle does not record one.
+    pub async fn verify<R, P>(
+        &self,
+        input: R,
+        bundle: Bundle,
+        policy: &P,
+        offline: bool,
+    ) -> VerificationResult
+    where
+        R: AsyncRead + Unpin + Send,
+        P: VerificationPolicy + ?Sized,
+    {
+        let digest_algorithm = message_digest_algorithm(&bundle)?;
+        let input_digest = match digest_algorithm {
+            DigestAlgorithm::Sha256 => hash_input::<_, Sha256>(input).await?.finalize().to_vec(),
+            DigestAlgorithm::Sha384 => hash_input::<_, Sha384>(input).await?.finalize().to_vec(),
+            DigestAlgorithm::Sha512 => hash_input::<_, Sha512>(input).await?.finalize().to_vec(),
+        };
+
+        self.verify_message_digest(digest_algorithm, &input_digest, bundle, policy, offline)
+            .await
+    }
+
+    /// Verifies an input against the given Sigstore Bundle, enforcing every requirement of the
+    /// provided [`PolicyDocument`]: its certificate policy, and its requirements over the
+    /// verification's outcome.
+    ///
+    /// See [`Verifier::verify`].
+    pub async fn verify_with_document<R>(
+        &self,
+        input: R,
+        bundle: Bundle,
+        document: &PolicyDocument,
+        offline: bool,
+    ) -> VerificationResult
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        let outcome = self
+            .verify(input, bundle, document.certificate_policy(), offline)
+            .await?;
+        document.verify_outcome(&outcome)?;
+
+        Ok(outcome)
+    }
+
+    /// Verifies an input digest against the given Sigstore Bundle, enforcing every requirement of
+    /// the provided [`PolicyDocument`].
+    ///
+    /// See [`Verifier::verify_digest`] and [`Verifier::verify_with_document`].
+    pub async fn verify_digest_with_document<D>(
+        &self,
+        input_digest: D,
+        bundle: Bundle,
+        document: &PolicyDocument,
+        offline: bool,
+    ) -> VerificationResult
+    where
+        D: Digest + AssociatedOid,
+    {
+        let outcome = self
+            .verify_digest(input_digest, bundle, document.certificate_policy(), offline)
+            .await?;
+        document.verify_outcome(&outcome)?;
+
+        Ok(outcome)
+    }
+}
+
+/// Returns the digest algorithm of a bundle's message signature, defaulting to SHA-256 for
+/// bundles that do not record one.
+fn message_digest_algorithm(bundle: &Bundle) -> Result<DigestAlgorithm, SignatureErrorKind> {
+    let Some(bundle::Content::MessageSignature(MessageSignature {
+        message_digest: Some(message_digest),
+        ..
+    })) = &bundle.content
+    else {
+        return Ok(DigestAlgorithm::Sha256);
+    };
+
+    let algorithm = message_digest.algorithm();
+    algorithm
+        .try_into()
+        .or(Err(SignatureErrorKind::DigestAlgorithmUnsupported(
+            algorithm.as_str_name().to_owned(),
+        )))
+}
+
+/// Hashes an asynchronous input.
+async fn hash_input<R, D>(mut input: R) -> Result<D, VerificationError>
+where
+    R: AsyncRead + Unpin + Send,
+    D: Digest,
+{
+    // arbitrary buffer size, chosen to be a multiple of the digest size.
+    let mut buf = [0u8; 1024];
+    let mut hasher = D::new();
+
+    loop {
+        match input
+            .read(&mut buf)
+            .await
+            .map_err(VerificationError::Input)?
+        {
+            0 => break,
+            n => hasher.update(&buf[..n]),
+        }
+    }
+
+    Ok(hasher)
+}
+
+/// Verifies that a log entry is included in the transparency log by recomputing the Merkle tree's
+/// root hash from the entry and its inclusion proof.
+fn verify_inclusion(
+    entry: &TransparencyLogEntry,
+    proof: &InclusionProof,
+) -> Result<(), TransparencyErrorKind> {
+    let index = proof
+        .log_index
+        .try_into()
+        .or(Err(TransparencyErrorKind::EntryMalformed))?;
+    let tree_size = proof
+        .tree_size
+        .try_into()
+        .or(Err(TransparencyErrorKind::EntryMalformed))?;
+    let leaf_hash = merkle::hash_leaf(&entry.canonicalized_body);
+
+    merkle::verify_inclusion(
+        index,
+        tree_size,
+        &leaf_hash,
+        &proof.hashes,
+        &proof.root_hash,
+    )
+    .map_err(TransparencyErrorKind::InclusionProof)
+}
+
+/// Verifies that an inclusion proof's checkpoint is signed at `time` by the log that produced the
+/// entry, and that it commits to the same tree as the proof.
+fn verify_checkpoint(
+    entry: &TransparencyLogEntry,
+    proof: &InclusionProof,
+    envelope: &str,
+    keyring: &Keyring,
+    time: DateTime<Utc>,
+) -> Result<(), TransparencyErrorKind> {
+    let log_id = log_id(entry)?;
+    let tree_size = proof
+        .tree_size
+        .try_into()
+        .or(Err(TransparencyErrorKind::EntryMalformed))?;
+
+    let checkpoint: SignedCheckpoint = envelope.parse()?;
+    checkpoint.is_valid_for_proof(&proof.root_hash, tree_size)?;
+
+    verify_checkpoint_signature(&checkpoint, &log_id, keyring, time)
+}
+
+/// Verifies the signature of `live`, a checkpoint of the log's current state signed at `now`,
+/// and that the tree it describes extends the one described by `checkpoint`, given the
+/// consistency proof `proof`.
+fn verify_live_checkpoint(
+    log_id: &[u8; 32],
+    checkpoint: &SignedCheckpoint,
+    live: &SignedCheckpoint,
+    proof: &[Vec<u8>],
+    keyring: &Keyring,
+    now: DateTime<Utc>,
+) -> Result<(), TransparencyErrorKind> {
+    verify_checkpoint_signature(live, log_id, keyring, now)?;
+
+    merkle::verify_consistency(
+        checkpoint.note.size,
+        live.note.size,
+        proof,
+        &checkpoint.note.hash,
+        &live.note.hash,
+    )
+    .map_err(TransparencyErrorKind::Consistency)
+}
+
+/// Verifies that a checkpoint was signed at `time` by the log with ID `log_id`.
+///
+/// Signatures are matched to the log by their key hint, which may collide with other signers':
+/// the checkpoint is valid if any of the matching signatures verifies.
+fn verify_checkpoint_signature(
+    checkpoint: &SignedCheckpoint,
+    log_id: &[u8; 32],
+    keyring: &Keyring,
+    time: DateTime<Utc>,
+) -> Result<(), TransparencyErrorKind> {
+    let note = checkpoint.note.marshal();
+
+    let mut result = Ok(());
+    for signature in checkpoint.signatures_for(log_id)? {
+        result = keyring.verify(log_id, &signature.signature, note.as_bytes(), time);
+        if result.is_ok() {
+            break;
+        }
+    }
+
+    result.map_err(TransparencyErrorKind::CheckpointSignature)
+}
+
+/// Returns the ID of the log that a log entry belongs to: the SHA-256 digest of its public key.
+fn log_id(entry: &TransparencyLogEntry) -> Result<[u8; 32], TransparencyErrorKind> {
+    entry
+        .log_id
+        .as_ref()
+        .and_then(|id| id.key_id.as_slice().try_into().ok())
+        .ok_or(TransparencyErrorKind::EntryMalformed)
+}
+
+/// Rewraps an error from a request to Rekor.
+fn rekor_error<E: ToString>(error: E) -> TransparencyErrorKind {
+    TransparencyErrorKind::Rekor(error.to_string())
+}
+
+/// The parts of an entry fetched from Rekor that complete a bundle's entry.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FetchedLogEntry {
+    log_index: i64,
+    verification: Verification,
+}
+
+/// Returns the time at which a log entry was integrated into the log. The log's key must have been
+/// valid at that time.
+fn integrated_time(entry: &TransparencyLogEntry) -> Result<DateTime<Utc>, TransparencyErrorKind> {
+    DateTime::from_timestamp(entry.integrated_time, 0).ok_or(TransparencyErrorKind::EntryMalformed)
+}
+
+/// The payload signed by Rekor to produce a Signed Entry Timestamp.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignedEntryTimestampPayload {
+    body: String,
+    integrated_time: i64,
+    #[serde(rename = "logID")]
+    log_id: String,
+    log_index: i64,
+}
+
+/// Verifies a log entry's Signed Entry Timestamp (SET), Rekor's signed promise to include the
+/// entry in the log.
+///
+/// The SET is a signature over the canonical JSON of the entry's body, integrated time, log index
+/// and log ID, made by the key of the log identified by the log ID.
+fn verify_set(
+    entry: &TransparencyLogEntry,
+    keyring: &Keyring,
+) -> Result<(), TransparencyErrorKind> {
+    let promise = entry
+        .inclusion_promise
+        .as_ref()
+        .ok_or(TransparencyErrorKind::EntryMalformed)?;
+    let log_id = log_id(entry)?;
+
+    let payload = SignedEntryTimestampPayload {
+        body: base64.encode(&entry.canonicalized_body),
+        integrated_time: entry.integrated_time,
+        log_id: hex::encode(log_id),
+        log_index: entry.log_index,
+    };
+    let payload = {
+        let mut payload =
+            json_syntax::to_value(payload).or(Err(TransparencyErrorKind::EntryMalformed))?;
+        payload.canonicalize();
+        payload.compact_print().to_string()
+    };
+
+    keyring
+        .verify(
+            &log_id,
+            &promise.signed_entry_timestamp,
+            payload.as_bytes(),
+            integrated_time(entry)?,
+        )
+        .map_err(TransparencyErrorKind::SignedEntryTimestamp)
+}
+
+impl Verifier {
+    /// Constructs an [`Verifier`] against the public-good trust root.
+    #[cfg(feature = "sigstore-trust-root")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sigstore-trust-root")))]
+    pub async fn production() -> SigstoreResult<Verifier> {
+        let updater = SigstoreTrustRoot::new(None).await?;
+
+        Verifier::new(Default::default(), updater)
+    }
+}
+
+pub mod blocking {
+    use super::{Verifier as AsyncVerifier, *};
+
+    /// A synchronous Sigstore verifier.
+    pub struct Verifier {
+        inner: AsyncVerifier,
+        rt: tokio::runtime::Runtime,
+    }
+
+    impl Verifier {
+        /// Constructs a synchronous Sigstore verifier.
+        ///
+        /// For verifications against the public-good trust root, use [`Verifier::production()`].
+        pub fn new<R: TrustRoot>(
+            rekor_config: RekorConfiguration,
+            trust_repo: R,
+        ) -> SigstoreResult<Self> {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            let inner = AsyncVerifier::new(rekor_config, trust_repo)?;
+
+            Ok(Self { rt, inner })
+        }
+
+        /// Trusts the given long-lived public keys, keyed by the hint that identifies them in
+        /// bundles.
+        ///
+        /// See [`AsyncVerifier::with_trusted_keys`].
+        pub fn with_trusted_keys<I>(self, keys: I) -> Self
+        where
+            I: IntoIterator<Item = (String, CosignVerificationKey)>,
+        {
+            Self {
+                inner: self.inner.with_trusted_keys(keys),
+                rt: self.rt,
+            }
+        }
+
+        /// Sets the transparency evidence that bundles must carry.
+        ///
+        /// See [`AsyncVerifier::with_config`].
+        pub fn with_config(self, config: VerifierConfig) -> Self {
+            Self {
+                inner: self.inner.with_config(config),
+                rt: self.rt,
+            }
+        }
+
+        /// Verifies an input digest against the given Sigstore Bundle, ensuring conformance to the
+        /// provided [`VerificationPolicy`].
+        pub fn verify_digest<D, P>(
+            &self,
+            input_digest: D,
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> VerificationResult
+        where
+            D: Digest + AssociatedOid,
+            P: VerificationPolicy + ?Sized,
+        {
+            self.rt.block_on(
+                self.inner
+                    .verify_digest(input_digest, bundle, policy, offline),
+            )
+        }
+
+        /// Verifies a precomputed input digest against the given Sigstore Bundle, ensuring
+        /// conformance to the provided [`VerificationPolicy`].
+        ///
+        /// See [`AsyncVerifier::verify_digest_bytes`].
+        pub fn verify_digest_bytes<P>(
+            &self,
+            digest_algorithm: DigestAlgorithm,
+            input_digest: &[u8],
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> VerificationResult
+        where
+            P: VerificationPolicy + ?Sized,
+        {
+            self.rt.block_on(self.inner.verify_digest_bytes(
+                digest_algorithm,
+                input_digest,
+                bundle,
+                policy,
+                offline,
+            ))
+        }
+
+        /// Verifies a Sigstore Bundle containing a DSSE envelope, ensuring conformance to the
+        /// provided [`VerificationPolicy`], and returns the envelope's payload along
+        /// with the verification's outcome.
+        pub fn verify_dsse<P>(
+            &self,
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> Result<Verified<DssePayload>, VerificationError>
+        where
+            P: VerificationPolicy + ?Sized,
+        {
+            self.rt
+                .block_on(self.inner.verify_dsse(bundle, policy, offline))
+        }
+
+        /// Verifies an input digest against the given Sigstore Bundle containing an in-toto
+        /// attestation, ensuring conformance to the provided [`VerificationPolicy`], and returns
+        /// the attestation's statement along with the verification's outcome.
+        pub fn verify_attestation_digest<D, P>(
+            &self,
+            input_digest: D,
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> Result<Verified<Statement>, VerificationError>
+        where
+            D: Digest + AssociatedOid,
+            P: VerificationPolicy + ?Sized,
+        {
+            self.rt.block_on(self.inner.verify_attestation_digest(
+                input_digest,
+                bundle,
+                policy,
+                offline,
+            ))
+        }
+
+        /// Verifies an input against the given Sigstore Bundle containing an in-toto
+        /// attestation, ensuring conformance to the provided [`VerificationPolicy`], and returns
+        /// the attestation's statement along with the verification's outcome.
+        pub fn verify_attestation<R, P>(
+            &self,
+            mut input: R,
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> Result<Verified<Statement>, VerificationError>
+        where
+            R: Read,
+            P: VerificationPolicy + ?Sized,
+        {
+            let mut hasher = Sha256::new();
+            io::copy(&mut input, &mut hasher).map_err(VerificationError::Input)?;
+
+            self.verify_attestation_digest(hasher, bundle, policy, offline)
+        }
+
+        /// Verifies an input digest against the given Sigstore Bundle containing a SLSA
+        /// Provenance attestation, ensuring that the signing certificate conforms to `policy` and
+        /// the provenance conforms to `provenance_policy`, and returns the verified provenance
+        /// along with the verification's outcome.
+        pub fn verify_provenance_digest<D, P, Q>(
+            &self,
+            input_digest: D,
+            bundle: Bundle,
+            policy: &P,
+            provenance_policy: &Q,
+            offline: bool,
+        ) -> Result<Verified<Provenance>, VerificationError>
+        where
+            D: Digest + AssociatedOid,
+            P: VerificationPolicy + ?Sized,
+            Q: ProvenancePolicy + ?Sized,
+        {
+            self.rt.block_on(self.inner.verify_provenance_digest(
+                input_digest,
+                bundle,
+                policy,
+                provenance_policy,
+                offline,
+            ))
+        }
+
+        /// Verifies an input against the given Sigstore Bundle containing a SLSA Provenance
+        /// attestation, ensuring that the signing certificate conforms to `policy` and the
+        /// provenance conforms to `provenance_policy`, and returns the verified provenance
+        /// along with the verification's outcome.
+        pub fn verify_provenance<R, P, Q>(
+            &self,
+            mut input: R,
+            bundle: Bundle,
+            policy: &P,
+            provenance_policy: &Q,
+            offline: bool,
+        ) -> Result<Verified<Provenance>, VerificationError>
+        where
+            R: Read,
+            P: VerificationPolicy + ?Sized,
+            Q: ProvenancePolicy + ?Sized,
+        {
+            let mut hasher = Sha256::new();
+            io::copy(&mut input, &mut hasher).map_err(VerificationError::Input)?;
+
+            self.verify_provenance_digest(hasher, bundle, policy, provenance_policy, offline)
+        }
+
+        /// Verifies an input against the given Sigstore Bundle, ensuring conformance to the provided
+        /// [`VerificationPolicy`].
+        ///
+        /// See [`AsyncVerifier::verify`].
+        pub fn verify<R, P>(
+            &self,
+            mut input: R,
+            bundle: Bundle,
+            policy: &P,
+            offline: bool,
+        ) -> VerificationResult
+        where
+            R: Read,
+            P: VerificationPolicy + ?Sized,
+        {
+            let digest_algorithm = message_digest_algorithm(&bundle)?;
+            let input_digest = match digest_algorithm {
+                DigestAlgorithm::Sha256 => hash_reader::<_, Sha256>(&mut input)?,
+                DigestAlgorithm::Sha384 => hash_reader::<_, Sha384>(&mut input)?,
+                DigestAlgorithm::Sha512 => hash_reader::<_, Sha512>(&mut input)?,
+            };
+
+            self.rt.block_on(self.inner.verify_message_digest(
+                digest_algorithm,
+                &input_digest,
+                bundle,
+                policy,
+                offline,
+            ))
+        }
+
+        /// Verifies an input against the given Sigstore Bundle, enforcing every requirement of
+        /// the provided [`PolicyDocument`].
+        ///
+        /// See [`AsyncVerifier::verify_with_document`].
+        pub fn verify_with_document<R>(
+            &self,
+            input: R,
+            bundle: Bundle,
+            document: &PolicyDocument,
+            offline: bool,
+        ) -> VerificationResult
+        where
+            R: Read,
+        {
+            let outcome = self.verify(input, bundle, document.certificate_policy(), offline)?;
+            document.verify_outcome(&outcome)?;
+
+            Ok(outcome)
+        }
+
+        /// Verifies an input digest against the given Sigstore Bundle, enforcing every
+        /// requirement of the provided [`PolicyDocument`].
+        ///
+        /// See [`AsyncVerifier::verify_digest_with_document`].
+        pub fn verify_digest_with_document<D>(
+            &self,
+            input_digest: D,
+            bundle: Bundle,
+            document: &PolicyDocument,
+            offline: bool,
+        ) -> VerificationResult
+        where
+            D: Digest + AssociatedOid,
+        {
+            self.rt.block_on(self.inner.verify_digest_with_document(
+                input_digest,
+                bundle,
+                document,
+                offline,
+            ))
+        }
+    }
+
+    /// Hashes a synchronous input.
+    fn hash_reader<R, D>(input: &mut R) -> Result<Vec<u8>, VerificationError>
+    where
+        R: Read,
+        D: Digest + io::Write,
+    {
+        let mut hasher = D::new();
+        io::copy(input, &mut hasher).map_err(VerificationError::Input)?;
+
+        Ok(hasher.finalize().to_vec())
     }
 
     impl Verifier {
@@ -302,3 +1560,1392 @@ pub mod blocking {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        bundle::verify::policy::{Identity, PolicyError},
+        crypto::{
+            keyring::KeyringError,
+            tests::{generate_certificate, CertGenerationOptions},
+            timestamp::tests::TestTsa,
+        },
+        rekor::models::checkpoint::{CheckpointError, CheckpointNote, CheckpointSignature},
+        trust::{CertificateAuthority, LogKey, ManualTrustRoot},
+    };
+    use chrono::TimeDelta;
+    use p256::ecdsa::{
+        signature::{hazmat::PrehashSigner, Signer},
+        SigningKey, VerifyingKey,
+    };
+    use serde_json::json;
+    use sigstore_protobuf_specs::dev::sigstore::{
+        bundle::v1::{
+            bundle, verification_material, TimestampVerificationData, VerificationMaterial,
+        },
+        common::v1::{
+            HashOutput, LogId, MessageSignature, PublicKeyIdentifier, Rfc3161SignedTimestamp,
+        },
+        rekor::v1::{Checkpoint, InclusionPromise},
+    };
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+    use x509_cert::{der::Decode, spki::EncodePublicKey};
+
+    const REKOR_PUB_KEY: &str = r#"-----BEGIN PUBLIC KEY-----
+MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE2G2Y+2tabdTV5BcGiBIx0a9fAFwr
+kBbmLSGtks4L3qX6yYY0zufBnhC8Ur/iy55GhWP/9A/bY2LhC30M9+RYtw==
+-----END PUBLIC KEY-----"#;
+
+    fn rekor_keyring() -> Keyring {
+        let key = VerifyingKey::from_str(REKOR_PUB_KEY).unwrap();
+        Keyring::new([key.to_public_key_der().unwrap().as_bytes()]).unwrap()
+    }
+
+    /// A `hashedrekord` entry from the public-good Rekor instance.
+    fn log_entry() -> TransparencyLogEntry {
+        let body = "eyJhcGlWZXJzaW9uIjoiMC4wLjEiLCJraW5kIjoiaGFzaGVkcmVrb3JkIiwic3BlYyI6eyJkYXRhIjp7Imhhc2giOnsiYWxnb3JpdGhtIjoic2hhMjU2IiwidmFsdWUiOiI0YmM0NTNiNTNjYjNkOTE0YjQ1ZjRiMjUwMjk0MjM2YWRiYTJjMGUwOWZmNmYwMzc5Mzk0OWU3ZTM5ZmQ0Y2MxIn19LCJzaWduYXR1cmUiOnsiY29udGVudCI6Ik1FUUNJR3AxWFpQNXphSW1vc3JCaERQQ2RYbjNmOHhJOUZIR0xzR1Z4NlVlUlBDZ0FpQXQ1R3JzZFFoT0tuWmNBM0VXZWN2Z0pTSHpDSWpXaWZGQlFrRDdIZHN5bWc9PSIsInB1YmxpY0tleSI6eyJjb250ZW50IjoiTFMwdExTMUNSVWRKVGlCRFJWSlVTVVpKUTBGVVJTMHRMUzB0Q2sxSlNVTnhSRU5EUVdrclowRjNTVUpCWjBsVlZGQlhWR1pQTHpGT1VtRlRSbVJsWTJGQlVTOXdRa1JIU25BNGQwTm5XVWxMYjFwSmVtb3dSVUYzVFhjS1RucEZWazFDVFVkQk1WVkZRMmhOVFdNeWJHNWpNMUoyWTIxVmRWcEhWakpOVWpSM1NFRlpSRlpSVVVSRmVGWjZZVmRrZW1SSE9YbGFVekZ3WW01U2JBcGpiVEZzV2tkc2FHUkhWWGRJYUdOT1RXcEplRTFVU1RGTlJHTjZUbnBGZVZkb1kwNU5ha2w0VFZSSk1VMUVZekJPZWtWNVYycEJRVTFHYTNkRmQxbElDa3R2V2tsNmFqQkRRVkZaU1V0dldrbDZhakJFUVZGalJGRm5RVVZLVVZFMFZ5ODFXRkE1YlRSWllsZFNRbEYwU0VkWGQyNDVkVlZvWVdVek9GVndZMG9LY0VWTk0wUlBjelI2VnpSTlNYSk5abGMwVjFGRU1HWjNjRGhRVlZWU1JGaDJVVE01TkhCdmNXZEhSVzFUYTNKMVRIRlBRMEZWTkhkblowWkxUVUUwUndwQk1WVmtSSGRGUWk5M1VVVkJkMGxJWjBSQlZFSm5UbFpJVTFWRlJFUkJTMEpuWjNKQ1owVkdRbEZqUkVGNlFXUkNaMDVXU0ZFMFJVWm5VVlZ2TTB0dUNtcEtVVm93V0dacFoySkVOV0l3VDFaT1RqQjRjVk52ZDBoM1dVUldVakJxUWtKbmQwWnZRVlV6T1ZCd2VqRlphMFZhWWpWeFRtcHdTMFpYYVhocE5Ga0tXa1E0ZDBwM1dVUldVakJTUVZGSUwwSkNNSGRITkVWYVdrZEdkV0ZYVm5OTWJVcHNaRzFXZFdGWVZucFJSMlIwV1Zkc2MweHRUblppVkVGelFtZHZjZ3BDWjBWRlFWbFBMMDFCUlVKQ1FqVnZaRWhTZDJONmIzWk1NbVJ3WkVkb01WbHBOV3BpTWpCMllrYzVibUZYTkhaaU1rWXhaRWRuZDJkWmMwZERhWE5IQ2tGUlVVSXhibXREUWtGSlJXWlJVamRCU0d0QlpIZEVaRkJVUW5GNGMyTlNUVzFOV2tob2VWcGFlbU5EYjJ0d1pYVk9ORGh5Wml0SWFXNUxRVXg1Ym5VS2FtZEJRVUZaVTNSMVFraDVRVUZCUlVGM1FrbE5SVmxEU1ZGRVRUVlpVMUV2UjB3MlMwazFVamxQWkdOdUwzQlRheXR4VmtRMlluTk1PRE1yUlhBNVVnb3lhRmRVWVhkSmFFRkxNV3BwTVd4YU5UWkVjMloxVEdaWU4ySkNRemx1WWxJelJXeDRZV3hDYUhZeGVsRllUVlUzZEd4M1RVRnZSME5EY1VkVFRUUTVDa0pCVFVSQk1tTkJUVWRSUTAxQ1N6aDBjMmRJWldkMWFDdFphR1ZzTTFCcGFraFJiSGxLTVZFMVN6WTBjREI0Y1VSa2J6ZFhOR1o0Wm05QlV6bDRjbEFLY3pKUVMxRmpaRzlFT1dKWWQyZEpkMWcyZWt4cWVXSmFhMDVJVURWNGRFSndOM1pMTWtaWlpWcDBNRTlYVEZKc1ZXeHNZMVZFVEROVUx6ZEtVV1ozWXdwSFUzRTJkbFpDVG5kS01EQjNPVWhTQ2kwdExTMHRSVTVFSUVORlVsUkpSa2xEUVZSRkxTMHRMUzBLIn19fX0=";
+
+        TransparencyLogEntry {
+            log_index: 7810348,
+            log_id: Some(LogId {
+                key_id: hex::decode(
+                    "c0d23d6ad406973f9559f3ba2d1ca01f84147d8ffc5b8445c224f98b9591801d",
+                )
+                .unwrap(),
+            }),
+            kind_version: None,
+            integrated_time: 1669361833,
+            inclusion_promise: Some(InclusionPromise {
+                signed_entry_timestamp: base64
+                    .decode("MEUCIC3c+21v9pk6o4BpB/dRAM9lGnyWLi3Xnc+i8LmnNJmeAiEAiqZJbZHx3Idnw+zXv6yM0ipPw/p16R28YGuCJFQ1u8U=")
+                    .unwrap(),
+            }),
+            inclusion_proof: None,
+            canonicalized_body: base64.decode(body).unwrap(),
+        }
+    }
+
+    #[test]
+    fn verify_set_success() {
+        assert!(verify_set(&log_entry(), &rekor_keyring()).is_ok());
+    }
+
+    #[test]
+    fn verify_set_validity_period() {
+        let key = VerifyingKey::from_str(REKOR_PUB_KEY).unwrap();
+        let spki = key.to_public_key_der().unwrap();
+        let integrated_time = DateTime::from_timestamp(log_entry().integrated_time, 0).unwrap();
+        let keyring = |valid_for| {
+            Keyring::from_log_keys([LogKey {
+                key: spki.as_bytes(),
+                valid_for,
+            }])
+            .unwrap()
+        };
+
+        // The key has since been rotated out, but was valid when the entry was integrated.
+        let rotated_out = keyring(ValidityPeriod {
+            start: Some(integrated_time - TimeDelta::days(1)),
+            end: Some(integrated_time + TimeDelta::days(1)),
+        });
+        assert!(verify_set(&log_entry(), &rotated_out).is_ok());
+
+        // The key was not yet valid when the entry was integrated.
+        let not_yet_valid = keyring(ValidityPeriod {
+            start: Some(integrated_time + TimeDelta::seconds(1)),
+            end: None,
+        });
+        assert!(matches!(
+            verify_set(&log_entry(), &not_yet_valid),
+            Err(TransparencyErrorKind::SignedEntryTimestamp(
+                KeyringError::KeyOutsideValidityPeriod
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_set_failure() {
+        let keyring = rekor_keyring();
+
+        let mut entry = log_entry();
+        entry.integrated_time += 1;
+        assert!(matches!(
+            verify_set(&entry, &keyring),
+            Err(TransparencyErrorKind::SignedEntryTimestamp(_))
+        ));
+
+        let mut entry = log_entry();
+        entry.log_index += 1;
+        assert!(matches!(
+            verify_set(&entry, &keyring),
+            Err(TransparencyErrorKind::SignedEntryTimestamp(_))
+        ));
+
+        let mut entry = log_entry();
+        entry.log_id = Some(LogId {
+            key_id: vec![0; 32],
+        });
+        assert!(matches!(
+            verify_set(&entry, &keyring),
+            Err(TransparencyErrorKind::SignedEntryTimestamp(_))
+        ));
+
+        let mut entry = log_entry();
+        entry.inclusion_promise = None;
+        assert!(matches!(
+            verify_set(&entry, &keyring),
+            Err(TransparencyErrorKind::EntryMalformed)
+        ));
+    }
+
+    /// Returns a log entry with an inclusion proof in a single-leaf tree, whose checkpoint is
+    /// signed by `signer`, along with a keyring holding `signer`'s key.
+    fn checkpointed_log_entry(signer: &SigningKey) -> (TransparencyLogEntry, Keyring) {
+        let spki = signer.verifying_key().to_public_key_der().unwrap();
+        let log_id: [u8; 32] = Sha256::digest(spki.as_bytes()).into();
+        let keyring = Keyring::new([spki.as_bytes()]).unwrap();
+
+        let mut entry = log_entry();
+        entry.log_id = Some(LogId {
+            key_id: log_id.to_vec(),
+        });
+        let root_hash = merkle::hash_leaf(&entry.canonicalized_body);
+        let checkpoint = signed_checkpoint(signer, 1, root_hash);
+
+        entry.inclusion_proof = Some(InclusionProof {
+            log_index: 0,
+            root_hash: root_hash.to_vec(),
+            tree_size: 1,
+            hashes: vec![],
+            checkpoint: Some(Checkpoint {
+                envelope: checkpoint.to_string(),
+            }),
+        });
+
+        (entry, keyring)
+    }
+
+    /// Returns a checkpoint of a tree of `size` leaves with root hash `hash`, signed by `signer`.
+    pub(crate) fn signed_checkpoint(
+        signer: &SigningKey,
+        size: u64,
+        hash: merkle::MerkleHash,
+    ) -> SignedCheckpoint {
+        let spki = signer.verifying_key().to_public_key_der().unwrap();
+        let log_id: [u8; 32] = Sha256::digest(spki.as_bytes()).into();
+
+        let note = CheckpointNote {
+            origin: "rekor.example.com - 1".into(),
+            size,
+            hash,
+            other_content: vec![],
+        };
+        let signature: p256::ecdsa::Signature = signer.sign(note.marshal().as_bytes());
+        SignedCheckpoint {
+            note,
+            signatures: vec![CheckpointSignature {
+                name: "rekor.example.com".into(),
+                key_hint: log_id[..4].try_into().unwrap(),
+                signature: signature.to_der().as_bytes().to_vec(),
+            }],
+        }
+    }
+
+    fn check(entry: &TransparencyLogEntry, keyring: &Keyring) -> Result<(), TransparencyErrorKind> {
+        let proof = entry.inclusion_proof.as_ref().unwrap();
+        let envelope = &proof.checkpoint.as_ref().unwrap().envelope;
+        verify_inclusion(entry, proof)?;
+        verify_checkpoint(entry, proof, envelope, keyring, integrated_time(entry)?)
+    }
+
+    #[test]
+    fn verify_checkpoint_success() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let (entry, keyring) = checkpointed_log_entry(&signer);
+
+        assert!(check(&entry, &keyring).is_ok());
+    }
+
+    #[test]
+    fn verify_checkpoint_colliding_key_hints() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let (mut entry, keyring) = checkpointed_log_entry(&signer);
+        let proof = entry.inclusion_proof.as_mut().unwrap();
+        let checkpoint = proof.checkpoint.as_mut().unwrap();
+        let mut signed: SignedCheckpoint = checkpoint.envelope.parse().unwrap();
+
+        // Another signer whose key hint collides with the log's signs first.
+        let other_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signature: p256::ecdsa::Signature = other_signer.sign(signed.note.marshal().as_bytes());
+        let colliding = CheckpointSignature {
+            name: "witness.example.com".into(),
+            key_hint: signed.signatures[0].key_hint,
+            signature: signature.to_der().as_bytes().to_vec(),
+        };
+        signed.signatures.insert(0, colliding);
+        checkpoint.envelope = signed.to_string();
+        assert!(check(&entry, &keyring).is_ok());
+
+        // None of the colliding signatures is the log's.
+        let proof = entry.inclusion_proof.as_mut().unwrap();
+        let checkpoint = proof.checkpoint.as_mut().unwrap();
+        signed.signatures.truncate(1);
+        checkpoint.envelope = signed.to_string();
+        assert!(matches!(
+            check(&entry, &keyring),
+            Err(TransparencyErrorKind::CheckpointSignature(_))
+        ));
+    }
+
+    #[test]
+    fn verify_checkpoint_failure() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let (entry, keyring) = checkpointed_log_entry(&signer);
+
+        // Signed by a key that is not in the keyring.
+        let other_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let (other_entry, _) = checkpointed_log_entry(&other_signer);
+        let mut forged = entry.clone();
+        forged.inclusion_proof = other_entry.inclusion_proof;
+        assert!(matches!(
+            check(&forged, &keyring),
+            Err(TransparencyErrorKind::Checkpoint(
+                CheckpointError::MissingSignature(_)
+            ))
+        ));
+
+        // Checkpoint does not commit to the proof's tree.
+        let mut mismatched = entry.clone();
+        let proof = mismatched.inclusion_proof.as_mut().unwrap();
+        let checkpoint = proof.checkpoint.as_mut().unwrap();
+        checkpoint.envelope = checkpoint.envelope.replacen("\n1\n", "\n2\n", 1);
+        assert!(matches!(
+            check(&mismatched, &keyring),
+            Err(TransparencyErrorKind::Checkpoint(
+                CheckpointError::TreeSizeMismatch { .. }
+            ))
+        ));
+
+        // Checkpoint note was altered after signing.
+        let mut tampered = entry.clone();
+        let proof = tampered.inclusion_proof.as_mut().unwrap();
+        let checkpoint = proof.checkpoint.as_mut().unwrap();
+        checkpoint.envelope = checkpoint.envelope.replacen(" - 1\n", " - 2\n", 1);
+        assert!(matches!(
+            check(&tampered, &keyring),
+            Err(TransparencyErrorKind::CheckpointSignature(_))
+        ));
+
+        // Malformed checkpoint.
+        let mut malformed = entry;
+        let proof = malformed.inclusion_proof.as_mut().unwrap();
+        proof.checkpoint.as_mut().unwrap().envelope = "not a checkpoint".into();
+        assert!(matches!(
+            check(&malformed, &keyring),
+            Err(TransparencyErrorKind::Checkpoint(
+                CheckpointError::Malformed(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn verify_live_checkpoint_consistency() {
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let (entry, keyring) = checkpointed_log_entry(&signer);
+        let log_id = log_id(&entry).unwrap();
+        let envelope = &entry
+            .inclusion_proof
+            .as_ref()
+            .unwrap()
+            .checkpoint
+            .as_ref()
+            .unwrap()
+            .envelope;
+        let checkpoint: SignedCheckpoint = envelope.parse().unwrap();
+
+        // The log has grown by one leaf since the entry was integrated.
+        let leaf0 = merkle::hash_leaf(&entry.canonicalized_body);
+        let leaf1 = merkle::hash_leaf(b"another entry");
+        let live = signed_checkpoint(&signer, 2, merkle::hash_children(&leaf0, &leaf1));
+        let proof = vec![leaf1.to_vec()];
+        let now = Utc::now();
+
+        assert!(verify_live_checkpoint(&log_id, &checkpoint, &live, &proof, &keyring, now).is_ok());
+        assert!(
+            verify_live_checkpoint(&log_id, &checkpoint, &checkpoint, &[], &keyring, now).is_ok()
+        );
+
+        // Proof does not show that the trees are consistent.
+        let wrong_proof = vec![leaf0.to_vec()];
+        assert!(matches!(
+            verify_live_checkpoint(&log_id, &checkpoint, &live, &wrong_proof, &keyring, now),
+            Err(TransparencyErrorKind::Consistency(
+                MerkleProofError::RootMismatch { .. }
+            ))
+        ));
+
+        // The log has shrunk.
+        assert!(matches!(
+            verify_live_checkpoint(&log_id, &live, &checkpoint, &proof, &keyring, now),
+            Err(TransparencyErrorKind::Consistency(
+                MerkleProofError::TreeSizeDecreased { .. }
+            ))
+        ));
+
+        // Signed by a key that is not the log's.
+        let other_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let mut forged = signed_checkpoint(&other_signer, 2, live.note.hash);
+        forged.signatures[0].key_hint = live.signatures[0].key_hint;
+        assert!(matches!(
+            verify_live_checkpoint(&log_id, &checkpoint, &forged, &proof, &keyring, now),
+            Err(TransparencyErrorKind::CheckpointSignature(_))
+        ));
+    }
+
+    /// Serves canned Rekor responses, keyed by request method and path, from a local socket, and
+    /// returns a verifier using it along with the paths requested so far.
+    fn mock_rekor(
+        responses: Vec<(&'static str, String, u16, String)>,
+    ) -> (Verifier, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_path = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let requested = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                reader.read_exact(&mut vec![0; content_length]).unwrap();
+
+                let mut parts = request_line.split_whitespace();
+                let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                requested.lock().unwrap().push(path.to_owned());
+                let (status, body) = responses
+                    .iter()
+                    .find(|(m, p, ..)| *m == method && p == path)
+                    .map_or((404, "{}"), |(.., status, body)| (*status, body.as_str()));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        let rekor_config = RekorConfiguration {
+            base_path,
+            client: reqwest::Client::builder().no_proxy().build().unwrap(),
+            ..Default::default()
+        };
+        let verifier = Verifier::new(rekor_config, ManualTrustRoot::default()).unwrap();
+
+        (verifier, requests)
+    }
+
+    /// Returns Rekor's response to a lookup of the entry with the given UUID and log index.
+    fn fetched_entry(uuid: &str, log_index: i64, root_hash: &str) -> String {
+        json!({
+            uuid: {
+                "logIndex": log_index,
+                "verification": {
+                    "inclusionProof": {
+                        "hashes": ["0a0b"],
+                        "logIndex": log_index - 1,
+                        "rootHash": root_hash,
+                        "treeSize": log_index + 1,
+                        "checkpoint": "checkpoint",
+                    },
+                    "signedEntryTimestamp": "",
+                },
+            },
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn fetch_inclusion_proof_by_uuid() {
+        let entry = log_entry();
+        let uuid = hex::encode(merkle::hash_leaf(&entry.canonicalized_body));
+        let (verifier, requests) = mock_rekor(vec![(
+            "GET",
+            format!("/api/v1/log/entries/{uuid}"),
+            200,
+            fetched_entry(&uuid, entry.log_index, "0c0d"),
+        )]);
+
+        let proof = verifier
+            .fetch_inclusion_proof(&entry, "sha256:00")
+            .await
+            .unwrap();
+        assert_eq!(proof.log_index, entry.log_index - 1);
+        assert_eq!(proof.root_hash, [0x0c, 0x0d]);
+        assert_eq!(proof.hashes, [vec![0x0a, 0x0b]]);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [format!("/api/v1/log/entries/{uuid}")]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_inclusion_proof_falls_back_to_search() {
+        let entry = log_entry();
+        let uuid = hex::encode(merkle::hash_leaf(&entry.canonicalized_body));
+        // Entries of inactive shards are only found with their shard-qualified UUID.
+        let sharded_uuid = format!("24296fb24b8ad77a{uuid}");
+        let (verifier, requests) = mock_rekor(vec![
+            (
+                "POST",
+                "/api/v1/index/retrieve".to_owned(),
+                200,
+                json!([sharded_uuid]).to_string(),
+            ),
+            (
+                "GET",
+                format!("/api/v1/log/entries/{sharded_uuid}"),
+                200,
+                fetched_entry(&sharded_uuid, entry.log_index, "0c0d"),
+            ),
+        ]);
+
+        let proof = verifier
+            .fetch_inclusion_proof(&entry, "sha256:00")
+            .await
+            .unwrap();
+        assert_eq!(proof.log_index, entry.log_index - 1);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [
+                format!("/api/v1/log/entries/{uuid}"),
+                "/api/v1/index/retrieve".to_owned(),
+                format!("/api/v1/log/entries/{sharded_uuid}"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_inclusion_proof_of_other_entry() {
+        let entry = log_entry();
+        let uuid = hex::encode(merkle::hash_leaf(&entry.canonicalized_body));
+        // Rekor serves an entry under the UUID, but not the bundle's.
+        let (verifier, _) = mock_rekor(vec![
+            (
+                "GET",
+                format!("/api/v1/log/entries/{uuid}"),
+                200,
+                fetched_entry(&uuid, entry.log_index + 1, "0c0d"),
+            ),
+            (
+                "POST",
+                "/api/v1/index/retrieve".to_owned(),
+                200,
+                json!([uuid]).to_string(),
+            ),
+        ]);
+
+        assert!(matches!(
+            verifier.fetch_entry_inclusion_proof(&entry, &uuid).await,
+            Ok(None)
+        ));
+        assert!(matches!(
+            verifier.fetch_inclusion_proof(&entry, "sha256:00").await,
+            Err(TransparencyErrorKind::EntryNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_inclusion_proof_malformed() {
+        let entry = log_entry();
+        let uuid = hex::encode(merkle::hash_leaf(&entry.canonicalized_body));
+        let (verifier, _) = mock_rekor(vec![(
+            "GET",
+            format!("/api/v1/log/entries/{uuid}"),
+            200,
+            fetched_entry(&uuid, entry.log_index, "not hex"),
+        )]);
+
+        match verifier.fetch_inclusion_proof(&entry, "sha256:00").await {
+            Err(TransparencyErrorKind::Rekor(message)) => {
+                assert_eq!(message, "inclusion proof is malformed")
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    /// Returns a v0.1 bundle signed by `signer` over `input_digest`, identified by `hint`, and
+    /// whose log entry is promised by `log_signer`.
+    fn public_key_bundle(
+        signer: &SigningKey,
+        hint: &str,
+        input_digest: &[u8],
+        log_signer: &SigningKey,
+    ) -> Bundle {
+        let mut bundle = public_key_bundle_with_digest(
+            signer,
+            hint,
+            DigestAlgorithm::Sha256,
+            input_digest,
+            log_signer,
+        );
+        // Older bundles do not record the message digest.
+        if let Some(bundle::Content::MessageSignature(signature)) = &mut bundle.content {
+            signature.message_digest = None;
+        }
+        bundle
+    }
+
+    /// Like [`public_key_bundle`], but over a digest computed with `digest_algorithm`, which is
+    /// recorded in the bundle and the log entry.
+    fn public_key_bundle_with_digest(
+        signer: &SigningKey,
+        hint: &str,
+        digest_algorithm: DigestAlgorithm,
+        input_digest: &[u8],
+        log_signer: &SigningKey,
+    ) -> Bundle {
+        let signature: p256::ecdsa::Signature = signer.sign_prehash(input_digest).unwrap();
+        let signature = signature.to_der().as_bytes().to_vec();
+        let public_key_pem = signer
+            .verifying_key()
+            .to_public_key_pem(pkcs8::LineEnding::LF)
+            .unwrap();
+
+        let body = serde_json::json!({
+            "apiVersion": "0.0.1",
+            "kind": "hashedrekord",
+            "spec": {
+                "data": {
+                    "hash": {"algorithm": digest_algorithm.name(), "value": hex::encode(input_digest)},
+                },
+                "signature": {
+                    "content": base64.encode(&signature),
+                    "publicKey": {"content": base64.encode(public_key_pem)},
+                },
+            },
+        });
+        let entry = promised_log_entry(&body, log_signer);
+
+        Bundle {
+            media_type: crate::bundle::Version::Bundle0_1.to_string(),
+            verification_material: Some(VerificationMaterial {
+                content: Some(verification_material::Content::PublicKey(
+                    PublicKeyIdentifier {
+                        hint: hint.to_owned(),
+                    },
+                )),
+                tlog_entries: vec![entry],
+                timestamp_verification_data: None,
+            }),
+            content: Some(bundle::Content::MessageSignature(MessageSignature {
+                message_digest: Some(HashOutput {
+                    algorithm: HashAlgorithm::from(digest_algorithm).into(),
+                    digest: input_digest.to_vec(),
+                }),
+                signature,
+            })),
+        }
+    }
+
+    /// Returns a log entry with the given body, whose inclusion is promised by `log_signer`.
+    pub(crate) fn promised_log_entry(
+        body: &serde_json::Value,
+        log_signer: &SigningKey,
+    ) -> TransparencyLogEntry {
+        let log_spki = log_signer.verifying_key().to_public_key_der().unwrap();
+        let log_id: [u8; 32] = Sha256::digest(log_spki.as_bytes()).into();
+
+        let mut entry = TransparencyLogEntry {
+            log_index: 0,
+            log_id: Some(LogId {
+                key_id: log_id.to_vec(),
+            }),
+            kind_version: None,
+            integrated_time: chrono::Utc::now().timestamp(),
+            inclusion_promise: None,
+            inclusion_proof: None,
+            canonicalized_body: serde_json::to_vec(body).unwrap(),
+        };
+        let payload = SignedEntryTimestampPayload {
+            body: base64.encode(&entry.canonicalized_body),
+            integrated_time: entry.integrated_time,
+            log_id: hex::encode(log_id),
+            log_index: entry.log_index,
+        };
+        let mut payload = json_syntax::to_value(payload).unwrap();
+        payload.canonicalize();
+        let set: p256::ecdsa::Signature =
+            log_signer.sign(payload.compact_print().to_string().as_bytes());
+        entry.inclusion_promise = Some(InclusionPromise {
+            signed_entry_timestamp: set.to_der().as_bytes().to_vec(),
+        });
+
+        entry
+    }
+
+    /// Returns a v0.1 bundle holding a DSSE envelope over `statement`, signed by `signer` and
+    /// identified by `hint`, and whose `dsse` log entry is promised by `log_signer`.
+    fn public_key_dsse_bundle(
+        signer: &SigningKey,
+        hint: &str,
+        statement: &serde_json::Value,
+        log_signer: &SigningKey,
+    ) -> Bundle {
+        let payload = serde_json::to_vec(statement).unwrap();
+        let signature: p256::ecdsa::Signature =
+            signer.sign(&dsse::pae(dsse::IN_TOTO_PAYLOAD_TYPE, &payload));
+        let signature = signature.to_der().as_bytes().to_vec();
+        let public_key_pem = signer
+            .verifying_key()
+            .to_public_key_pem(pkcs8::LineEnding::LF)
+            .unwrap();
+
+        let body = serde_json::json!({
+            "apiVersion": "0.0.1",
+            "kind": "dsse",
+            "spec": {
+                "envelopeHash": {"algorithm": "sha256", "value": "00"},
+                "payloadHash": {"algorithm": "sha256", "value": hex::encode(Sha256::digest(&payload))},
+                "signatures": [{
+                    "signature": base64.encode(&signature),
+                    "verifier": base64.encode(public_key_pem),
+                }],
+            },
+        });
+
+        Bundle {
+            media_type: crate::bundle::Version::Bundle0_1.to_string(),
+            verification_material: Some(VerificationMaterial {
+                content: Some(verification_material::Content::PublicKey(
+                    PublicKeyIdentifier {
+                        hint: hint.to_owned(),
+                    },
+                )),
+                tlog_entries: vec![promised_log_entry(&body, log_signer)],
+                timestamp_verification_data: None,
+            }),
+            content: Some(bundle::Content::DsseEnvelope(dsse::Envelope {
+                payload,
+                payload_type: dsse::IN_TOTO_PAYLOAD_TYPE.to_owned(),
+                signatures: vec![dsse::EnvelopeSignature {
+                    sig: signature,
+                    keyid: String::new(),
+                }],
+            })),
+        }
+    }
+
+    fn public_key_verifier(log_signer: &SigningKey, trusted: &SigningKey) -> Verifier {
+        let log_spki = log_signer.verifying_key().to_public_key_der().unwrap();
+        let trust_root = ManualTrustRoot {
+            rekor_keys: vec![log_spki.as_bytes().to_vec()],
+            ..Default::default()
+        };
+        let trusted_key = CosignVerificationKey::ECDSA_P256_SHA256_ASN1(*trusted.verifying_key());
+
+        Verifier::new(Default::default(), trust_root)
+            .unwrap()
+            .with_trusted_keys([("trusted".to_owned(), trusted_key)])
+    }
+
+    #[tokio::test]
+    async fn verify_with_document_enforces_requirements() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let bundle = public_key_bundle(&signer, "trusted", &input_digest, &log_signer);
+        let document = |requirements: &str| {
+            PolicyDocument::from_json(&format!(
+                r#"{{ "certificate": {{ "extension": {{ "build_trigger": "push" }} }}, {requirements} }}"#
+            ))
+            .unwrap()
+        };
+
+        assert!(verifier
+            .verify_with_document(
+                &input[..],
+                bundle.clone(),
+                &document(r#""min_transparency_log_entries": 1"#),
+                true,
+            )
+            .await
+            .is_ok());
+
+        // The bundle carries no signed timestamp, and is recorded in a single log.
+        for requirements in [
+            r#""min_signed_timestamps": 1"#,
+            r#""min_transparency_log_entries": 2"#,
+        ] {
+            assert!(matches!(
+                verifier
+                    .verify_digest_with_document(
+                        Sha256::new().chain_update(input),
+                        bundle.clone(),
+                        &document(requirements),
+                        true,
+                    )
+                    .await,
+                Err(VerificationError::InsufficientEvidence { .. })
+            ));
+        }
+        assert!(matches!(
+            verifier
+                .verify_with_document(
+                    &input[..],
+                    bundle,
+                    &document(r#""signing_time": { "not_after": "2024-01-01T00:00:00Z" }"#),
+                    true,
+                )
+                .await,
+            Err(VerificationError::Policy(
+                PolicyError::SigningTimeOutsideWindow(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_public_key_bundle() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+        // Not evaluated for bundles signed with a trusted key.
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let bundle = public_key_bundle(&signer, "trusted", &input_digest, &log_signer);
+
+        let outcome = verifier
+            .verify(&input[..], bundle.clone(), &policy, true)
+            .await
+            .unwrap();
+        assert!(matches!(
+            outcome.signer,
+            SignerIdentity::PublicKey { hint } if hint == "trusted"
+        ));
+        let [log_entry] = &outcome.log_entries[..] else {
+            panic!("expected 1 log entry, got {:?}", outcome.log_entries);
+        };
+        assert_eq!(log_entry.log_index, 0);
+        let [signing_time] = &outcome.signing_times[..] else {
+            panic!("expected 1 signing time, got {:?}", outcome.signing_times);
+        };
+        assert_eq!(signing_time.source, SigningTimeSource::TransparencyLog);
+
+        // Wrong input.
+        assert!(verifier
+            .verify(&b"goodbye world"[..], bundle, &policy, true)
+            .await
+            .is_err());
+
+        // Unknown key hint.
+        let bundle = public_key_bundle(&signer, "untrusted", &input_digest, &log_signer);
+        assert!(matches!(
+            verifier.verify(&input[..], bundle, &policy, true).await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::KeyUntrusted(_)
+            ))
+        ));
+
+        // Signed by another key under a trusted hint.
+        let other_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let bundle = public_key_bundle(&other_signer, "trusted", &input_digest, &log_signer);
+        assert!(matches!(
+            verifier.verify(&input[..], bundle, &policy, true).await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::VerificationFailed(_)
+            ))
+        ));
+
+        // Promised by an untrusted log.
+        let other_log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let bundle = public_key_bundle(&signer, "trusted", &input_digest, &other_log_signer);
+        assert!(matches!(
+            verifier.verify(&input[..], bundle, &policy, true).await,
+            Err(VerificationError::Transparency(
+                TransparencyErrorKind::SignedEntryTimestamp(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_public_key_bundle_by_key_id() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let key_id = hex::encode(Sha256::digest(
+            signer
+                .verifying_key()
+                .to_public_key_der()
+                .unwrap()
+                .as_bytes(),
+        ));
+
+        // Keys are found by their ID, or by their signature when the hint is empty. The outcome
+        // names the hint the key is trusted under.
+        for hint in [key_id.as_str(), ""] {
+            let bundle = public_key_bundle(&signer, hint, &input_digest, &log_signer);
+            let outcome = verifier
+                .verify(&input[..], bundle, &policy, true)
+                .await
+                .unwrap();
+            assert!(matches!(
+                outcome.signer,
+                SignerIdentity::PublicKey { hint } if hint == "trusted"
+            ));
+        }
+
+        // Another key's ID.
+        let other_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let bundle = public_key_bundle(&other_signer, &key_id, &input_digest, &log_signer);
+        assert!(matches!(
+            verifier.verify(&input[..], bundle, &policy, true).await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::VerificationFailed(_)
+            ))
+        ));
+
+        // No hint, and no trusted key verifies the signature.
+        let bundle = public_key_bundle(&other_signer, "", &input_digest, &log_signer);
+        assert!(matches!(
+            verifier.verify(&input[..], bundle, &policy, true).await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::KeyUntrusted(_)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_attestation_outcome() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+
+        let input = b"hello world";
+        let statement = serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "hello", "digest": {"sha256": hex::encode(Sha256::digest(input))}}],
+            "predicateType": "https://example.com/Predicate/v1",
+            "predicate": {},
+        });
+        let bundle = public_key_dsse_bundle(&signer, "trusted", &statement, &log_signer);
+
+        let verified = verifier
+            .verify_attestation(&input[..], bundle, &policy, true)
+            .await
+            .unwrap();
+        assert_eq!(verified.content.subject[0].name, "hello");
+        assert!(matches!(
+            verified.outcome.signer,
+            SignerIdentity::PublicKey { hint } if hint == "trusted"
+        ));
+        assert_eq!(verified.outcome.log_entries.len(), 1);
+        assert_eq!(verified.outcome.signing_times.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn verify_sha384_bundle() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+
+        let input = b"hello world";
+        let input_digest = Sha384::digest(input);
+        let bundle = public_key_bundle_with_digest(
+            &signer,
+            "trusted",
+            DigestAlgorithm::Sha384,
+            &input_digest,
+            &log_signer,
+        );
+
+        // The input is hashed with the algorithm recorded in the bundle.
+        assert!(verifier
+            .verify(&input[..], bundle.clone(), &policy, true)
+            .await
+            .is_ok());
+        assert!(verifier
+            .verify_digest(
+                Sha384::new_with_prefix(input),
+                bundle.clone(),
+                &policy,
+                true
+            )
+            .await
+            .is_ok());
+
+        // A digest computed with another algorithm.
+        assert!(matches!(
+            verifier
+                .verify_digest(
+                    Sha256::new_with_prefix(input),
+                    bundle.clone(),
+                    &policy,
+                    true
+                )
+                .await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::DigestMismatch
+            ))
+        ));
+
+        // The bundle and the log entry disagree on the algorithm.
+        let mut mismatched = bundle;
+        let Some(bundle::Content::MessageSignature(signature)) = &mut mismatched.content else {
+            unreachable!();
+        };
+        signature.message_digest = Some(HashOutput {
+            algorithm: HashAlgorithm::Sha2512.into(),
+            digest: Sha512::digest(input).to_vec(),
+        });
+        signature.signature = signer
+            .sign_prehash(&Sha512::digest(input))
+            .map(|sig: p256::ecdsa::Signature| sig.to_der().as_bytes().to_vec())
+            .unwrap();
+        assert!(matches!(
+            verifier.verify(&input[..], mismatched, &policy, true).await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::Transparency
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn verify_digest_bytes() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let verifier = public_key_verifier(&log_signer, &signer);
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+
+        // A digest as found in a checksum file, with no access to the input.
+        let checksum = "sha512:309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f";
+        let (algorithm, digest) = checksum.split_once(':').unwrap();
+        let digest_algorithm: DigestAlgorithm = algorithm.parse().unwrap();
+        let input_digest = hex::decode(digest).unwrap();
+        let bundle = public_key_bundle_with_digest(
+            &signer,
+            "trusted",
+            digest_algorithm,
+            &input_digest,
+            &log_signer,
+        );
+
+        assert!(verifier
+            .verify_digest_bytes(
+                digest_algorithm,
+                &input_digest,
+                bundle.clone(),
+                &policy,
+                true
+            )
+            .await
+            .is_ok());
+        // Same as hashing the input.
+        assert!(verifier
+            .verify(&b"hello world"[..], bundle.clone(), &policy, true)
+            .await
+            .is_ok());
+
+        // A digest of the wrong size for its algorithm.
+        assert!(matches!(
+            verifier
+                .verify_digest_bytes(
+                    DigestAlgorithm::Sha256,
+                    &input_digest,
+                    bundle.clone(),
+                    &policy,
+                    true
+                )
+                .await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::DigestMalformed(DigestAlgorithm::Sha256)
+            ))
+        ));
+
+        // Another digest.
+        assert!(matches!(
+            verifier
+                .verify_digest_bytes(digest_algorithm, &[0; 64], bundle, &policy, true)
+                .await,
+            Err(VerificationError::Signature(
+                SignatureErrorKind::DigestMismatch
+            ))
+        ));
+    }
+
+    /// A verifier trusting `trusted` and the transparency logs of `log_signers`, and `tsa`.
+    pub(crate) fn threshold_verifier(
+        log_signers: &[&SigningKey],
+        trusted: &SigningKey,
+        tsa: &TestTsa,
+        config: VerifierConfig,
+    ) -> Verifier {
+        let rekor_keys = log_signers
+            .iter()
+            .map(|signer| {
+                let spki = signer.verifying_key().to_public_key_der().unwrap();
+                spki.as_bytes().to_vec()
+            })
+            .collect();
+        let trust_root =
+            ManualTrustRoot::new(vec![], rekor_keys, vec![]).with_tsa_certs(tsa.certificates());
+        let trusted_key = CosignVerificationKey::ECDSA_P256_SHA256_ASN1(*trusted.verifying_key());
+
+        Verifier::new(Default::default(), trust_root)
+            .unwrap()
+            .with_trusted_keys([("trusted".to_owned(), trusted_key)])
+            .with_config(config)
+    }
+
+    fn insufficient(result: VerificationResult) -> Option<(&'static str, usize, usize)> {
+        match result {
+            Err(VerificationError::InsufficientEvidence {
+                evidence,
+                required,
+                actual,
+            }) => Some((evidence, required, actual)),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn verify_tlog_threshold() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let other_log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+        let verifier = |tlog_threshold| {
+            threshold_verifier(
+                &[&log_signer, &other_log_signer],
+                &signer,
+                &tsa,
+                VerifierConfig {
+                    tlog_threshold,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let bundle = public_key_bundle(&signer, "trusted", &input_digest, &log_signer);
+        let other_bundle = public_key_bundle(&signer, "trusted", &input_digest, &other_log_signer);
+        let with_entries = |entries: Vec<TransparencyLogEntry>| {
+            let mut bundle = bundle.clone();
+            if let Some(material) = &mut bundle.verification_material {
+                material.tlog_entries = entries;
+            }
+            bundle
+        };
+        let entry = bundle.verification_material.as_ref().unwrap().tlog_entries[0].clone();
+        let other_entry = other_bundle
+            .verification_material
+            .as_ref()
+            .unwrap()
+            .tlog_entries[0]
+            .clone();
+
+        // Entries from two logs.
+        let two_logs = with_entries(vec![entry.clone(), other_entry.clone()]);
+        let outcome = verifier(2)
+            .verify(&input[..], two_logs.clone(), &policy, true)
+            .await
+            .unwrap();
+        assert_eq!(outcome.log_entries.len(), 2);
+        assert_eq!(outcome.signing_times.len(), 2);
+        assert_eq!(
+            insufficient(
+                verifier(3)
+                    .verify(&input[..], two_logs, &policy, true)
+                    .await
+            ),
+            Some(("transparency log entries from distinct logs", 3, 2))
+        );
+
+        // Entries from the same log count once.
+        let same_log = with_entries(vec![entry.clone(), entry.clone()]);
+        assert!(verifier(1)
+            .verify(&input[..], same_log.clone(), &policy, true)
+            .await
+            .is_ok());
+        assert_eq!(
+            insufficient(
+                verifier(2)
+                    .verify(&input[..], same_log, &policy, true)
+                    .await
+            ),
+            Some(("transparency log entries from distinct logs", 2, 1))
+        );
+
+        // Every entry is verified, whether or not it is needed.
+        let mut forged_entry = other_entry;
+        forged_entry.integrated_time += 1;
+        assert!(matches!(
+            verifier(1)
+                .verify(
+                    &input[..],
+                    with_entries(vec![entry, forged_entry]),
+                    &policy,
+                    true
+                )
+                .await,
+            Err(VerificationError::Transparency(
+                TransparencyErrorKind::SignedEntryTimestamp(_)
+            ))
+        ));
+
+        // No entries at all.
+        let no_entries = with_entries(vec![]);
+        assert_eq!(
+            insufficient(
+                verifier(1)
+                    .verify(&input[..], no_entries.clone(), &policy, true)
+                    .await
+            ),
+            Some(("transparency log entries from distinct logs", 1, 0))
+        );
+        let outcome = verifier(0)
+            .verify(&input[..], no_entries, &policy, true)
+            .await
+            .unwrap();
+        assert!(outcome.log_entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unpromised_integrated_time_is_not_trusted() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+        let verifier = |config| threshold_verifier(&[&log_signer], &signer, &tsa, config);
+
+        // A v0.2 bundle whose entry carries an inclusion proof but no SET, and a forged
+        // integrated time.
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let mut bundle = public_key_bundle(&signer, "trusted", &input_digest, &log_signer);
+        bundle.media_type = crate::bundle::Version::Bundle0_2.to_string();
+        let entry = &mut bundle.verification_material.as_mut().unwrap().tlog_entries[0];
+        entry.inclusion_promise = None;
+        entry.integrated_time = 1577836800;
+        let root_hash = merkle::hash_leaf(&entry.canonicalized_body);
+        entry.inclusion_proof = Some(InclusionProof {
+            log_index: entry.log_index,
+            root_hash: root_hash.to_vec(),
+            tree_size: 1,
+            hashes: vec![],
+            checkpoint: Some(Checkpoint {
+                envelope: signed_checkpoint(&log_signer, 1, root_hash).to_string(),
+            }),
+        });
+
+        // The entry counts towards the log threshold, but its integrated time is not a signing
+        // time.
+        let outcome = verifier(Default::default())
+            .verify(&input[..], bundle.clone(), &policy, true)
+            .await
+            .unwrap();
+        assert_eq!(outcome.log_entries.len(), 1);
+        assert!(outcome.signing_times.is_empty());
+
+        assert_eq!(
+            insufficient(
+                verifier(VerifierConfig {
+                    timestamp_threshold: 1,
+                    integrated_time_as_timestamp: true,
+                    ..Default::default()
+                })
+                .verify(&input[..], bundle, &policy, true)
+                .await
+            ),
+            Some(("signed timestamps", 1, 0))
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_timestamp_threshold() {
+        let log_signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let signer = SigningKey::random(&mut rand::rngs::OsRng);
+        let tsa = TestTsa::new();
+        let policy = Identity::new("signer@example.com", "https://issuer.example.com");
+        let verifier = |config| threshold_verifier(&[&log_signer], &signer, &tsa, config);
+
+        let input = b"hello world";
+        let input_digest = Sha256::digest(input);
+        let bundle = public_key_bundle(&signer, "trusted", &input_digest, &log_signer);
+        let Some(bundle::Content::MessageSignature(signature)) = &bundle.content else {
+            unreachable!();
+        };
+        let mut timestamped = bundle.clone();
+        if let Some(material) = &mut timestamped.verification_material {
+            material.timestamp_verification_data = Some(TimestampVerificationData {
+                rfc3161_timestamps: vec![Rfc3161SignedTimestamp {
+                    signed_timestamp: tsa.timestamp(&signature.signature),
+                }],
+            });
+        }
+
+        let one_timestamp = VerifierConfig {
+            timestamp_threshold: 1,
+            ..Default::default()
+        };
+        assert!(verifier(one_timestamp.clone())
+            .verify(&input[..], timestamped.clone(), &policy, true)
+            .await
+            .is_ok());
+        assert_eq!(
+            insufficient(
+                verifier(one_timestamp.clone())
+                    .verify(&input[..], bundle.clone(), &policy, true)
+                    .await
+            ),
+            Some(("signed timestamps", 1, 0))
+        );
+
+        // The integrated time may stand in for a signed timestamp.
+        let integrated_time_as_timestamp = VerifierConfig {
+            integrated_time_as_timestamp: true,
+            ..one_timestamp
+        };
+        assert!(verifier(integrated_time_as_timestamp.clone())
+            .verify(&input[..], bundle, &policy, true)
+            .await
+            .is_ok());
+        let two_timestamps = VerifierConfig {
+            timestamp_threshold: 2,
+            ..integrated_time_as_timestamp
+        };
+        assert!(verifier(two_timestamps.clone())
+            .verify(&input[..], timestamped.clone(), &policy, true)
+            .await
+            .is_ok());
+        assert_eq!(
+            insufficient(
+                verifier(VerifierConfig {
+                    integrated_time_as_timestamp: false,
+                    ..two_timestamps
+                })
+                .verify(&input[..], timestamped.clone(), &policy, true)
+                .await
+            ),
+            Some(("signed timestamps", 2, 1))
+        );
+
+        // A bundle without transparency log entries, vouched for by its timestamp alone.
+        let mut timestamp_only = timestamped;
+        if let Some(material) = &mut timestamp_only.verification_material {
+            material.tlog_entries.clear();
+        }
+        let outcome = verifier(VerifierConfig {
+            tlog_threshold: 0,
+            timestamp_threshold: 1,
+            ..Default::default()
+        })
+        .verify(&input[..], timestamp_only, &policy, true)
+        .await
+        .unwrap();
+        let [signing_time] = &outcome.signing_times[..] else {
+            panic!("expected 1 signing time, got {:?}", outcome.signing_times);
+        };
+        assert_eq!(signing_time.source, SigningTimeSource::TimestampAuthority);
+    }
+
+    /// A trust root holding a single Fulcio certificate authority with a validity period.
+    struct WindowedTrustRoot {
+        ca: Vec<u8>,
+        valid_for: ValidityPeriod,
+    }
+
+    impl TrustRoot for WindowedTrustRoot {
+        fn fulcio_certs(&self) -> SigstoreResult<Vec<CertificateDer<'_>>> {
+            Ok(vec![CertificateDer::from(self.ca.as_slice())])
+        }
+
+        fn rekor_keys(&self) -> SigstoreResult<Vec<&[u8]>> {
+            Ok(vec![])
+        }
+
+        fn ctfe_keys(&self) -> SigstoreResult<Vec<&[u8]>> {
+            Ok(vec![])
+        }
+
+        fn fulcio_authorities(&self) -> SigstoreResult<Vec<CertificateAuthority<'_>>> {
+            Ok(vec![CertificateAuthority {
+                certs: self.fulcio_certs()?,
+                valid_for: self.valid_for,
+            }])
+        }
+    }
+
+    #[test]
+    fn verify_certificate_chain_validity_period() {
+        let ca = generate_certificate(None, CertGenerationOptions::default()).unwrap();
+        let leaf = generate_certificate(Some(&ca), CertGenerationOptions::default()).unwrap();
+        let leaf_der = CertificateDer::from(leaf.cert.to_der().unwrap());
+        let ee_cert = EndEntityCert::try_from(&leaf_der).unwrap();
+        let leaf = Certificate::from_der(&leaf_der).unwrap();
+        let issued_at = leaf.tbs_certificate.validity.not_before.to_unix_duration();
+
+        let now = Utc::now();
+        let verifier = |valid_for| {
+            let trust_root = WindowedTrustRoot {
+                ca: ca.cert.to_der().unwrap(),
+                valid_for,
+            };
+            Verifier::new(Default::default(), trust_root).unwrap()
+        };
+
+        // The CA has since been rotated out, but was valid when the certificate was issued.
+        let rotated_out = verifier(ValidityPeriod {
+            start: Some(now - TimeDelta::days(3)),
+            end: Some(now - TimeDelta::hours(12)),
+        });
+        assert!(rotated_out
+            .verify_certificate_chain(&ee_cert, issued_at)
+            .is_ok());
+
+        // The CA was not yet valid when the certificate was issued.
+        let not_yet_valid = verifier(ValidityPeriod {
+            start: Some(now - TimeDelta::hours(12)),
+            end: None,
+        });
+        assert!(matches!(
+            not_yet_valid.verify_certificate_chain(&ee_cert, issued_at),
+            Err(webpki::Error::UnknownIssuer)
+        ));
+    }
+}
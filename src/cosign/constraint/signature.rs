@@ -15,24 +15,42 @@
 
 //! Structs that can be used to sign a [`crate::cosign::SignatureLayer`]
 
+use std::{fmt, future::Future};
+
 use base64::{engine::general_purpose::STANDARD as BASE64_STD_ENGINE, Engine as _};
+use tokio::runtime::Handle;
 use tracing::warn;
 use zeroize::Zeroizing;
 
 use crate::{
     cosign::SignatureLayer,
-    crypto::{signing_key::SigStoreKeyPair, SigStoreSigner, SigningScheme},
+    crypto::{signer::AsyncSigner, signing_key::SigStoreKeyPair, SigStoreSigner, SigningScheme},
     errors::{Result, SigstoreError},
 };
 
 use super::Constraint;
 
-/// Sign the [`SignatureLayer`] with the given [`SigStoreSigner`].
+/// Sign the [`SignatureLayer`] with the given [`SigStoreSigner`] or [`AsyncSigner`].
 /// This constraint must be the last one to applied to a [`SignatureLayer`],
 /// since all the plaintext is defined.
 #[derive(Debug)]
 pub struct PrivateKeySigner {
-    key: SigStoreSigner,
+    key: SignerKey,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum SignerKey {
+    SigStore(SigStoreSigner),
+    Async(Box<dyn AsyncSigner>),
+}
+
+impl fmt::Debug for SignerKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SigStore(signer) => f.debug_tuple("SigStore").field(signer).finish(),
+            Self::Async(_) => f.debug_tuple("Async").finish_non_exhaustive(),
+        }
+    }
 }
 
 impl PrivateKeySigner {
@@ -51,11 +69,28 @@ impl PrivateKeySigner {
         .to_sigstore_signer(signing_scheme)
         .map_err(|e| SigstoreError::ApplyConstraintError(e.to_string()))?;
 
-        Ok(Self { key: signer })
+        Ok(Self::new_with_signer(signer))
     }
 
     pub fn new_with_signer(signer: SigStoreSigner) -> Self {
-        Self { key: signer }
+        Self {
+            key: SignerKey::SigStore(signer),
+        }
+    }
+
+    /// Create a new [PrivateKeySigner] signing with `signer`, whose key
+    /// may live outside of the process, e.g. in an HSM or a cloud KMS.
+    ///
+    /// Signing blocks on `signer`: within a multi-threaded Tokio runtime,
+    /// the calling worker thread is handed over to the runtime's other tasks
+    /// while it waits. Otherwise, `signer` runs on a dedicated runtime.
+    pub fn new_with_async_signer<S>(signer: S) -> Self
+    where
+        S: AsyncSigner + 'static,
+    {
+        Self {
+            key: SignerKey::Async(Box::new(signer)),
+        }
     }
 }
 
@@ -66,9 +101,83 @@ impl Constraint for PrivateKeySigner {
             return Ok(false);
         }
         signature_layer.raw_data = serde_json::to_vec(&signature_layer.simple_signing)?;
-        let sig = self.key.sign(&signature_layer.raw_data)?;
+        let sig = match &self.key {
+            SignerKey::SigStore(signer) => signer.sign(&signature_layer.raw_data)?,
+            SignerKey::Async(signer) => block_on(signer.sign(&signature_layer.raw_data))??,
+        };
         let sig_base64 = BASE64_STD_ENGINE.encode(sig);
         signature_layer.signature = Some(sig_base64);
         Ok(true)
     }
 }
+
+/// Runs `future` to completion from synchronous code, whether or not it is
+/// called from within a Tokio runtime.
+fn block_on<F>(future: F) -> Result<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    let private_runtime = || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+    };
+
+    match Handle::try_current() {
+        // A runtime's worker thread cannot be blocked on, nor start another
+        // runtime: wait for a private runtime running on another thread.
+        Ok(_) => std::thread::scope(|scope| {
+            scope
+                .spawn(|| Ok(private_runtime()?.block_on(future)))
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        }),
+        Err(_) => Ok(private_runtime()?.block_on(future)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{signer::SoftwareSigner, CosignVerificationKey, Signature};
+
+    fn signature_layer() -> SignatureLayer {
+        let image_ref = "registry.example.com/app:v1".parse().unwrap();
+        let manifest_digest =
+            "sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        SignatureLayer::new_unsigned(&image_ref, manifest_digest).unwrap()
+    }
+
+    fn sign_with_async_signer() {
+        let signer = SoftwareSigner::generate(SigningScheme::ECDSA_P256_SHA256_ASN1).unwrap();
+        let public_key = signer.public_key_der().unwrap();
+        let constraint = PrivateKeySigner::new_with_async_signer(signer);
+
+        let mut layer = signature_layer();
+        assert!(constraint.add_constraint(&mut layer).unwrap());
+
+        let signature = BASE64_STD_ENGINE.decode(layer.signature.unwrap()).unwrap();
+        let key =
+            CosignVerificationKey::from_der(&public_key, &SigningScheme::ECDSA_P256_SHA256_ASN1)
+                .unwrap();
+        assert!(key
+            .verify_signature(Signature::Raw(&signature), &layer.raw_data)
+            .is_ok());
+    }
+
+    #[test]
+    fn async_signer_without_runtime() {
+        sign_with_async_signer();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn async_signer_within_multi_thread_runtime() {
+        sign_with_async_signer();
+    }
+
+    #[tokio::test]
+    async fn async_signer_within_current_thread_runtime() {
+        sign_with_async_signer();
+    }
+}
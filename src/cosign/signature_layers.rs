@@ -13,7 +13,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use const_oid::ObjectIdentifier;
 use digest::Digest;
 use oci_client::client::ImageLayer;
 use serde::Serialize;
@@ -27,12 +26,11 @@ use x509_cert::Certificate;
 
 use super::bundle::Bundle;
 use super::constants::{
-    SIGSTORE_BUNDLE_ANNOTATION, SIGSTORE_CERT_ANNOTATION, SIGSTORE_GITHUB_WORKFLOW_NAME_OID,
-    SIGSTORE_GITHUB_WORKFLOW_REF_OID, SIGSTORE_GITHUB_WORKFLOW_REPOSITORY_OID,
-    SIGSTORE_GITHUB_WORKFLOW_SHA_OID, SIGSTORE_GITHUB_WORKFLOW_TRIGGER_OID, SIGSTORE_ISSUER_OID,
-    SIGSTORE_OCI_MEDIA_TYPE, SIGSTORE_SIGNATURE_ANNOTATION,
+    SIGSTORE_BUNDLE_ANNOTATION, SIGSTORE_CERT_ANNOTATION, SIGSTORE_OCI_MEDIA_TYPE,
+    SIGSTORE_SIGNATURE_ANNOTATION,
 };
 use crate::crypto::certificate_pool::CertificatePool;
+use crate::crypto::FulcioCertificateExtensions;
 use crate::registry::oci_reference::OciReference;
 use crate::{
     cosign::simple_signing::SimpleSigning,
@@ -61,6 +59,9 @@ pub struct CertificateSignature {
     pub github_workflow_repository: Option<String>,
     /// The Git ref of the commit that triggered the GitHub workflow (e.g. `refs/tags/v0.9.9`)
     pub github_workflow_ref: Option<String>,
+    /// All the Fulcio extensions of the certificate, including the CI provider-agnostic build
+    /// details (e.g. `source_repository_uri`, `build_trigger`)
+    pub extensions: FulcioCertificateExtensions,
 }
 
 impl fmt::Display for CertificateSignature {
@@ -74,6 +75,7 @@ impl fmt::Display for CertificateSignature {
 - GitHub Workflow name: {:?}
 - GitHub Workflow repository: {:?}
 - GitHub Workflow ref: {:?}
+- Fulcio extensions: {:?}
 ---"#,
             self.issuer,
             self.subject,
@@ -82,6 +84,7 @@ impl fmt::Display for CertificateSignature {
             self.github_workflow_name,
             self.github_workflow_repository,
             self.github_workflow_ref,
+            self.extensions,
         );
 
         write!(f, "{msg}")
@@ -454,74 +457,23 @@ impl CertificateSignature {
                     ))
                 })?;
 
-        let issuer = get_cert_extension_by_oid(&cert, SIGSTORE_ISSUER_OID, "Issuer")?;
-
-        let github_workflow_trigger = get_cert_extension_by_oid(
-            &cert,
-            SIGSTORE_GITHUB_WORKFLOW_TRIGGER_OID,
-            "GitHub Workflow trigger",
-        )?;
-
-        let github_workflow_sha = get_cert_extension_by_oid(
-            &cert,
-            SIGSTORE_GITHUB_WORKFLOW_SHA_OID,
-            "GitHub Workflow sha",
-        )?;
-
-        let github_workflow_name = get_cert_extension_by_oid(
-            &cert,
-            SIGSTORE_GITHUB_WORKFLOW_NAME_OID,
-            "GitHub Workflow name",
-        )?;
-
-        let github_workflow_repository = get_cert_extension_by_oid(
-            &cert,
-            SIGSTORE_GITHUB_WORKFLOW_REPOSITORY_OID,
-            "GitHub Workflow repository",
-        )?;
-
-        let github_workflow_ref = get_cert_extension_by_oid(
-            &cert,
-            SIGSTORE_GITHUB_WORKFLOW_REF_OID,
-            "GitHub Workflow ref",
-        )?;
+        // Repeated or malformed extensions are left unset, rather than discarding the signature.
+        let extensions = FulcioCertificateExtensions::from_certificate_lossy(&cert);
 
         Ok(CertificateSignature {
             verification_key,
-            issuer,
-            github_workflow_trigger,
-            github_workflow_sha,
-            github_workflow_name,
-            github_workflow_repository,
-            github_workflow_ref,
+            issuer: extensions.issuer.clone(),
+            github_workflow_trigger: extensions.github_workflow_trigger.clone(),
+            github_workflow_sha: extensions.github_workflow_sha.clone(),
+            github_workflow_name: extensions.github_workflow_name.clone(),
+            github_workflow_repository: extensions.github_workflow_repository.clone(),
+            github_workflow_ref: extensions.github_workflow_ref.clone(),
+            extensions,
             subject,
         })
     }
 }
 
-fn get_cert_extension_by_oid(
-    cert: &Certificate,
-    ext_oid: ObjectIdentifier,
-    ext_oid_name: &str,
-) -> Result<Option<String>> {
-    cert.tbs_certificate
-        .extensions
-        .as_ref()
-        .ok_or(SigstoreError::X509Error(
-            "Certificate's extension is empty".to_string(),
-        ))?
-        .iter()
-        .find(|ext| ext.extn_id == ext_oid)
-        .map(|ext| {
-            String::from_utf8(ext.extn_value.clone().into_bytes()).map_err(|_| {
-                SigstoreError::X509Error(format!(
-                    "Certificate's extension Sigstore {ext_oid_name} is not UTF8 compatible"
-                ))
-            })
-        })
-        .transpose()
-}
-
 impl CertificateSubject {
     pub fn from_certificate(certificate: &Certificate) -> Result<CertificateSubject> {
         let (_, san) = certificate
@@ -862,8 +814,8 @@ JsB89BPhZYch0U0hKANx5TY+ncrm0s8bfJxxHoenAEFhwhuXeb4PqIrtoQ==
 
     // Testing CertificateSignature
     use crate::cosign::bundle::Payload;
-    use crate::crypto::tests::{generate_certificate, CertGenerationOptions};
-    use crate::crypto::SigningScheme;
+    use crate::crypto::tests::{generate_certificate, generate_rsa_keypair, CertGenerationOptions};
+    use crate::crypto::{Signature, SigningScheme};
     use chrono::{TimeDelta, Utc};
 
     impl TryFrom<X509> for crate::registry::Certificate {
@@ -977,6 +929,60 @@ JsB89BPhZYch0U0hKANx5TY+ncrm0s8bfJxxHoenAEFhwhuXeb4PqIrtoQ==
         Ok(())
     }
 
+    #[test]
+    fn certificate_signature_from_rsa_certificate() -> anyhow::Result<()> {
+        let ca_data = generate_certificate(None, CertGenerationOptions::default())?;
+
+        let (private_key, public_key) = generate_rsa_keypair(2048);
+        let issued_cert = generate_certificate(
+            Some(&ca_data),
+            CertGenerationOptions {
+                subject_email: Some("test@sigstore.dev".to_string()),
+                private_key,
+                public_key,
+                ..Default::default()
+            },
+        )?;
+
+        let issued_cert_pem = issued_cert.cert.to_pem()?;
+
+        let certs = vec![crate::registry::Certificate::try_from(ca_data.cert)
+            .unwrap()
+            .try_into()?];
+        let cert_pool = CertificatePool::from_certificates(certs, []).unwrap();
+
+        let integrated_time = Utc::now()
+            .checked_sub_signed(TimeDelta::try_minutes(1).unwrap())
+            .unwrap();
+        let bundle = Bundle {
+            signed_entry_timestamp: "not relevant".to_string(),
+            payload: Payload {
+                body: "not relevant".to_string(),
+                integrated_time: integrated_time.timestamp(),
+                log_index: 0,
+                log_id: "not relevant".to_string(),
+            },
+        };
+
+        let certificate_signature =
+            CertificateSignature::from_certificate(&issued_cert_pem, &cert_pool, &bundle)
+                .expect("Didn't expect an error");
+
+        // PKCS#1 v1.5 signatures, as made by cosign, embed the DigestInfo of the message digest.
+        let msg = b"some message";
+        let mut signer = openssl::sign::Signer::new(
+            openssl::hash::MessageDigest::sha256(),
+            &issued_cert.private_key,
+        )?;
+        let signature = signer.sign_oneshot_to_vec(msg)?;
+        assert!(certificate_signature
+            .verification_key
+            .verify_signature(Signature::Raw(&signature), msg)
+            .is_ok());
+
+        Ok(())
+    }
+
     #[test]
     fn certificate_signature_from_certificate_without_email_and_uri() -> anyhow::Result<()> {
         let ca_data = generate_certificate(None, CertGenerationOptions::default())?;
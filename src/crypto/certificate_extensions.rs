@@ -0,0 +1,343 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The X.509 extensions Fulcio records in signing certificates, describing the signer's OIDC
+//! token and, for CI workloads, the build that produced the signature.
+//!
+//! <https://github.com/sigstore/fulcio/blob/main/docs/oid-info.md>
+
+use const_oid::ObjectIdentifier;
+use serde::Serialize;
+use x509_cert::der::{asn1::Utf8StringRef, Decode};
+use x509_cert::ext::Extension;
+use x509_cert::Certificate;
+
+use crate::errors::{Result, SigstoreError};
+
+macro_rules! oids {
+    ($($(#[$meta:meta])* $name:ident = $value:literal),+ $(,)?) => {
+        $(
+            $(#[$meta])*
+            pub const $name: ObjectIdentifier = ObjectIdentifier::new_unwrap($value);
+        )+
+    };
+}
+
+oids! {
+    /// The OIDC issuer, as raw bytes. Deprecated in favor of [`ISSUER_V2`].
+    ISSUER = "1.3.6.1.4.1.57264.1.1",
+    /// The GitHub Actions workflow trigger, as raw bytes. Deprecated in favor of
+    /// [`BUILD_TRIGGER`].
+    GITHUB_WORKFLOW_TRIGGER = "1.3.6.1.4.1.57264.1.2",
+    /// The GitHub Actions workflow commit SHA, as raw bytes. Deprecated in favor of
+    /// [`SOURCE_REPOSITORY_DIGEST`].
+    GITHUB_WORKFLOW_SHA = "1.3.6.1.4.1.57264.1.3",
+    /// The GitHub Actions workflow name, as raw bytes. Deprecated.
+    GITHUB_WORKFLOW_NAME = "1.3.6.1.4.1.57264.1.4",
+    /// The GitHub Actions workflow repository, as raw bytes. Deprecated in favor of
+    /// [`SOURCE_REPOSITORY_URI`].
+    GITHUB_WORKFLOW_REPOSITORY = "1.3.6.1.4.1.57264.1.5",
+    /// The GitHub Actions workflow ref, as raw bytes. Deprecated in favor of
+    /// [`SOURCE_REPOSITORY_REF`].
+    GITHUB_WORKFLOW_REF = "1.3.6.1.4.1.57264.1.6",
+    /// The OIDC issuer.
+    ISSUER_V2 = "1.3.6.1.4.1.57264.1.8",
+    /// A reference to the build instructions that signed, e.g. a reusable workflow.
+    BUILD_SIGNER_URI = "1.3.6.1.4.1.57264.1.9",
+    /// An immutable reference to the build instructions that signed.
+    BUILD_SIGNER_DIGEST = "1.3.6.1.4.1.57264.1.10",
+    /// Whether the build ran on platform-hosted or self-hosted infrastructure.
+    RUNNER_ENVIRONMENT = "1.3.6.1.4.1.57264.1.11",
+    /// The URI of the source repository the build ran from.
+    SOURCE_REPOSITORY_URI = "1.3.6.1.4.1.57264.1.12",
+    /// An immutable reference to the source code the build ran from, e.g. a commit SHA.
+    SOURCE_REPOSITORY_DIGEST = "1.3.6.1.4.1.57264.1.13",
+    /// The source repository ref the build ran from, e.g. `refs/heads/main`.
+    SOURCE_REPOSITORY_REF = "1.3.6.1.4.1.57264.1.14",
+    /// The immutable identifier of the source repository.
+    SOURCE_REPOSITORY_IDENTIFIER = "1.3.6.1.4.1.57264.1.15",
+    /// The URI of the source repository's owner.
+    SOURCE_REPOSITORY_OWNER_URI = "1.3.6.1.4.1.57264.1.16",
+    /// The immutable identifier of the source repository's owner.
+    SOURCE_REPOSITORY_OWNER_IDENTIFIER = "1.3.6.1.4.1.57264.1.17",
+    /// A reference to the top-level build instructions, e.g. the calling workflow.
+    BUILD_CONFIG_URI = "1.3.6.1.4.1.57264.1.18",
+    /// An immutable reference to the top-level build instructions.
+    BUILD_CONFIG_DIGEST = "1.3.6.1.4.1.57264.1.19",
+    /// The event that triggered the build, e.g. `push`.
+    BUILD_TRIGGER = "1.3.6.1.4.1.57264.1.20",
+    /// The URI of the build run, e.g. a link to its logs.
+    RUN_INVOCATION_URI = "1.3.6.1.4.1.57264.1.21",
+    /// The visibility of the source repository when signing, e.g. `public`.
+    SOURCE_REPOSITORY_VISIBILITY_AT_SIGNING = "1.3.6.1.4.1.57264.1.22",
+}
+
+/// The Fulcio extensions of a signing certificate.
+///
+/// Every field is optional: which extensions Fulcio records depends on the OIDC provider that
+/// issued the signer's token. The build fields are set for CI providers such as GitHub Actions,
+/// GitLab CI and Buildkite alike, so that policies can be written independently of the provider.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FulcioCertificateExtensions {
+    /// The OIDC issuer, read from the V2 extension when present and from the deprecated one
+    /// otherwise.
+    pub issuer: Option<String>,
+    /// Deprecated: see [`FulcioCertificateExtensions::build_trigger`].
+    pub github_workflow_trigger: Option<String>,
+    /// Deprecated: see [`FulcioCertificateExtensions::source_repository_digest`].
+    pub github_workflow_sha: Option<String>,
+    /// Deprecated.
+    pub github_workflow_name: Option<String>,
+    /// Deprecated: see [`FulcioCertificateExtensions::source_repository_uri`].
+    pub github_workflow_repository: Option<String>,
+    /// Deprecated: see [`FulcioCertificateExtensions::source_repository_ref`].
+    pub github_workflow_ref: Option<String>,
+    /// A reference to the build instructions that signed, e.g. a reusable workflow.
+    pub build_signer_uri: Option<String>,
+    /// An immutable reference to the build instructions that signed.
+    pub build_signer_digest: Option<String>,
+    /// Whether the build ran on platform-hosted or self-hosted infrastructure.
+    pub runner_environment: Option<String>,
+    /// The URI of the source repository the build ran from.
+    pub source_repository_uri: Option<String>,
+    /// An immutable reference to the source code the build ran from, e.g. a commit SHA.
+    pub source_repository_digest: Option<String>,
+    /// The source repository ref the build ran from, e.g. `refs/heads/main`.
+    pub source_repository_ref: Option<String>,
+    /// The immutable identifier of the source repository.
+    pub source_repository_identifier: Option<String>,
+    /// The URI of the source repository's owner.
+    pub source_repository_owner_uri: Option<String>,
+    /// The immutable identifier of the source repository's owner.
+    pub source_repository_owner_identifier: Option<String>,
+    /// A reference to the top-level build instructions, e.g. the calling workflow.
+    pub build_config_uri: Option<String>,
+    /// An immutable reference to the top-level build instructions.
+    pub build_config_digest: Option<String>,
+    /// The event that triggered the build, e.g. `push`.
+    pub build_trigger: Option<String>,
+    /// The URI of the build run, e.g. a link to its logs.
+    pub run_invocation_uri: Option<String>,
+    /// The visibility of the source repository when signing, e.g. `public`.
+    pub source_repository_visibility_at_signing: Option<String>,
+}
+
+impl FulcioCertificateExtensions {
+    /// Parses the Fulcio extensions of `cert`. Other extensions are ignored.
+    ///
+    /// Fails if an extension is repeated, or is not encoded as Fulcio specifies: the deprecated
+    /// extensions hold raw UTF-8 strings, and the others DER-encoded `UTF8String`s.
+    pub fn from_certificate(cert: &Certificate) -> Result<Self> {
+        Self::parse(cert, false)
+    }
+
+    /// Parses the Fulcio extensions of `cert` like [`Self::from_certificate`], but leaves
+    /// repeated or malformed extensions unset instead of failing.
+    #[cfg(any(feature = "cosign", feature = "verify"))]
+    pub(crate) fn from_certificate_lossy(cert: &Certificate) -> Self {
+        Self::parse(cert, true).unwrap_or_default()
+    }
+
+    /// Parses the Fulcio extensions of `cert`. Repeated or malformed extensions are errors, unless
+    /// `lossy` is set: they are then left unset.
+    fn parse(cert: &Certificate, lossy: bool) -> Result<Self> {
+        let mut extensions = Self::default();
+        let mut issuer_v1 = None;
+        let mut rejected = Vec::new();
+
+        for ext in cert.tbs_certificate.extensions.iter().flatten() {
+            let field = match ext.extn_id {
+                ISSUER => &mut issuer_v1,
+                GITHUB_WORKFLOW_TRIGGER => &mut extensions.github_workflow_trigger,
+                GITHUB_WORKFLOW_SHA => &mut extensions.github_workflow_sha,
+                GITHUB_WORKFLOW_NAME => &mut extensions.github_workflow_name,
+                GITHUB_WORKFLOW_REPOSITORY => &mut extensions.github_workflow_repository,
+                GITHUB_WORKFLOW_REF => &mut extensions.github_workflow_ref,
+                ISSUER_V2 => &mut extensions.issuer,
+                BUILD_SIGNER_URI => &mut extensions.build_signer_uri,
+                BUILD_SIGNER_DIGEST => &mut extensions.build_signer_digest,
+                RUNNER_ENVIRONMENT => &mut extensions.runner_environment,
+                SOURCE_REPOSITORY_URI => &mut extensions.source_repository_uri,
+                SOURCE_REPOSITORY_DIGEST => &mut extensions.source_repository_digest,
+                SOURCE_REPOSITORY_REF => &mut extensions.source_repository_ref,
+                SOURCE_REPOSITORY_IDENTIFIER => &mut extensions.source_repository_identifier,
+                SOURCE_REPOSITORY_OWNER_URI => &mut extensions.source_repository_owner_uri,
+                SOURCE_REPOSITORY_OWNER_IDENTIFIER => {
+                    &mut extensions.source_repository_owner_identifier
+                }
+                BUILD_CONFIG_URI => &mut extensions.build_config_uri,
+                BUILD_CONFIG_DIGEST => &mut extensions.build_config_digest,
+                BUILD_TRIGGER => &mut extensions.build_trigger,
+                RUN_INVOCATION_URI => &mut extensions.run_invocation_uri,
+                SOURCE_REPOSITORY_VISIBILITY_AT_SIGNING => {
+                    &mut extensions.source_repository_visibility_at_signing
+                }
+                _ => continue,
+            };
+
+            let value = if field.is_some() || rejected.contains(&ext.extn_id) {
+                Err(SigstoreError::X509Error(format!(
+                    "Certificate has more than one Fulcio extension {}",
+                    ext.extn_id
+                )))
+            } else {
+                extension_value(ext)
+            };
+            match value {
+                Ok(value) => *field = Some(value),
+                Err(_) if lossy => {
+                    *field = None;
+                    rejected.push(ext.extn_id);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        extensions.issuer = extensions.issuer.or(issuer_v1);
+        Ok(extensions)
+    }
+}
+
+/// Decodes the value of the Fulcio extension `ext`, following the encoding of its OID.
+pub(crate) fn extension_value(ext: &Extension) -> Result<String> {
+    let bytes = ext.extn_value.as_bytes();
+    let value = match ext.extn_id {
+        ISSUER
+        | GITHUB_WORKFLOW_TRIGGER
+        | GITHUB_WORKFLOW_SHA
+        | GITHUB_WORKFLOW_NAME
+        | GITHUB_WORKFLOW_REPOSITORY
+        | GITHUB_WORKFLOW_REF => std::str::from_utf8(bytes).ok(),
+        _ => Utf8StringRef::from_der(bytes)
+            .ok()
+            .map(|value| value.as_str()),
+    };
+
+    value.map(str::to_owned).ok_or_else(|| {
+        SigstoreError::X509Error(format!(
+            "Certificate's Fulcio extension {} is not a valid string",
+            ext.extn_id
+        ))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::crypto::tests::{generate_certificate, CertGenerationOptions};
+    use x509_cert::der::{asn1::OctetString, Encode};
+
+    /// Returns a certificate for `tests@sigstore-rs.dev`, carrying the given extensions.
+    pub(crate) fn certificate_with(extensions: &[(ObjectIdentifier, Vec<u8>)]) -> Certificate {
+        let ca_data = generate_certificate(None, CertGenerationOptions::default()).unwrap();
+        let issued_cert =
+            generate_certificate(Some(&ca_data), CertGenerationOptions::default()).unwrap();
+        let mut cert = Certificate::from_der(&issued_cert.cert.to_der().unwrap()).unwrap();
+
+        cert.tbs_certificate
+            .extensions
+            .get_or_insert_with(Vec::new)
+            .extend(extensions.iter().map(|(oid, value)| Extension {
+                extn_id: *oid,
+                critical: false,
+                extn_value: OctetString::new(value.clone()).unwrap(),
+            }));
+        cert
+    }
+
+    /// Returns the DER encoding of `value`, as held by Fulcio's V2 extensions.
+    pub(crate) fn der(value: &str) -> Vec<u8> {
+        Utf8StringRef::new(value).unwrap().to_der().unwrap()
+    }
+
+    #[test]
+    fn from_certificate_parses_extensions() {
+        let cert = certificate_with(&[
+            (
+                ISSUER,
+                b"https://token.actions.githubusercontent.com".to_vec(),
+            ),
+            (GITHUB_WORKFLOW_TRIGGER, b"push".to_vec()),
+            (BUILD_TRIGGER, der("push")),
+            (
+                SOURCE_REPOSITORY_URI,
+                der("https://gitlab.com/sigstore/sigstore-rs"),
+            ),
+            (SOURCE_REPOSITORY_REF, der("refs/heads/main")),
+            (RUNNER_ENVIRONMENT, der("gitlab-hosted")),
+            (SOURCE_REPOSITORY_VISIBILITY_AT_SIGNING, der("public")),
+        ]);
+
+        let extensions = FulcioCertificateExtensions::from_certificate(&cert).unwrap();
+        assert_eq!(
+            extensions,
+            FulcioCertificateExtensions {
+                issuer: Some("https://token.actions.githubusercontent.com".into()),
+                github_workflow_trigger: Some("push".into()),
+                build_trigger: Some("push".into()),
+                source_repository_uri: Some("https://gitlab.com/sigstore/sigstore-rs".into()),
+                source_repository_ref: Some("refs/heads/main".into()),
+                runner_environment: Some("gitlab-hosted".into()),
+                source_repository_visibility_at_signing: Some("public".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn from_certificate_prefers_issuer_v2() {
+        let cert = certificate_with(&[
+            (ISSUER, b"https://deprecated.example.com".to_vec()),
+            (ISSUER_V2, der("https://gitlab.com")),
+        ]);
+
+        let extensions = FulcioCertificateExtensions::from_certificate(&cert).unwrap();
+        assert_eq!(extensions.issuer.as_deref(), Some("https://gitlab.com"));
+    }
+
+    #[test]
+    fn from_certificate_rejects_malformed_extensions() {
+        // V2 extensions must be DER-encoded.
+        let cert = certificate_with(&[(BUILD_TRIGGER, b"push".to_vec())]);
+        assert!(matches!(
+            FulcioCertificateExtensions::from_certificate(&cert),
+            Err(SigstoreError::X509Error(_))
+        ));
+
+        let cert = certificate_with(&[(BUILD_TRIGGER, der("push")), (BUILD_TRIGGER, der("tag"))]);
+        assert!(matches!(
+            FulcioCertificateExtensions::from_certificate(&cert),
+            Err(SigstoreError::X509Error(_))
+        ));
+    }
+
+    #[test]
+    fn from_certificate_lossy_skips_malformed_extensions() {
+        let cert = certificate_with(&[
+            (BUILD_TRIGGER, der("push")),
+            (BUILD_TRIGGER, der("tag")),
+            (BUILD_TRIGGER, der("schedule")),
+            (SOURCE_REPOSITORY_REF, b"refs/heads/main".to_vec()),
+            (ISSUER_V2, der("https://gitlab.com")),
+        ]);
+
+        assert_eq!(
+            FulcioCertificateExtensions::from_certificate_lossy(&cert),
+            FulcioCertificateExtensions {
+                issuer: Some("https://gitlab.com".into()),
+                ..Default::default()
+            }
+        );
+    }
+}
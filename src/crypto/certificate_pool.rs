@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use const_oid::db::rfc5280::ID_KP_CODE_SIGNING;
+use const_oid::{db::rfc5280::ID_KP_CODE_SIGNING, ObjectIdentifier};
 use webpki::{
     types::{CertificateDer, TrustAnchor, UnixTime},
     EndEntityCert, KeyUsage, VerifiedPath,
@@ -103,18 +103,31 @@ impl CertificatePool {
         cert: &'cert EndEntityCert<'cert>,
         verification_time: UnixTime,
     ) -> Result<VerifiedPath<'cert>, webpki::Error>
+    where
+        'a: 'cert,
+    {
+        self.verify_cert_with_time_for_usage(cert, verification_time, &ID_KP_CODE_SIGNING)
+    }
+
+    /// Like [`CertificatePool::verify_cert_with_time`], but requires the given extended key
+    /// usage instead of code signing.
+    pub(crate) fn verify_cert_with_time_for_usage<'a, 'cert>(
+        &'a self,
+        cert: &'cert EndEntityCert<'cert>,
+        verification_time: UnixTime,
+        extended_key_usage: &'static ObjectIdentifier,
+    ) -> Result<VerifiedPath<'cert>, webpki::Error>
     where
         'a: 'cert,
     {
         let signing_algs = webpki::ALL_VERIFICATION_ALGS;
-        let eku_code_signing = ID_KP_CODE_SIGNING.as_bytes();
 
         cert.verify_for_usage(
             signing_algs,
             &self.trusted_roots,
             self.intermediates.as_slice(),
             verification_time,
-            KeyUsage::required(eku_code_signing),
+            KeyUsage::required(extended_key_usage.as_bytes()),
             None,
             None,
         )
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 
+use chrono::{DateTime, Utc};
 use const_oid::db::rfc5912::{ID_EC_PUBLIC_KEY, RSA_ENCRYPTION, SECP_256_R_1};
 use digest::Digest;
 use ring::{signature as ring_signature, signature::UnparsedPublicKey};
@@ -24,6 +25,8 @@ use x509_cert::{
     spki::SubjectPublicKeyInfoOwned,
 };
 
+use crate::trust::{LogKey, ValidityPeriod};
+
 #[derive(Error, Debug)]
 pub enum KeyringError {
     #[error("malformed key")]
@@ -33,6 +36,8 @@ pub enum KeyringError {
 
     #[error("requested key not in keyring")]
     KeyNotFound,
+    #[error("requested key was not valid at the time of signing")]
+    KeyOutsideValidityPeriod,
     #[error("verification failed")]
     VerificationFailed,
 }
@@ -44,11 +49,13 @@ struct Key {
     /// The key's RFC 6962-style "key ID".
     /// <https://datatracker.ietf.org/doc/html/rfc6962#section-3.2>
     fingerprint: [u8; 32],
+    /// The periods during which the key may have signed.
+    valid_for: Vec<ValidityPeriod>,
 }
 
 impl Key {
     /// Creates a `Key` from a DER blob containing a SubjectPublicKeyInfo object.
-    pub fn new(spki_bytes: &[u8]) -> Result<Self> {
+    pub fn new(spki_bytes: &[u8], valid_for: ValidityPeriod) -> Result<Self> {
         let spki = SubjectPublicKeyInfoOwned::from_der(spki_bytes)?;
         let (algo, params) = if let Some(params) = &spki.algorithm.parameters {
             // Special-case RSA keys, which don't have SPKI parameters.
@@ -74,6 +81,7 @@ impl Key {
                     spki.encode(&mut hasher).expect("failed to hash key!");
                     hasher.finalize().into()
                 },
+                valid_for: vec![valid_for],
             }),
             _ => Err(KeyringError::AlgoUnsupported),
         }
@@ -85,19 +93,43 @@ impl Key {
 pub struct Keyring(HashMap<[u8; 32], Key>);
 
 impl Keyring {
-    /// Creates a `Keyring` from DER encoded SPKI-format public keys.
+    /// Creates a `Keyring` from DER encoded SPKI-format public keys, valid at all times.
     pub fn new<'a>(keys: impl IntoIterator<Item = &'a [u8]>) -> Result<Self> {
-        Ok(Self(
-            keys.into_iter()
-                .flat_map(Key::new)
-                .map(|k| Ok((k.fingerprint, k)))
-                .collect::<Result<_>>()?,
-        ))
+        Self::from_log_keys(keys.into_iter().map(|key| LogKey {
+            key,
+            valid_for: ValidityPeriod::default(),
+        }))
+    }
+
+    /// Creates a `Keyring` from log keys, each restricted to its validity period. A key listed
+    /// more than once is valid during any of its periods.
+    pub fn from_log_keys<'a>(keys: impl IntoIterator<Item = LogKey<'a>>) -> Result<Self> {
+        let mut keyring: HashMap<[u8; 32], Key> = HashMap::new();
+        for key in keys.into_iter().flat_map(|k| Key::new(k.key, k.valid_for)) {
+            match keyring.entry(key.fingerprint) {
+                Entry::Occupied(mut existing) => existing.get_mut().valid_for.extend(key.valid_for),
+                Entry::Vacant(slot) => {
+                    slot.insert(key);
+                }
+            }
+        }
+
+        Ok(Self(keyring))
     }
 
-    /// Verifies `data` against a `signature` with a public key identified by `key_id`.
-    pub fn verify(&self, key_id: &[u8; 32], signature: &[u8], data: &[u8]) -> Result<()> {
+    /// Verifies `data` against a `signature` made at `time` with a public key identified by
+    /// `key_id`. The key must have been valid at `time`.
+    pub fn verify(
+        &self,
+        key_id: &[u8; 32],
+        signature: &[u8],
+        data: &[u8],
+        time: DateTime<Utc>,
+    ) -> Result<()> {
         let key = self.0.get(key_id).ok_or(KeyringError::KeyNotFound)?;
+        if !key.valid_for.iter().any(|period| period.contains(time)) {
+            return Err(KeyringError::KeyOutsideValidityPeriod);
+        }
 
         key.inner
             .verify(data, signature)
@@ -109,8 +141,10 @@ impl Keyring {
 
 #[cfg(test)]
 mod tests {
-    use super::Keyring;
+    use super::{Keyring, KeyringError};
     use crate::crypto::signing_key::ecdsa::{ECDSAKeys, EllipticCurve};
+    use crate::trust::{LogKey, ValidityPeriod};
+    use chrono::{TimeDelta, Utc};
     use digest::Digest;
     use std::io::Write;
 
@@ -134,19 +168,19 @@ mod tests {
 
         // Check for success.
         assert!(keyring
-            .verify(&key_id, signature.as_slice(), message)
+            .verify(&key_id, signature.as_slice(), message, Utc::now())
             .is_ok());
 
         // Check for failure with incorrect key id.
         assert!(keyring
-            .verify(&[0; 32], signature.as_slice(), message)
+            .verify(&[0; 32], signature.as_slice(), message, Utc::now())
             .is_err());
 
         // Check for failure with incorrect payload.
         let incorrect_message = b"another message";
 
         assert!(keyring
-            .verify(&key_id, signature.as_slice(), incorrect_message)
+            .verify(&key_id, signature.as_slice(), incorrect_message, Utc::now())
             .is_err());
 
         // Check for failure with incorrect keyring.
@@ -159,7 +193,86 @@ mod tests {
         .unwrap();
 
         assert!(incorrect_keyring
-            .verify(&key_id, signature.as_slice(), message)
+            .verify(&key_id, signature.as_slice(), message, Utc::now())
             .is_err());
     }
+
+    #[test]
+    fn verify_keyring_validity_period() {
+        let message = b"some message";
+
+        let key_pair = ECDSAKeys::new(EllipticCurve::P256).unwrap();
+        let signer = key_pair.to_sigstore_signer().unwrap();
+        let pub_key = key_pair.as_inner().public_key_to_der().unwrap();
+        let key_id: [u8; 32] = sha2::Sha256::digest(&pub_key).into();
+        let signature = signer.sign(message).unwrap();
+
+        // A key rotated out a day ago.
+        let now = Utc::now();
+        let keyring = Keyring::from_log_keys([LogKey {
+            key: pub_key.as_slice(),
+            valid_for: ValidityPeriod {
+                start: Some(now - TimeDelta::days(2)),
+                end: Some(now - TimeDelta::days(1)),
+            },
+        }])
+        .unwrap();
+
+        // Signatures made while the key was valid still verify.
+        assert!(keyring
+            .verify(&key_id, &signature, message, now - TimeDelta::hours(36))
+            .is_ok());
+
+        // Signatures made before or after the key's validity period are rejected.
+        for time in [now - TimeDelta::days(3), now] {
+            assert!(matches!(
+                keyring.verify(&key_id, &signature, message, time),
+                Err(KeyringError::KeyOutsideValidityPeriod)
+            ));
+        }
+    }
+
+    #[test]
+    fn verify_keyring_multiple_validity_periods() {
+        let message = b"some message";
+
+        let key_pair = ECDSAKeys::new(EllipticCurve::P256).unwrap();
+        let signer = key_pair.to_sigstore_signer().unwrap();
+        let pub_key = key_pair.as_inner().public_key_to_der().unwrap();
+        let key_id: [u8; 32] = sha2::Sha256::digest(&pub_key).into();
+        let signature = signer.sign(message).unwrap();
+
+        // The same key, listed twice with disjoint validity periods.
+        let now = Utc::now();
+        let keyring = Keyring::from_log_keys([
+            LogKey {
+                key: pub_key.as_slice(),
+                valid_for: ValidityPeriod {
+                    start: Some(now - TimeDelta::days(4)),
+                    end: Some(now - TimeDelta::days(3)),
+                },
+            },
+            LogKey {
+                key: pub_key.as_slice(),
+                valid_for: ValidityPeriod {
+                    start: Some(now - TimeDelta::days(2)),
+                    end: Some(now - TimeDelta::days(1)),
+                },
+            },
+        ])
+        .unwrap();
+
+        // Signatures made during either period verify.
+        for time in [now - TimeDelta::hours(84), now - TimeDelta::hours(36)] {
+            assert!(keyring.verify(&key_id, &signature, message, time).is_ok());
+        }
+
+        // Signatures made between or after the periods are rejected.
+        for time in [now - TimeDelta::hours(60), now] {
+            assert!(matches!(
+                keyring.verify(&key_id, &signature, message, time),
+                Err(KeyringError::KeyOutsideValidityPeriod)
+            ));
+        }
+    }
 }
@@ -0,0 +1,456 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for verifying Merkle tree inclusion and consistency proofs.
+//!
+//! The hashing scheme and the verification algorithm follow [RFC 6962] and its successor,
+//! [RFC 9162]. Rekor uses the same construction for its transparency log.
+//!
+//! [RFC 6962]: https://datatracker.ietf.org/doc/html/rfc6962#section-2.1
+//! [RFC 9162]: https://datatracker.ietf.org/doc/html/rfc9162#section-2.1.3.2
+
+use digest::Digest;
+use sha2::Sha256;
+use thiserror::Error;
+
+/// Domain separation prefix for leaf hashes.
+const LEAF_HASH_PREFIX: u8 = 0;
+
+/// Domain separation prefix for interior node hashes.
+const NODE_HASH_PREFIX: u8 = 1;
+
+/// The output of the tree's hash function.
+pub type MerkleHash = [u8; 32];
+
+#[derive(Error, Debug)]
+pub enum MerkleProofError {
+    #[error("leaf index {index} is out of range for tree size {tree_size}")]
+    IndexOutOfRange { index: u64, tree_size: u64 },
+
+    #[error("inclusion proof has wrong size (got {actual} hashes, expected {expected})")]
+    WrongProofSize { actual: usize, expected: usize },
+
+    #[error("inclusion proof contains a malformed hash")]
+    MalformedHash,
+
+    #[error("calculated root hash {computed} does not match expected root hash {expected}")]
+    RootMismatch { computed: String, expected: String },
+
+    #[error("tree of size {old_size} cannot be a prefix of tree of size {new_size}")]
+    TreeSizeDecreased { old_size: u64, new_size: u64 },
+
+    #[error("consistency proof is malformed")]
+    ConsistencyProofMalformed,
+}
+
+/// Computes the hash of a leaf: `SHA-256(0x00 || leaf)`.
+pub fn hash_leaf(leaf: &[u8]) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_HASH_PREFIX]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+/// Computes the hash of an interior node: `SHA-256(0x01 || left || right)`.
+pub fn hash_children(left: &[u8], right: &[u8]) -> MerkleHash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_HASH_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Verifies that the leaf at `index` with hash `leaf_hash` is included in a tree of `tree_size`
+/// leaves with root hash `root_hash`, given the audit path `proof` (ordered from leaf to root).
+pub fn verify_inclusion<H>(
+    index: u64,
+    tree_size: u64,
+    leaf_hash: &MerkleHash,
+    proof: &[H],
+    root_hash: &[u8],
+) -> Result<(), MerkleProofError>
+where
+    H: AsRef<[u8]>,
+{
+    let computed = root_from_inclusion_proof(index, tree_size, leaf_hash, proof)?;
+
+    if computed[..] != *root_hash {
+        return Err(MerkleProofError::RootMismatch {
+            computed: hex::encode(computed),
+            expected: hex::encode(root_hash),
+        });
+    }
+
+    Ok(())
+}
+
+/// Recomputes the root hash of a tree of `tree_size` leaves from the leaf at `index` and its
+/// audit path.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc9162#section-2.1.3.2>
+pub fn root_from_inclusion_proof<H>(
+    index: u64,
+    tree_size: u64,
+    leaf_hash: &MerkleHash,
+    proof: &[H],
+) -> Result<MerkleHash, MerkleProofError>
+where
+    H: AsRef<[u8]>,
+{
+    if index >= tree_size {
+        return Err(MerkleProofError::IndexOutOfRange { index, tree_size });
+    }
+
+    let expected = expected_proof_size(index, tree_size);
+    if proof.len() != expected {
+        return Err(MerkleProofError::WrongProofSize {
+            actual: proof.len(),
+            expected,
+        });
+    }
+
+    let (mut fn_, mut sn) = (index, tree_size - 1);
+    let mut r = *leaf_hash;
+
+    for p in proof {
+        let p = p.as_ref();
+        if p.len() != r.len() {
+            return Err(MerkleProofError::MalformedHash);
+        }
+
+        if fn_ & 1 == 1 || fn_ == sn {
+            r = hash_children(p, &r);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            r = hash_children(&r, p);
+        }
+
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    // This is guaranteed by the proof size check above.
+    debug_assert_eq!(sn, 0);
+
+    Ok(r)
+}
+
+/// Verifies that a tree of `old_size` leaves with root hash `old_root` is a prefix of a tree of
+/// `new_size` leaves with root hash `new_root`, given the consistency proof `proof`.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc9162#section-2.1.4.2>
+pub fn verify_consistency<H>(
+    old_size: u64,
+    new_size: u64,
+    proof: &[H],
+    old_root: &[u8],
+    new_root: &[u8],
+) -> Result<(), MerkleProofError>
+where
+    H: AsRef<[u8]>,
+{
+    if old_size > new_size {
+        return Err(MerkleProofError::TreeSizeDecreased { old_size, new_size });
+    }
+
+    // Identical trees and the empty tree need no proof.
+    if old_size == new_size || old_size == 0 {
+        if !proof.is_empty() {
+            return Err(MerkleProofError::ConsistencyProofMalformed);
+        }
+        return match old_size {
+            0 => Ok(()),
+            _ => check_root(old_root, new_root),
+        };
+    }
+
+    // When the old tree is complete, its root is the first node of the proof.
+    let mut path: Vec<&[u8]> = Vec::with_capacity(proof.len() + 1);
+    if old_size.is_power_of_two() {
+        path.push(old_root);
+    }
+    path.extend(proof.iter().map(AsRef::as_ref));
+
+    let [first, rest @ ..] = &path[..] else {
+        return Err(MerkleProofError::ConsistencyProofMalformed);
+    };
+    let first: MerkleHash = (*first)
+        .try_into()
+        .or(Err(MerkleProofError::MalformedHash))?;
+
+    let (mut fn_, mut sn) = (old_size - 1, new_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    let (mut fr, mut sr) = (first, first);
+    for c in rest {
+        if c.len() != fr.len() {
+            return Err(MerkleProofError::MalformedHash);
+        }
+        if sn == 0 {
+            return Err(MerkleProofError::ConsistencyProofMalformed);
+        }
+
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = hash_children(c, &fr);
+            sr = hash_children(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = hash_children(&sr, c);
+        }
+
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+
+    if sn != 0 {
+        return Err(MerkleProofError::ConsistencyProofMalformed);
+    }
+
+    check_root(&fr, old_root)?;
+    check_root(&sr, new_root)
+}
+
+fn check_root(computed: &[u8], expected: &[u8]) -> Result<(), MerkleProofError> {
+    if computed != expected {
+        return Err(MerkleProofError::RootMismatch {
+            computed: hex::encode(computed),
+            expected: hex::encode(expected),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns the number of hashes in the audit path for the leaf at `index` in a tree of
+/// `tree_size` leaves.
+fn expected_proof_size(index: u64, tree_size: u64) -> usize {
+    // The audit path consists of one hash per level below the point where the paths to `index`
+    // and to the last leaf diverge ("inner" proof), plus one hash per set bit above it ("border"
+    // proof).
+    let inner = u64::BITS - (index ^ (tree_size - 1)).leading_zeros();
+    let border = (index >> inner).count_ones();
+
+    (inner + border) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Computes MTH(D[n]) from RFC 6962 section 2.1.
+    fn tree_hash(leaves: &[MerkleHash]) -> MerkleHash {
+        match leaves {
+            [leaf] => *leaf,
+            _ => {
+                let k = split_point(leaves.len());
+                hash_children(&tree_hash(&leaves[..k]), &tree_hash(&leaves[k..]))
+            }
+        }
+    }
+
+    /// Computes PATH(m, D[n]) from RFC 6962 section 2.1.1.
+    fn audit_path(m: usize, leaves: &[MerkleHash]) -> Vec<MerkleHash> {
+        if leaves.len() <= 1 {
+            return vec![];
+        }
+
+        let k = split_point(leaves.len());
+        if m < k {
+            let mut path = audit_path(m, &leaves[..k]);
+            path.push(tree_hash(&leaves[k..]));
+            path
+        } else {
+            let mut path = audit_path(m - k, &leaves[k..]);
+            path.push(tree_hash(&leaves[..k]));
+            path
+        }
+    }
+
+    /// Computes PROOF(m, D[n]) from RFC 6962 section 2.1.2.
+    fn consistency_proof(m: usize, leaves: &[MerkleHash]) -> Vec<MerkleHash> {
+        fn subproof(m: usize, leaves: &[MerkleHash], complete: bool) -> Vec<MerkleHash> {
+            let n = leaves.len();
+            if m == n {
+                return if complete {
+                    vec![]
+                } else {
+                    vec![tree_hash(leaves)]
+                };
+            }
+
+            let k = split_point(n);
+            if m <= k {
+                let mut proof = subproof(m, &leaves[..k], complete);
+                proof.push(tree_hash(&leaves[k..]));
+                proof
+            } else {
+                let mut proof = subproof(m - k, &leaves[k..], false);
+                proof.push(tree_hash(&leaves[..k]));
+                proof
+            }
+        }
+
+        subproof(m, leaves, true)
+    }
+
+    /// The largest power of two smaller than `n`.
+    fn split_point(n: usize) -> usize {
+        let mut k = 1;
+        while k << 1 < n {
+            k <<= 1;
+        }
+        k
+    }
+
+    fn leaves(n: usize) -> Vec<MerkleHash> {
+        (0..n)
+            .map(|i| hash_leaf(format!("leaf {i}").as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn verify_inclusion_all_sizes() {
+        for tree_size in 1..=33 {
+            let leaves = leaves(tree_size);
+            let root = tree_hash(&leaves);
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = audit_path(index, &leaves);
+                assert!(
+                    verify_inclusion(index as u64, tree_size as u64, leaf, &proof, &root).is_ok(),
+                    "proof for leaf {index} in tree of size {tree_size} failed"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verify_inclusion_wrong_root() {
+        let leaves = leaves(7);
+        let proof = audit_path(3, &leaves);
+
+        let root = hash_leaf(b"not the root");
+        assert!(matches!(
+            verify_inclusion(3, 7, &leaves[3], &proof, &root),
+            Err(MerkleProofError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_inclusion_wrong_leaf() {
+        let leaves = leaves(7);
+        let root = tree_hash(&leaves);
+        let proof = audit_path(3, &leaves);
+
+        assert!(matches!(
+            verify_inclusion(3, 7, &leaves[4], &proof, &root),
+            Err(MerkleProofError::RootMismatch { .. })
+        ));
+        assert!(matches!(
+            verify_inclusion(4, 7, &leaves[3], &proof, &root),
+            Err(MerkleProofError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn verify_inclusion_malformed_proof() {
+        let leaves = leaves(7);
+        let root = tree_hash(&leaves);
+        let mut proof = audit_path(3, &leaves);
+
+        assert!(matches!(
+            verify_inclusion(7, 7, &leaves[3], &proof, &root),
+            Err(MerkleProofError::IndexOutOfRange { .. })
+        ));
+        assert!(matches!(
+            verify_inclusion(3, 7, &leaves[3], &proof[1..], &root),
+            Err(MerkleProofError::WrongProofSize { .. })
+        ));
+
+        proof[0] = [0; 32];
+        let mut proof: Vec<Vec<u8>> = proof.iter().map(|h| h.to_vec()).collect();
+        proof[1].pop();
+        assert!(matches!(
+            verify_inclusion(3, 7, &leaves[3], &proof, &root),
+            Err(MerkleProofError::MalformedHash)
+        ));
+    }
+
+    #[test]
+    fn verify_consistency_all_sizes() {
+        let leaves = leaves(33);
+
+        for new_size in 1..=leaves.len() {
+            let new_root = tree_hash(&leaves[..new_size]);
+            for old_size in 1..=new_size {
+                let old_root = tree_hash(&leaves[..old_size]);
+                let proof = consistency_proof(old_size, &leaves[..new_size]);
+                assert!(
+                    verify_consistency(
+                        old_size as u64,
+                        new_size as u64,
+                        &proof,
+                        &old_root,
+                        &new_root
+                    )
+                    .is_ok(),
+                    "proof from size {old_size} to size {new_size} failed"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verify_consistency_failure() {
+        let leaves = leaves(7);
+        let old_root = tree_hash(&leaves[..3]);
+        let new_root = tree_hash(&leaves);
+        let proof = consistency_proof(3, &leaves);
+
+        // Roots that do not match the proof.
+        assert!(matches!(
+            verify_consistency(3, 7, &proof, &new_root, &new_root),
+            Err(MerkleProofError::RootMismatch { .. })
+        ));
+        assert!(matches!(
+            verify_consistency(3, 7, &proof, &old_root, &old_root),
+            Err(MerkleProofError::RootMismatch { .. })
+        ));
+
+        // Proofs of the wrong size.
+        assert!(matches!(
+            verify_consistency(3, 7, &proof[1..], &old_root, &new_root),
+            Err(MerkleProofError::ConsistencyProofMalformed)
+        ));
+        assert!(matches!(
+            verify_consistency(7, 7, &proof, &new_root, &new_root),
+            Err(MerkleProofError::ConsistencyProofMalformed)
+        ));
+
+        // A tree that shrunk.
+        assert!(matches!(
+            verify_consistency(7, 3, &proof, &new_root, &old_root),
+            Err(MerkleProofError::TreeSizeDecreased { .. })
+        ));
+    }
+}
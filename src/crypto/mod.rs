@@ -15,6 +15,10 @@
 
 //! Structures and constants required to perform cryptographic operations.
 
+use std::fmt::Display;
+use std::str::FromStr;
+
+use const_oid::ObjectIdentifier;
 use sha2::{Sha256, Sha384};
 
 use crate::errors::*;
@@ -163,6 +167,74 @@ impl Default for SigningScheme {
     }
 }
 
+/// Digest algorithms supported for signed artifacts and prehashed signatures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// Looks up the algorithm of a [`digest::Digest`] by its OID.
+    pub(crate) fn from_oid(oid: ObjectIdentifier) -> Option<Self> {
+        match oid {
+            const_oid::db::rfc5912::ID_SHA_256 => Some(Self::Sha256),
+            const_oid::db::rfc5912::ID_SHA_384 => Some(Self::Sha384),
+            const_oid::db::rfc5912::ID_SHA_512 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// The algorithm's name, as used in `hashedrekord` entries and in-toto digest sets.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// The size of the algorithm's digests, in bytes.
+    pub fn output_size(self) -> usize {
+        match self {
+            Self::Sha256 => 32,
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// Computes the digest of `msg`.
+    pub fn digest(self, msg: &[u8]) -> Vec<u8> {
+        use sha2::{Digest, Sha512};
+
+        match self {
+            Self::Sha256 => Sha256::digest(msg).to_vec(),
+            Self::Sha384 => Sha384::digest(msg).to_vec(),
+            Self::Sha512 => Sha512::digest(msg).to_vec(),
+        }
+    }
+}
+
+impl Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(DigestAlgorithm::Sha256),
+            "sha384" => Ok(DigestAlgorithm::Sha384),
+            "sha512" => Ok(DigestAlgorithm::Sha512),
+            _ => Err(()),
+        }
+    }
+}
+
 /// A signature produced by a private key
 pub enum Signature<'a> {
     /// Raw signature. There's no need to process the contents
@@ -173,6 +245,11 @@ pub enum Signature<'a> {
 
 #[cfg(feature = "cert")]
 pub(crate) mod certificate;
+#[cfg_attr(docsrs, doc(cfg(feature = "cert")))]
+#[cfg(feature = "cert")]
+pub mod certificate_extensions;
+#[cfg(feature = "cert")]
+pub use certificate_extensions::FulcioCertificateExtensions;
 #[cfg(feature = "cert")]
 pub(crate) mod certificate_pool;
 #[cfg(feature = "cert")]
@@ -185,11 +262,19 @@ pub mod verification_key;
 use self::signing_key::{
     ecdsa::ec::{EcdsaKeys, EcdsaSigner},
     ed25519::{Ed25519Keys, Ed25519Signer},
-    rsa::{keypair::RSAKeys, DigestAlgorithm, PaddingScheme, RSASigner, DEFAULT_KEY_SIZE},
+    rsa::{keypair::RSAKeys, PaddingScheme, RSASigner, DEFAULT_KEY_SIZE},
 };
 
 pub mod signing_key;
 
+pub mod signer;
+
+#[cfg(feature = "verify")]
+pub(crate) mod merkle;
+
+#[cfg(feature = "verify")]
+pub(crate) mod timestamp;
+
 #[cfg(any(feature = "sign", feature = "verify"))]
 pub(crate) mod transparency;
 
@@ -0,0 +1,331 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signing keys that may be held outside of the process.
+//!
+//! [`AsyncSigner`] abstracts over where a private key lives: in memory, in an HSM, on a PKCS#11
+//! token or in a cloud KMS. Signing sessions, Fulcio certificate requests and cosign's
+//! `PrivateKeySigner` can all sign through it. [`SoftwareSigner`] holds its key in memory.
+
+use async_trait::async_trait;
+use pkcs8::{DecodePrivateKey, EncodePublicKey};
+use rsa::{pkcs1v15, RsaPrivateKey};
+use sha2::{Sha256, Sha384, Sha512};
+use signature::{hazmat::PrehashSigner, SignatureEncoding, Signer};
+
+use super::{signing_key::SigStoreSigner, DigestAlgorithm, SigningScheme};
+use crate::errors::{Result, SigstoreError};
+
+/// A private key producing signatures asynchronously.
+///
+/// ECDSA signatures must be DER-encoded, and RSA signatures must use PKCS#1 v1.5 padding, as
+/// expected by Fulcio and Rekor.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait AsyncSigner: Send + Sync {
+    /// Returns the key's public key, as a DER-encoded `SubjectPublicKeyInfo`.
+    async fn public_key(&self) -> Result<Vec<u8>>;
+
+    /// The digest algorithm used to sign whole messages with [`AsyncSigner::sign`].
+    fn digest_algorithm(&self) -> DigestAlgorithm;
+
+    /// Signs `digest`, computed with `digest_algorithm`.
+    async fn sign_digest(
+        &self,
+        digest_algorithm: DigestAlgorithm,
+        digest: &[u8],
+    ) -> Result<Vec<u8>>;
+
+    /// Signs `msg`. By default, its digest is computed with [`AsyncSigner::digest_algorithm`]
+    /// and signed with [`AsyncSigner::sign_digest`].
+    ///
+    /// Keys that sign whole messages rather than digests, such as Ed25519 keys, must override
+    /// this method.
+    async fn sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        let digest_algorithm = self.digest_algorithm();
+        self.sign_digest(digest_algorithm, &digest_algorithm.digest(msg))
+            .await
+    }
+}
+
+/// An [`AsyncSigner`] holding its private key in memory.
+///
+/// RSA keys sign with PKCS#1 v1.5 padding: RSA-PSS signing schemes are not supported. Ed25519
+/// keys can only sign whole messages, not digests.
+pub struct SoftwareSigner(SoftwareKey);
+
+enum SoftwareKey {
+    P256(p256::ecdsa::SigningKey),
+    P384(p384::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+    /// An RSA key, and the digest algorithm used to sign whole messages.
+    Rsa(RsaPrivateKey, DigestAlgorithm),
+}
+
+impl SoftwareSigner {
+    /// Generates a new key for `signing_scheme`.
+    pub fn generate(signing_scheme: SigningScheme) -> Result<Self> {
+        let mut rng = rand::thread_rng();
+        let rsa = |bits, digest_algorithm| -> Result<SoftwareKey> {
+            Ok(SoftwareKey::Rsa(
+                RsaPrivateKey::new(&mut rand::thread_rng(), bits)?,
+                digest_algorithm,
+            ))
+        };
+
+        let key = match signing_scheme {
+            SigningScheme::ECDSA_P256_SHA256_ASN1 => {
+                SoftwareKey::P256(p256::SecretKey::random(&mut rng).into())
+            }
+            SigningScheme::ECDSA_P384_SHA384_ASN1 => {
+                SoftwareKey::P384(p384::SecretKey::random(&mut rng).into())
+            }
+            SigningScheme::ED25519 => {
+                SoftwareKey::Ed25519(ed25519_dalek::SigningKey::generate(&mut rng))
+            }
+            SigningScheme::RSA_PKCS1_SHA256(bits) => rsa(bits, DigestAlgorithm::Sha256)?,
+            SigningScheme::RSA_PKCS1_SHA384(bits) => rsa(bits, DigestAlgorithm::Sha384)?,
+            SigningScheme::RSA_PKCS1_SHA512(bits) => rsa(bits, DigestAlgorithm::Sha512)?,
+            SigningScheme::RSA_PSS_SHA256(_)
+            | SigningScheme::RSA_PSS_SHA384(_)
+            | SigningScheme::RSA_PSS_SHA512(_) => {
+                return Err(SigstoreError::SigningSchemeUnsupportedError(
+                    signing_scheme.to_string(),
+                ))
+            }
+        };
+
+        Ok(Self(key))
+    }
+
+    /// Returns the key's public key, as a DER-encoded `SubjectPublicKeyInfo`.
+    pub(crate) fn public_key_der(&self) -> Result<Vec<u8>> {
+        let der = match &self.0 {
+            SoftwareKey::P256(key) => key.verifying_key().to_public_key_der(),
+            SoftwareKey::P384(key) => key.verifying_key().to_public_key_der(),
+            SoftwareKey::Ed25519(key) => key.verifying_key().to_public_key_der(),
+            SoftwareKey::Rsa(key, _) => key.to_public_key().to_public_key_der(),
+        }
+        .map_err(|err| SigstoreError::PKCS8SpkiError(err.to_string()))?;
+
+        Ok(der.into_vec())
+    }
+}
+
+impl TryFrom<&SigStoreSigner> for SoftwareSigner {
+    type Error = SigstoreError;
+
+    /// Copies the key of `signer`, which must not use an RSA-PSS signing scheme.
+    fn try_from(signer: &SigStoreSigner) -> Result<Self> {
+        let der = signer.to_sigstore_keypair()?.private_key_to_der()?;
+        let pkcs8_error = |err: pkcs8::Error| SigstoreError::PKCS8Error(err.to_string());
+        let rsa = |digest_algorithm| -> Result<SoftwareKey> {
+            Ok(SoftwareKey::Rsa(
+                RsaPrivateKey::from_pkcs8_der(&der).map_err(pkcs8_error)?,
+                digest_algorithm,
+            ))
+        };
+
+        let key = match signer {
+            SigStoreSigner::ECDSA_P256_SHA256_ASN1(_) => SoftwareKey::P256(
+                p256::ecdsa::SigningKey::from_pkcs8_der(&der).map_err(pkcs8_error)?,
+            ),
+            SigStoreSigner::ECDSA_P384_SHA384_ASN1(_) => SoftwareKey::P384(
+                p384::ecdsa::SigningKey::from_pkcs8_der(&der).map_err(pkcs8_error)?,
+            ),
+            SigStoreSigner::ED25519(_) => SoftwareKey::Ed25519(
+                ed25519_dalek::SigningKey::from_pkcs8_der(&der).map_err(pkcs8_error)?,
+            ),
+            SigStoreSigner::RSA_PKCS1_SHA256(_) => rsa(DigestAlgorithm::Sha256)?,
+            SigStoreSigner::RSA_PKCS1_SHA384(_) => rsa(DigestAlgorithm::Sha384)?,
+            SigStoreSigner::RSA_PKCS1_SHA512(_) => rsa(DigestAlgorithm::Sha512)?,
+            SigStoreSigner::RSA_PSS_SHA256(_)
+            | SigStoreSigner::RSA_PSS_SHA384(_)
+            | SigStoreSigner::RSA_PSS_SHA512(_) => {
+                return Err(SigstoreError::SigningSchemeUnsupportedError(
+                    "RSA_PSS".into(),
+                ))
+            }
+        };
+
+        Ok(Self(key))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl AsyncSigner for SoftwareSigner {
+    async fn public_key(&self) -> Result<Vec<u8>> {
+        self.public_key_der()
+    }
+
+    fn digest_algorithm(&self) -> DigestAlgorithm {
+        match &self.0 {
+            SoftwareKey::P256(_) => DigestAlgorithm::Sha256,
+            SoftwareKey::P384(_) => DigestAlgorithm::Sha384,
+            SoftwareKey::Ed25519(_) => DigestAlgorithm::Sha512,
+            SoftwareKey::Rsa(_, digest_algorithm) => *digest_algorithm,
+        }
+    }
+
+    async fn sign_digest(
+        &self,
+        digest_algorithm: DigestAlgorithm,
+        digest: &[u8],
+    ) -> Result<Vec<u8>> {
+        Ok(match &self.0 {
+            SoftwareKey::P256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign_prehash(digest)?;
+                signature.to_der().to_vec()
+            }
+            SoftwareKey::P384(key) => {
+                let signature: p384::ecdsa::Signature = key.sign_prehash(digest)?;
+                signature.to_der().to_vec()
+            }
+            SoftwareKey::Ed25519(_) => {
+                return Err(SigstoreError::SigningSchemeUnsupportedError(
+                    "ED25519 signatures over digests".into(),
+                ))
+            }
+            SoftwareKey::Rsa(key, _) => match digest_algorithm {
+                DigestAlgorithm::Sha256 => pkcs1v15::SigningKey::<Sha256>::new(key.clone())
+                    .sign_prehash(digest)?
+                    .to_vec(),
+                DigestAlgorithm::Sha384 => pkcs1v15::SigningKey::<Sha384>::new(key.clone())
+                    .sign_prehash(digest)?
+                    .to_vec(),
+                DigestAlgorithm::Sha512 => pkcs1v15::SigningKey::<Sha512>::new(key.clone())
+                    .sign_prehash(digest)?
+                    .to_vec(),
+            },
+        })
+    }
+
+    async fn sign(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        match &self.0 {
+            SoftwareKey::Ed25519(key) => Ok(key.try_sign(msg)?.to_vec()),
+            _ => {
+                let digest_algorithm = self.digest_algorithm();
+                self.sign_digest(digest_algorithm, &digest_algorithm.digest(msg))
+                    .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{CosignVerificationKey, Signature};
+    use ed25519_dalek::pkcs8::DecodePublicKey;
+    use x509_cert::{der::Decode, spki::SubjectPublicKeyInfoOwned};
+
+    const MESSAGE: &[u8] = b"artifact";
+
+    #[tokio::test]
+    async fn software_signer_signs_digests_and_messages() {
+        for scheme in [
+            SigningScheme::ECDSA_P256_SHA256_ASN1,
+            SigningScheme::ECDSA_P384_SHA384_ASN1,
+            SigningScheme::RSA_PKCS1_SHA256(2048),
+        ] {
+            let signer = SoftwareSigner::generate(scheme).unwrap();
+            let public_key =
+                SubjectPublicKeyInfoOwned::from_der(&signer.public_key().await.unwrap()).unwrap();
+            let verification_key = CosignVerificationKey::try_from(&public_key).unwrap();
+
+            let digest = DigestAlgorithm::Sha256.digest(MESSAGE);
+            let signature = signer
+                .sign_digest(DigestAlgorithm::Sha256, &digest)
+                .await
+                .unwrap();
+            assert!(
+                verification_key
+                    .verify_prehash(Signature::Raw(&signature), &digest)
+                    .is_ok(),
+                "{scheme}: digest signature"
+            );
+
+            let signature = signer.sign(MESSAGE).await.unwrap();
+            assert!(
+                verification_key
+                    .verify_signature(Signature::Raw(&signature), MESSAGE)
+                    .is_ok(),
+                "{scheme}: message signature"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn software_signer_ed25519() {
+        let signer = SoftwareSigner::generate(SigningScheme::ED25519).unwrap();
+        let public_key =
+            ed25519_dalek::VerifyingKey::from_public_key_der(&signer.public_key().await.unwrap())
+                .unwrap();
+
+        let signature = signer.sign(MESSAGE).await.unwrap();
+        let signature = ed25519::Signature::from_slice(&signature).unwrap();
+        assert!(public_key.verify_strict(MESSAGE, &signature).is_ok());
+
+        // Ed25519 signs whole messages, not digests.
+        assert!(matches!(
+            signer
+                .sign_digest(
+                    DigestAlgorithm::Sha512,
+                    &DigestAlgorithm::Sha512.digest(MESSAGE)
+                )
+                .await,
+            Err(SigstoreError::SigningSchemeUnsupportedError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn software_signer_from_sigstore_signer() {
+        let sigstore_signer = SigningScheme::ECDSA_P384_SHA384_ASN1
+            .create_signer()
+            .unwrap();
+        let signer = SoftwareSigner::try_from(&sigstore_signer).unwrap();
+
+        assert_eq!(signer.digest_algorithm(), DigestAlgorithm::Sha384);
+        assert_eq!(
+            signer.public_key().await.unwrap(),
+            sigstore_signer
+                .to_sigstore_keypair()
+                .unwrap()
+                .public_key_to_der()
+                .unwrap()
+        );
+
+        let signature = signer.sign(MESSAGE).await.unwrap();
+        assert!(sigstore_signer
+            .to_verification_key()
+            .unwrap()
+            .verify_signature(Signature::Raw(&signature), MESSAGE)
+            .is_ok());
+    }
+
+    #[test]
+    fn software_signer_rejects_rsa_pss() {
+        assert!(matches!(
+            SoftwareSigner::generate(SigningScheme::RSA_PSS_SHA256(2048)),
+            Err(SigstoreError::SigningSchemeUnsupportedError(_))
+        ));
+
+        let sigstore_signer = SigningScheme::RSA_PSS_SHA256(2048).create_signer().unwrap();
+        assert!(matches!(
+            SoftwareSigner::try_from(&sigstore_signer),
+            Err(SigstoreError::SigningSchemeUnsupportedError(_))
+        ));
+    }
+}
@@ -68,11 +68,7 @@ pub const DEFAULT_KEY_SIZE: usize = 2048;
 
 /// Different digest algorithms used in
 /// RSA-based signing algorithm.
-pub enum DigestAlgorithm {
-    Sha256,
-    Sha384,
-    Sha512,
-}
+pub use crate::crypto::DigestAlgorithm;
 
 /// Different padding schemes used in
 /// RSA-based signing algorithm.
@@ -0,0 +1,489 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for verifying [RFC 3161] signed timestamps.
+//!
+//! A timestamp authority (TSA) countersigns a digest of a Sigstore signature along with the
+//! current time. The signed time can then stand in for the time of signing when checking the
+//! signing certificate's validity.
+//!
+//! [RFC 3161]: https://datatracker.ietf.org/doc/html/rfc3161
+
+use chrono::{DateTime, Utc};
+use cmpv2::status::PkiStatus;
+use cms::{
+    cert::{CertificateChoices, IssuerAndSerialNumber},
+    content_info::ContentInfo,
+    signed_data::{SignedData, SignerIdentifier, SignerInfo},
+};
+use const_oid::{
+    db::{
+        rfc5280::ID_KP_TIME_STAMPING,
+        rfc5911::{ID_CONTENT_TYPE, ID_MESSAGE_DIGEST, ID_SIGNED_DATA},
+        rfc5912::{ID_SHA_256, ID_SHA_384, ID_SHA_512},
+    },
+    ObjectIdentifier,
+};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use thiserror::Error;
+use webpki::{
+    types::{CertificateDer, UnixTime},
+    EndEntityCert,
+};
+use x509_cert::{
+    der::{
+        asn1::{ObjectIdentifier as Oid, OctetString},
+        Decode, Encode,
+    },
+    ext::pkix::SubjectKeyIdentifier,
+    Certificate,
+};
+use x509_tsp::{TimeStampResp, TstInfo};
+
+use crate::errors::Result as SigstoreResult;
+
+use super::{CertificatePool, CosignVerificationKey, Signature};
+
+/// The content type of an RFC 3161 `TSTInfo`.
+const ID_CT_TST_INFO: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.16.1.4");
+
+#[derive(Error, Debug)]
+pub enum TimestampError {
+    #[error("timestamp is malformed")]
+    Malformed(#[source] x509_cert::der::Error),
+
+    #[error("timestamp request was not granted")]
+    NotGranted,
+
+    #[error("timestamp has unexpected content type {0}")]
+    ContentType(ObjectIdentifier),
+
+    #[error("unsupported digest algorithm {0}")]
+    DigestAlgorithmUnsupported(ObjectIdentifier),
+
+    #[error("timestamp's message imprint does not match the signature")]
+    MessageImprintMismatch,
+
+    #[error("timestamp needs 1 signer, got {0}")]
+    SignerCount(usize),
+
+    #[error("timestamp's signed attributes are invalid: {0}")]
+    SignedAttributes(&'static str),
+
+    #[error("timestamp's signing certificate was not found")]
+    CertificateNotFound,
+
+    #[error("timestamp's signing certificate verification failed")]
+    CertificateVerificationFailed(#[source] webpki::Error),
+
+    #[error("timestamp signature verification failed")]
+    SignatureVerificationFailed(#[source] crate::errors::SigstoreError),
+}
+
+impl From<x509_cert::der::Error> for TimestampError {
+    fn from(value: x509_cert::der::Error) -> Self {
+        Self::Malformed(value)
+    }
+}
+
+/// Verifies RFC 3161 timestamps against a set of trusted timestamp authority certificates.
+pub(crate) struct TimestampVerifier {
+    cert_pool: CertificatePool,
+    certificates: Vec<Certificate>,
+}
+
+impl TimestampVerifier {
+    /// Creates a `TimestampVerifier` from the certificate chains of trusted timestamp
+    /// authorities.
+    pub(crate) fn new<'a>(
+        tsa_certs: impl IntoIterator<Item = CertificateDer<'a>>,
+    ) -> SigstoreResult<Self> {
+        let tsa_certs: Vec<_> = tsa_certs.into_iter().collect();
+        let certificates = tsa_certs
+            .iter()
+            .map(|cert| Certificate::from_der(cert))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cert_pool: CertificatePool::from_certificates(tsa_certs, [])?,
+            certificates,
+        })
+    }
+
+    /// Verifies a DER-encoded `TimeStampResp` or `TimeStampToken` over `signature`, and returns
+    /// the time it attests to.
+    pub(crate) fn verify(
+        &self,
+        timestamp: &[u8],
+        signature: &[u8],
+    ) -> Result<DateTime<Utc>, TimestampError> {
+        let token = parse_token(timestamp)?;
+        if token.content_type != ID_SIGNED_DATA {
+            return Err(TimestampError::ContentType(token.content_type));
+        }
+        let signed_data: SignedData = token.content.decode_as()?;
+
+        // Decode the TSTInfo and check that it covers the signature.
+        let content_info = &signed_data.encap_content_info;
+        if content_info.econtent_type != ID_CT_TST_INFO {
+            return Err(TimestampError::ContentType(content_info.econtent_type));
+        }
+        let tst_info_der = content_info
+            .econtent
+            .as_ref()
+            .ok_or(TimestampError::ContentType(content_info.econtent_type))?
+            .value();
+        let tst_info = TstInfo::from_der(tst_info_der)?;
+
+        let imprint = &tst_info.message_imprint;
+        let signature_digest = digest(imprint.hash_algorithm.oid, signature)?;
+        if signature_digest != imprint.hashed_message.as_bytes() {
+            return Err(TimestampError::MessageImprintMismatch);
+        }
+
+        // Check the CMS signature over the TSTInfo.
+        let [signer_info] = signed_data.signer_infos.0.as_slice() else {
+            return Err(TimestampError::SignerCount(
+                signed_data.signer_infos.0.len(),
+            ));
+        };
+        let signed_attrs = verify_signed_attributes(signer_info, tst_info_der)?;
+
+        let certificate = signed_data
+            .certificates
+            .iter()
+            .flat_map(|set| set.0.iter())
+            .filter_map(|choice| match choice {
+                CertificateChoices::Certificate(cert) => Some(cert),
+                _ => None,
+            })
+            .chain(self.certificates.iter())
+            .find(|cert| is_signer(cert, &signer_info.sid))
+            .ok_or(TimestampError::CertificateNotFound)?;
+
+        let gen_time = UnixTime::since_unix_epoch(tst_info.gen_time.to_unix_duration());
+        let cert_der = CertificateDer::from(certificate.to_der()?);
+        let ee_cert = EndEntityCert::try_from(&cert_der)
+            .map_err(TimestampError::CertificateVerificationFailed)?;
+        self.cert_pool
+            .verify_cert_with_time_for_usage(&ee_cert, gen_time, &ID_KP_TIME_STAMPING)
+            .map_err(TimestampError::CertificateVerificationFailed)?;
+
+        let key =
+            CosignVerificationKey::try_from(&certificate.tbs_certificate.subject_public_key_info)
+                .map_err(TimestampError::SignatureVerificationFailed)?;
+        key.verify_prehash(
+            Signature::Raw(signer_info.signature.as_bytes()),
+            &digest(signer_info.digest_alg.oid, &signed_attrs)?,
+        )
+        .map_err(TimestampError::SignatureVerificationFailed)?;
+
+        Ok(tst_info.gen_time.to_system_time().into())
+    }
+}
+
+/// Parses a `TimeStampResp`, falling back to a bare `TimeStampToken`.
+fn parse_token(timestamp: &[u8]) -> Result<ContentInfo, TimestampError> {
+    match TimeStampResp::from_der(timestamp) {
+        Ok(response) => match response.status.status {
+            PkiStatus::Accepted | PkiStatus::GrantedWithMods => {
+                response.time_stamp_token.ok_or(TimestampError::NotGranted)
+            }
+            _ => Err(TimestampError::NotGranted),
+        },
+        Err(_) => Ok(ContentInfo::from_der(timestamp)?),
+    }
+}
+
+/// Checks the signer's content type and message digest attributes, and returns the DER-encoded
+/// signed attributes: the message that is actually signed.
+fn verify_signed_attributes(
+    signer_info: &SignerInfo,
+    content: &[u8],
+) -> Result<Vec<u8>, TimestampError> {
+    let signed_attrs = signer_info
+        .signed_attrs
+        .as_ref()
+        .ok_or(TimestampError::SignedAttributes("missing"))?;
+
+    let attribute = |oid| {
+        let mut values = signed_attrs
+            .iter()
+            .filter(|attr| attr.oid == oid)
+            .flat_map(|attr| attr.values.iter());
+        match (values.next(), values.next()) {
+            (Some(value), None) => Ok(value),
+            _ => Err(TimestampError::SignedAttributes(
+                "content type and message digest must each have exactly one value",
+            )),
+        }
+    };
+
+    let content_type: Oid = attribute(ID_CONTENT_TYPE)?.decode_as()?;
+    if content_type != ID_CT_TST_INFO {
+        return Err(TimestampError::SignedAttributes("content type mismatch"));
+    }
+
+    let message_digest: OctetString = attribute(ID_MESSAGE_DIGEST)?.decode_as()?;
+    if message_digest.as_bytes() != digest(signer_info.digest_alg.oid, content)? {
+        return Err(TimestampError::SignedAttributes("message digest mismatch"));
+    }
+
+    Ok(signed_attrs.to_der()?)
+}
+
+/// Checks whether `cert` is the certificate identified by `sid`.
+fn is_signer(cert: &Certificate, sid: &SignerIdentifier) -> bool {
+    match sid {
+        SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+            issuer,
+            serial_number,
+        }) => {
+            cert.tbs_certificate.issuer == *issuer
+                && cert.tbs_certificate.serial_number == *serial_number
+        }
+        SignerIdentifier::SubjectKeyIdentifier(ski) => {
+            matches!(
+                cert.tbs_certificate.get::<SubjectKeyIdentifier>(),
+                Ok(Some((_, cert_ski))) if cert_ski == *ski
+            )
+        }
+    }
+}
+
+fn digest(algorithm: ObjectIdentifier, data: &[u8]) -> Result<Vec<u8>, TimestampError> {
+    Ok(match algorithm {
+        ID_SHA_256 => Sha256::digest(data).to_vec(),
+        ID_SHA_384 => Sha384::digest(data).to_vec(),
+        ID_SHA_512 => Sha512::digest(data).to_vec(),
+        _ => return Err(TimestampError::DigestAlgorithmUnsupported(algorithm)),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use cmpv2::status::PkiStatusInfo;
+    use cms::{
+        builder::{SignedDataBuilder, SignerInfoBuilder},
+        cert::x509::{
+            builder::{Builder, CertificateBuilder, Profile},
+            ext::pkix::ExtendedKeyUsage,
+            name::Name,
+            serial_number::SerialNumber,
+            spki::{AlgorithmIdentifierOwned, EncodePublicKey, SubjectPublicKeyInfoOwned},
+            time::Validity,
+        },
+        signed_data::EncapsulatedContentInfo,
+    };
+    use p384::ecdsa::{DerSignature, SigningKey};
+    use std::{str::FromStr, time::Duration};
+    use x509_cert::der::{
+        asn1::{GeneralizedTime, Int},
+        Any,
+    };
+    use x509_tsp::{MessageImprint, TspVersion};
+
+    /// A minimal timestamp authority, with a self-signed root and a leaf signing certificate.
+    pub(crate) struct TestTsa {
+        pub(crate) root: Certificate,
+        leaf: Certificate,
+        leaf_key: SigningKey,
+    }
+
+    impl TestTsa {
+        pub(crate) fn new() -> Self {
+            let root_key = SigningKey::random(&mut rand::rngs::OsRng);
+            let root_name = Name::from_str("CN=Test TSA Root").unwrap();
+            let validity = Validity::from_now(Duration::from_secs(24 * 60 * 60)).unwrap();
+            let root = CertificateBuilder::new(
+                Profile::Root,
+                SerialNumber::from(1u32),
+                validity,
+                root_name.clone(),
+                spki(&root_key),
+                &root_key,
+            )
+            .unwrap()
+            .build::<DerSignature>()
+            .unwrap();
+
+            let leaf_key = SigningKey::random(&mut rand::rngs::OsRng);
+            let mut builder = CertificateBuilder::new(
+                Profile::Leaf {
+                    issuer: root_name,
+                    enable_key_agreement: false,
+                    enable_key_encipherment: false,
+                },
+                SerialNumber::from(2u32),
+                validity,
+                Name::from_str("CN=Test TSA").unwrap(),
+                spki(&leaf_key),
+                &root_key,
+            )
+            .unwrap();
+            builder
+                .add_extension(&ExtendedKeyUsage(vec![ID_KP_TIME_STAMPING]))
+                .unwrap();
+            let leaf = builder.build::<DerSignature>().unwrap();
+
+            Self {
+                root,
+                leaf,
+                leaf_key,
+            }
+        }
+
+        /// The DER-encoded certificates to trust.
+        pub(crate) fn certificates(&self) -> Vec<CertificateDer<'static>> {
+            vec![CertificateDer::from(self.root.to_der().unwrap())]
+        }
+
+        /// Returns a DER-encoded `TimeStampToken` over `signature`.
+        pub(crate) fn timestamp(&self, signature: &[u8]) -> Vec<u8> {
+            let tst_info = TstInfo {
+                version: TspVersion::V1,
+                policy: ObjectIdentifier::new_unwrap("1.3.6.1.4.1.57264.2"),
+                message_imprint: MessageImprint {
+                    hash_algorithm: AlgorithmIdentifierOwned {
+                        oid: ID_SHA_256,
+                        parameters: None,
+                    },
+                    hashed_message: OctetString::new(Sha256::digest(signature).to_vec()).unwrap(),
+                },
+                serial_number: Int::new(&[1]).unwrap(),
+                gen_time: GeneralizedTime::from_unix_duration(Duration::from_secs(
+                    UnixTime::now().as_secs(),
+                ))
+                .unwrap(),
+                accuracy: None,
+                ordering: false,
+                nonce: None,
+                tsa: None,
+                extensions: None,
+            };
+            let content = EncapsulatedContentInfo {
+                econtent_type: ID_CT_TST_INFO,
+                econtent: Some(
+                    Any::new(x509_cert::der::Tag::OctetString, tst_info.to_der().unwrap()).unwrap(),
+                ),
+            };
+
+            let digest_algorithm = AlgorithmIdentifierOwned {
+                oid: ID_SHA_384,
+                parameters: None,
+            };
+            let sid = SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+                issuer: self.leaf.tbs_certificate.issuer.clone(),
+                serial_number: self.leaf.tbs_certificate.serial_number.clone(),
+            });
+            let signer_info = SignerInfoBuilder::new(
+                &self.leaf_key,
+                sid,
+                digest_algorithm.clone(),
+                &content,
+                None,
+            )
+            .unwrap();
+
+            SignedDataBuilder::new(&content)
+                .add_digest_algorithm(digest_algorithm)
+                .unwrap()
+                .add_certificate(CertificateChoices::Certificate(self.leaf.clone()))
+                .unwrap()
+                .add_signer_info::<_, DerSignature>(signer_info)
+                .unwrap()
+                .build()
+                .unwrap()
+                .to_der()
+                .unwrap()
+        }
+
+        /// Returns a DER-encoded `TimeStampResp` granting a timestamp over `signature`.
+        pub(crate) fn response(&self, signature: &[u8]) -> Vec<u8> {
+            TimeStampResp {
+                status: PkiStatusInfo {
+                    status: PkiStatus::Accepted,
+                    status_string: None,
+                    fail_info: None,
+                },
+                time_stamp_token: Some(ContentInfo::from_der(&self.timestamp(signature)).unwrap()),
+            }
+            .to_der()
+            .unwrap()
+        }
+    }
+
+    /// An in-process stand-in for a timestamp authority's HTTP endpoint.
+    #[cfg(feature = "sign")]
+    #[async_trait::async_trait]
+    impl crate::tsa::TimestampAuthority for TestTsa {
+        async fn timestamp(&self, signature: &[u8]) -> SigstoreResult<Vec<u8>> {
+            Ok(self.response(signature))
+        }
+    }
+
+    fn spki(key: &SigningKey) -> SubjectPublicKeyInfoOwned {
+        let der = key.verifying_key().to_public_key_der().unwrap();
+        SubjectPublicKeyInfoOwned::from_der(der.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn verify_timestamp_success() {
+        let tsa = TestTsa::new();
+        let verifier = TimestampVerifier::new(tsa.certificates()).unwrap();
+
+        let timestamp = tsa.timestamp(b"signature");
+        let time = verifier.verify(&timestamp, b"signature").unwrap();
+        assert!((Utc::now() - time).num_seconds().abs() < 60);
+
+        let response = tsa.response(b"signature");
+        assert!(verifier.verify(&response, b"signature").is_ok());
+    }
+
+    #[test]
+    fn verify_timestamp_wrong_signature() {
+        let tsa = TestTsa::new();
+        let verifier = TimestampVerifier::new(tsa.certificates()).unwrap();
+
+        let timestamp = tsa.timestamp(b"signature");
+        assert!(matches!(
+            verifier.verify(&timestamp, b"other signature"),
+            Err(TimestampError::MessageImprintMismatch)
+        ));
+    }
+
+    #[test]
+    fn verify_timestamp_untrusted_tsa() {
+        let tsa = TestTsa::new();
+        let verifier = TimestampVerifier::new(TestTsa::new().certificates()).unwrap();
+
+        let timestamp = tsa.timestamp(b"signature");
+        assert!(matches!(
+            verifier.verify(&timestamp, b"signature"),
+            Err(TimestampError::CertificateVerificationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn verify_timestamp_malformed() {
+        let tsa = TestTsa::new();
+        let verifier = TimestampVerifier::new(tsa.certificates()).unwrap();
+
+        assert!(matches!(
+            verifier.verify(b"not a timestamp", b"signature"),
+            Err(TimestampError::Malformed(_))
+        ));
+    }
+}
@@ -14,6 +14,7 @@
 
 //! Types for Certificate Transparency validation.
 
+use chrono::DateTime;
 use const_oid::db::rfc6962::{CT_PRECERT_SCTS, CT_PRECERT_SIGNING_CERT};
 use digest::Digest;
 use thiserror::Error;
@@ -55,6 +56,39 @@ fn find_issuer_cert(chain: &[Certificate]) -> Option<&Certificate> {
     Some(cert)
 }
 
+/// Returns the DER-encoded SPKI of the leaf's issuer in a verified certificate chain.
+fn verified_issuer_spki(chain: &webpki::VerifiedPath) -> Result<Vec<u8>, SCTError> {
+    if let Some(issuer) = chain.intermediate_certificates().next() {
+        debug!("intermediate is the leaf's issuer");
+
+        let issuer = Certificate::from_der(&issuer.der())
+            .map_err(CertificateErrorKind::from)?
+            .tbs_certificate;
+        Ok(issuer
+            .subject_public_key_info
+            .to_der()
+            .map_err(CertificateErrorKind::from)?)
+    } else {
+        debug!("anchor is the leaf's issuer");
+
+        // Prefix the SPKI with the DER SEQUENCE tag and a short definite-form length.
+        let body = &chain.anchor().subject_public_key_info[..];
+        let body_len = body
+            .len()
+            .try_into()
+            .or(Err(CertificateErrorKind::IssuerMalformed))?;
+        let prefix = &[0x30u8, body_len];
+
+        Ok([prefix, body].concat())
+    }
+}
+
+/// The SHA-256 digest of the public key of the leaf's issuer in a verified certificate chain.
+#[cfg(feature = "verify")]
+pub fn verified_issuer_id(chain: &webpki::VerifiedPath) -> Result<[u8; 32], SCTError> {
+    Ok(sha2::Sha256::digest(verified_issuer_spki(chain)?).into())
+}
+
 #[derive(Debug, Error)]
 pub enum CertificateErrorKind {
     #[error("SCT list extension missing from leaf certificate")]
@@ -93,6 +127,9 @@ pub enum SCTError {
     #[error("failed to reconstruct signed payload")]
     Serialization(#[source] tls_codec::Error),
 
+    #[error("SCT timestamp out of range")]
+    TimestampOutOfRange,
+
     #[error("failed to verify SCT")]
     Verification(#[from] KeyringError),
 }
@@ -156,35 +193,36 @@ pub struct CertificateEmbeddedSCT<'a> {
 
 impl<'a> CertificateEmbeddedSCT<'a> {
     fn new_with_spki(cert: &'a Certificate, spki: &[u8]) -> Result<Self, SCTError> {
+        let mut scts = Self::all_with_spki(cert, spki)?;
+
+        // We expect exactly one element here. Fail if there are more or less.
+        if scts.len() != 1 {
+            return Err(CertificateErrorKind::LeafSCTMissing)?;
+        }
+
+        Ok(scts.remove(0))
+    }
+
+    fn all_with_spki(cert: &'a Certificate, spki: &[u8]) -> Result<Vec<Self>, SCTError> {
         let scts: SignedCertificateTimestampList = match cert.tbs_certificate.get() {
             Ok(Some((_, ext))) => ext,
             _ => return Err(SCTError::Parsing(CertificateErrorKind::LeafSCTMissing))?,
         };
 
+        let issuer_id: [u8; 32] = sha2::Sha256::digest(spki).into();
+
         // Parse SCT structures.
-        let sct = match scts
-            .parse_timestamps()
+        scts.parse_timestamps()
             .map_err(CertificateErrorKind::from)?
-            .as_slice()
-        {
-            [e] => e,
-            // We expect exactly one element here. Fail if there are more or less.
-            _ => return Err(CertificateErrorKind::LeafSCTMissing)?,
-        }
-        .parse_timestamp()
-        .map_err(CertificateErrorKind::from)?;
-
-        let issuer_id = {
-            let mut hasher = sha2::Sha256::new();
-            hasher.update(spki);
-            hasher.finalize().into()
-        };
-
-        Ok(Self {
-            cert,
-            sct,
-            issuer_id,
-        })
+            .iter()
+            .map(|sct| {
+                Ok(Self {
+                    cert,
+                    sct: sct.parse_timestamp().map_err(CertificateErrorKind::from)?,
+                    issuer_id,
+                })
+            })
+            .collect()
     }
 
     pub fn new(leaf: &'a Certificate, chain: &[Certificate]) -> Result<Self, SCTError> {
@@ -204,31 +242,28 @@ impl<'a> CertificateEmbeddedSCT<'a> {
         leaf: &'a Certificate,
         chain: &webpki::VerifiedPath,
     ) -> Result<Self, SCTError> {
-        let issuer_spki = if let Some(issuer) = chain.intermediate_certificates().next() {
-            debug!("intermediate is the leaf's issuer");
-
-            let issuer = Certificate::from_der(&issuer.der())
-                .map_err(CertificateErrorKind::from)?
-                .tbs_certificate;
-            issuer
-                .subject_public_key_info
-                .to_der()
-                .map_err(CertificateErrorKind::from)?
-        } else {
-            debug!("anchor is the leaf's issuer");
-
-            // Prefix the SPKI with the DER SEQUENCE tag and a short definite-form length.
-            let body = &chain.anchor().subject_public_key_info[..];
-            let body_len = body
-                .len()
-                .try_into()
-                .or(Err(CertificateErrorKind::IssuerMalformed))?;
-            let prefix = &[0x30u8, body_len];
-
-            [prefix, body].concat()
-        };
+        Self::new_with_spki(leaf, &verified_issuer_spki(chain)?)
+    }
+
+    /// Returns all of the SCTs embedded in `leaf`, which must contain at least one.
+    ///
+    /// Unlike [`CertificateEmbeddedSCT::new_with_verified_path`], this accepts certificates
+    /// logged to several certificate transparency logs.
+    pub fn all_with_verified_path(
+        leaf: &'a Certificate,
+        chain: &webpki::VerifiedPath,
+    ) -> Result<Vec<Self>, SCTError> {
+        Self::all_with_spki(leaf, &verified_issuer_spki(chain)?)
+    }
+
+    /// The SHA-256 digest of the issuing certificate authority's public key.
+    pub fn issuer_id(&self) -> [u8; 32] {
+        self.issuer_id
+    }
 
-        Self::new_with_spki(leaf, &issuer_spki)
+    /// The ID of the log that issued the SCT: the SHA-256 digest of its public key.
+    pub fn log_id(&self) -> [u8; 32] {
+        self.sct.log_id.key_id
     }
 }
 
@@ -300,8 +335,13 @@ where
 {
     let sct: DigitallySigned = sct.into();
     let serialized = sct.tls_serialize().map_err(SCTError::Serialization)?;
+    // The log's key must have been valid when it issued the SCT.
+    let timestamp = i64::try_from(sct.timestamp)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .ok_or(SCTError::TimestampOutOfRange)?;
 
-    keyring.verify(&sct.log_id, &sct.signature, &serialized)?;
+    keyring.verify(&sct.log_id, &sct.signature, &serialized, timestamp)?;
 
     Ok(())
 }
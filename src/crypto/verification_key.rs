@@ -19,11 +19,14 @@ use ed25519::pkcs8::DecodePublicKey as ED25519DecodePublicKey;
 use rsa::{pkcs1v15, pss};
 use sha2::{Digest, Sha256, Sha384};
 use signature::{hazmat::PrehashVerifier, DigestVerifier, Verifier};
-use x509_cert::{der::referenced::OwnedToRef, spki::SubjectPublicKeyInfoOwned};
+use x509_cert::{
+    der::referenced::OwnedToRef,
+    spki::{EncodePublicKey, SubjectPublicKeyInfoOwned},
+};
 
 use super::{
     signing_key::{KeyPair, SigStoreSigner},
-    Signature, SigningScheme,
+    DigestAlgorithm, Signature, SigningScheme,
 };
 
 use crate::errors::*;
@@ -105,7 +108,7 @@ impl TryFrom<&SubjectPublicKeyInfoOwned> for CosignVerificationKey {
                         ))
                     })?;
                 Ok(CosignVerificationKey::RSA_PKCS1_SHA256(
-                    pkcs1v15::VerifyingKey::<sha2::Sha256>::from(pubkey),
+                    pkcs1v15::VerifyingKey::<sha2::Sha256>::new(pubkey),
                 ))
             }
             //
@@ -257,6 +260,24 @@ impl CosignVerificationKey {
         signer.to_verification_key(signing_scheme)
     }
 
+    /// Returns the DER-encoded SubjectPublicKeyInfo of the key.
+    pub fn to_der(&self) -> Result<Vec<u8>> {
+        let der = match self {
+            CosignVerificationKey::RSA_PSS_SHA256(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::RSA_PSS_SHA384(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::RSA_PSS_SHA512(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::RSA_PKCS1_SHA256(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::RSA_PKCS1_SHA384(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::RSA_PKCS1_SHA512(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::ECDSA_P256_SHA256_ASN1(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::ECDSA_P384_SHA384_ASN1(inner) => inner.to_public_key_der(),
+            CosignVerificationKey::ED25519(inner) => inner.to_public_key_der(),
+        }
+        .map_err(|e| SigstoreError::PKCS8SpkiError(e.to_string()))?;
+
+        Ok(der.into_vec())
+    }
+
     /// Verify the signature provided has been actually generated by the given key
     /// when signing the provided message.
     pub fn verify_signature(&self, signature: Signature, msg: &[u8]) -> Result<()> {
@@ -329,6 +350,31 @@ impl CosignVerificationKey {
         }
     }
 
+    /// Returns the key, verifying PKCS#1 v1.5 RSA signatures over digests computed with
+    /// `digest_algorithm`, whose identifier they are padded with. Other keys are returned
+    /// unchanged: their signatures do not depend on the digest algorithm.
+    pub(crate) fn with_digest_algorithm(&self, digest_algorithm: DigestAlgorithm) -> Self {
+        let public_key = match self {
+            CosignVerificationKey::RSA_PKCS1_SHA256(inner) => inner.as_ref(),
+            CosignVerificationKey::RSA_PKCS1_SHA384(inner) => inner.as_ref(),
+            CosignVerificationKey::RSA_PKCS1_SHA512(inner) => inner.as_ref(),
+            _ => return self.clone(),
+        };
+        let public_key: rsa::RsaPublicKey = public_key.clone();
+
+        match digest_algorithm {
+            DigestAlgorithm::Sha256 => CosignVerificationKey::RSA_PKCS1_SHA256(
+                pkcs1v15::VerifyingKey::<sha2::Sha256>::new(public_key),
+            ),
+            DigestAlgorithm::Sha384 => CosignVerificationKey::RSA_PKCS1_SHA384(
+                pkcs1v15::VerifyingKey::<sha2::Sha384>::new(public_key),
+            ),
+            DigestAlgorithm::Sha512 => CosignVerificationKey::RSA_PKCS1_SHA512(
+                pkcs1v15::VerifyingKey::<sha2::Sha512>::new(public_key),
+            ),
+        }
+    }
+
     /// Verify the signature provided has been actually generated by the given key
     /// when signing the provided prehashed message.
     pub(crate) fn verify_prehash(&self, signature: Signature, msg: &[u8]) -> Result<()> {
@@ -414,6 +460,15 @@ mod tests {
         assert!(outcome.is_ok());
     }
 
+    #[test]
+    fn to_der_roundtrip() {
+        let key_pem = pem::parse(PUBLIC_KEY.as_bytes()).unwrap();
+        let verification_key = CosignVerificationKey::try_from_der(key_pem.contents())
+            .expect("Cannot create CosignVerificationKey");
+
+        assert_eq!(verification_key.to_der().unwrap(), key_pem.contents());
+    }
+
     #[test]
     fn verify_signature_failure_because_wrong_msg() {
         let signature = Signature::Base64Encoded(b"MEUCIQD6q/COgzOyW0YH1Dk+CCYSt4uAhm3FDHUwvPI55zwnlwIgE0ZK58ZOWpZw8YVmBapJhBqCfdPekIknimuO0xH8Jh8=");
@@ -72,6 +72,12 @@ pub enum SigstoreError {
     #[error("Public key with unsupported algorithm: {0}")]
     PublicKeyUnsupportedAlgorithmError(String),
 
+    #[error("Unsupported digest algorithm: {0}")]
+    DigestUnsupportedAlgorithmError(String),
+
+    #[error("Unsupported signing scheme: {0}")]
+    SigningSchemeUnsupportedError(String),
+
     #[error("Public key verification error")]
     PublicKeyVerificationError,
 
@@ -111,6 +117,9 @@ pub enum SigstoreError {
     #[error("Certificate pool error: {0}")]
     CertificatePoolError(String),
 
+    #[error("Signing context is not configured for {0}")]
+    SigningContextMismatchError(&'static str),
+
     #[error("Signing session expired")]
     ExpiredSigningSession(),
 
@@ -132,6 +141,9 @@ pub enum SigstoreError {
     #[error("Rekor request unsuccessful: {0}")]
     RekorClientError(String),
 
+    #[error("Timestamp authority request unsuccessful: {0}")]
+    TimestampAuthorityError(String),
+
     #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
 
@@ -143,6 +155,11 @@ pub enum SigstoreError {
     #[error(transparent)]
     SCTError(#[from] crate::crypto::transparency::SCTError),
 
+    #[cfg(feature = "verify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    #[error("signature failed verification after signing")]
+    SelfVerificationError(#[source] Box<crate::bundle::verify::VerificationError>),
+
     // HACK(tnytown): Remove when we rework the Fulcio V2 endpoint.
     #[cfg(feature = "fulcio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "fulcio")))]
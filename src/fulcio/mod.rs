@@ -2,8 +2,9 @@ pub(crate) mod models;
 
 pub mod oauth;
 
+use crate::crypto::signer::AsyncSigner;
 use crate::crypto::signing_key::SigStoreSigner;
-use crate::crypto::SigningScheme;
+use crate::crypto::{DigestAlgorithm, SigningScheme};
 use crate::errors::{Result, SigstoreError};
 use crate::fulcio::models::{CreateSigningCertificateRequest, SigningCertificate};
 use crate::fulcio::oauth::OauthTokenProvider;
@@ -15,7 +16,14 @@ use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 use std::fmt::{Debug, Display, Formatter};
 use url::Url;
-use x509_cert::{der::Decode, Certificate};
+use x509_cert::attr::{AttributeTypeAndValue, AttributeValue};
+use x509_cert::der::asn1::{Any, BitString, SetOfVec};
+use x509_cert::der::{Decode, Encode};
+use x509_cert::ext::{pkix::BasicConstraints, AsExtension};
+use x509_cert::name::Name;
+use x509_cert::request::{CertReq, CertReqInfo, ExtensionReq};
+use x509_cert::spki::{AlgorithmIdentifierOwned, SubjectPublicKeyInfoOwned};
+use x509_cert::Certificate;
 
 pub use models::{CertificateResponse, SigningCertificateDetachedSCT};
 
@@ -199,6 +207,35 @@ impl FulcioClient {
         Ok((signer, FulcioCert(cert)))
     }
 
+    /// Request a certificate from Fulcio with the V2 endpoint, for the key held by `signer`.
+    ///
+    /// The certificate signing request is issued for the email address of `identity`, and signed
+    /// by `signer` to prove possession of its key.
+    pub async fn request_cert_with_signer(
+        &self,
+        signer: &dyn AsyncSigner,
+        identity: &IdentityToken,
+    ) -> Result<CertificateResponse> {
+        let subject =
+                // SEQUENCE OF RelativeDistinguishedName
+                vec![
+                    // SET OF AttributeTypeAndValue
+                    vec![
+                        // AttributeTypeAndValue, `emailAddress=...`
+                        AttributeTypeAndValue {
+                            oid: const_oid::db::rfc3280::EMAIL_ADDRESS,
+                            value: AttributeValue::new(
+                                x509_cert::der::Tag::Utf8String,
+                                identity.unverified_claims().email.as_ref(),
+                            )?,
+                        }
+                    ].try_into()?
+                ].into();
+
+        let request = certificate_request(signer, subject).await?;
+        self.request_cert_v2(request, identity).await
+    }
+
     /// Request a certificate from Fulcio with the V2 endpoint.
     ///
     /// TODO(tnytown): This (and other API clients) should be autogenerated. See sigstore-rs#209.
@@ -267,3 +304,123 @@ impl FulcioClient {
         })
     }
 }
+
+/// Builds a certificate signing request for `subject`, signed by `signer`, for a key that may not
+/// be used as a CA.
+async fn certificate_request(signer: &dyn AsyncSigner, subject: Name) -> Result<CertReq> {
+    let public_key = SubjectPublicKeyInfoOwned::from_der(&signer.public_key().await?)?;
+    let algorithm = signature_algorithm(&public_key, signer.digest_algorithm())?;
+
+    let basic_constraints = BasicConstraints {
+        ca: false,
+        path_len_constraint: None,
+    };
+    let extension_req = ExtensionReq(vec![basic_constraints.to_extension(&subject, &[])?]);
+    let info = CertReqInfo {
+        version: x509_cert::request::Version::V1,
+        subject,
+        public_key,
+        attributes: SetOfVec::try_from(vec![extension_req.try_into()?])?,
+    };
+
+    let signature = signer.sign(&info.to_der()?).await?;
+    Ok(CertReq {
+        info,
+        algorithm,
+        signature: BitString::from_bytes(&signature)?,
+    })
+}
+
+/// Returns the identifier of the algorithm of signatures by `public_key`'s private key, over
+/// messages hashed with `digest_algorithm`.
+fn signature_algorithm(
+    public_key: &SubjectPublicKeyInfoOwned,
+    digest_algorithm: DigestAlgorithm,
+) -> Result<AlgorithmIdentifierOwned> {
+    use const_oid::db::{rfc5912::*, rfc8410::ID_ED_25519};
+
+    let (oid, parameters) = match (public_key.algorithm.oid, digest_algorithm) {
+        (ID_EC_PUBLIC_KEY, DigestAlgorithm::Sha256) => (ECDSA_WITH_SHA_256, None),
+        (ID_EC_PUBLIC_KEY, DigestAlgorithm::Sha384) => (ECDSA_WITH_SHA_384, None),
+        (ID_EC_PUBLIC_KEY, DigestAlgorithm::Sha512) => (ECDSA_WITH_SHA_512, None),
+        (RSA_ENCRYPTION, DigestAlgorithm::Sha256) => {
+            (SHA_256_WITH_RSA_ENCRYPTION, Some(Any::null()))
+        }
+        (RSA_ENCRYPTION, DigestAlgorithm::Sha384) => {
+            (SHA_384_WITH_RSA_ENCRYPTION, Some(Any::null()))
+        }
+        (RSA_ENCRYPTION, DigestAlgorithm::Sha512) => {
+            (SHA_512_WITH_RSA_ENCRYPTION, Some(Any::null()))
+        }
+        (ID_ED_25519, _) => (ID_ED_25519, None),
+        (oid, _) => {
+            return Err(SigstoreError::PublicKeyUnsupportedAlgorithmError(
+                oid.to_string(),
+            ))
+        }
+    };
+
+    Ok(AlgorithmIdentifierOwned { oid, parameters })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::crypto::signer::SoftwareSigner;
+    use crate::crypto::{CosignVerificationKey, Signature};
+
+    #[tokio::test]
+    async fn certificate_request_signed_by_signer() {
+        for scheme in [
+            SigningScheme::ECDSA_P256_SHA256_ASN1,
+            SigningScheme::ECDSA_P384_SHA384_ASN1,
+            SigningScheme::RSA_PKCS1_SHA512(2048),
+        ] {
+            let signer = SoftwareSigner::generate(scheme).unwrap();
+            let request = certificate_request(&signer, Name::from_str("CN=sigstore").unwrap())
+                .await
+                .unwrap();
+
+            // The request is self-signed with the advertised algorithm.
+            let key = CosignVerificationKey::from_der(
+                &request.info.public_key.to_der().unwrap(),
+                &scheme,
+            )
+            .unwrap();
+            assert!(
+                key.verify_signature(
+                    Signature::Raw(request.signature.raw_bytes()),
+                    &request.info.to_der().unwrap(),
+                )
+                .is_ok(),
+                "{scheme}"
+            );
+            assert_eq!(
+                request.algorithm,
+                signature_algorithm(&request.info.public_key, signer.digest_algorithm()).unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn certificate_request_ed25519() {
+        use ed25519_dalek::pkcs8::DecodePublicKey;
+
+        let signer = SoftwareSigner::generate(SigningScheme::ED25519).unwrap();
+        let request = certificate_request(&signer, Name::from_str("CN=sigstore").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(request.algorithm.oid, const_oid::db::rfc8410::ID_ED_25519);
+        let key = ed25519_dalek::VerifyingKey::from_public_key_der(
+            &request.info.public_key.to_der().unwrap(),
+        )
+        .unwrap();
+        let signature = ed25519::Signature::from_slice(request.signature.raw_bytes()).unwrap();
+        assert!(key
+            .verify_strict(&request.info.to_der().unwrap(), &signature)
+            .is_ok());
+    }
+}
@@ -92,11 +92,11 @@
 //!     data: fulcio_cert_data
 //!   };
 //!
-//!   let mut repo = sigstore::trust::ManualTrustRoot {
-//!     fulcio_certs: vec![fulcio_cert.try_into().unwrap()],
-//!     rekor_keys: vec![rekor_pub_key],
-//!     ..Default::default()
-//!   };
+//!   let mut repo = sigstore::trust::ManualTrustRoot::new(
+//!     vec![fulcio_cert.try_into().unwrap()],
+//!     vec![rekor_pub_key],
+//!     vec![],
+//!   );
 //!
 //!   let mut client = sigstore::cosign::ClientBuilder::default()
 //!     .with_trust_repository(&repo)
@@ -292,3 +292,7 @@ pub mod rekor;
 #[cfg_attr(docsrs, doc(cfg(any(feature = "sign", feature = "verify"))))]
 #[cfg(any(feature = "sign", feature = "verify"))]
 pub mod bundle;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+#[cfg(feature = "sign")]
+pub mod tsa;
@@ -0,0 +1,383 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for transparency log checkpoints.
+//!
+//! A checkpoint is a snapshot of the log's state (its origin, tree size and root hash), encoded
+//! in the [checkpoint format] and signed as a [signed note].
+//!
+//! [checkpoint format]: https://github.com/transparency-dev/formats/blob/main/log/README.md
+//! [signed note]: https://github.com/C2SP/C2SP/blob/main/signed-note.md
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::STANDARD as BASE64_STD_ENGINE, Engine as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// The prefix of each signature line in a signed note (an em dash followed by a space).
+const SIGNATURE_LINE_PREFIX: &str = "\u{2014} ";
+
+/// The length of the key hint that prefixes each note signature.
+const KEY_HINT_LEN: usize = 4;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CheckpointError {
+    #[error("checkpoint is malformed: {0}")]
+    Malformed(&'static str),
+
+    #[error("checkpoint has no signature with key hint {0}")]
+    MissingSignature(String),
+
+    #[error("checkpoint tree size {actual} does not match expected tree size {expected}")]
+    TreeSizeMismatch { actual: u64, expected: u64 },
+
+    #[error("checkpoint root hash {actual} does not match expected root hash {expected}")]
+    RootHashMismatch { actual: String, expected: String },
+}
+
+/// A checkpoint, together with the signatures over it.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SignedCheckpoint {
+    pub note: CheckpointNote,
+    pub signatures: Vec<CheckpointSignature>,
+}
+
+/// The body of a checkpoint: the log state that is signed.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointNote {
+    /// Uniquely identifies the log that produced the checkpoint.
+    pub origin: String,
+    /// The number of entries in the log.
+    pub size: u64,
+    /// The root hash of the log's Merkle tree.
+    pub hash: [u8; 32],
+    /// Any extension lines following the root hash, without trailing newlines.
+    pub other_content: Vec<String>,
+}
+
+/// A single signature line of a signed note.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointSignature {
+    /// The name of the signer.
+    pub name: String,
+    /// The first four bytes of the signer's key ID.
+    pub key_hint: [u8; KEY_HINT_LEN],
+    /// The raw signature over the note's text.
+    pub signature: Vec<u8>,
+}
+
+impl SignedCheckpoint {
+    /// Returns the signatures whose key hint matches the given key ID, in note order.
+    ///
+    /// Key hints may collide, so callers must try each signature in turn. For Rekor, the key ID
+    /// is the log ID: the SHA-256 digest of the log's DER-encoded public key.
+    pub fn signatures_for(
+        &self,
+        key_id: &[u8],
+    ) -> Result<Vec<&CheckpointSignature>, CheckpointError> {
+        let hint = key_id.get(..KEY_HINT_LEN).unwrap_or(key_id);
+
+        let signatures: Vec<_> = self
+            .signatures
+            .iter()
+            .filter(|s| s.key_hint[..] == *hint)
+            .collect();
+        if signatures.is_empty() {
+            return Err(CheckpointError::MissingSignature(hex::encode(hint)));
+        }
+
+        Ok(signatures)
+    }
+
+    /// Checks that the checkpoint describes a tree with the given root hash and size, as
+    /// claimed by an inclusion proof.
+    pub fn is_valid_for_proof(
+        &self,
+        root_hash: &[u8],
+        tree_size: u64,
+    ) -> Result<(), CheckpointError> {
+        if self.note.size != tree_size {
+            return Err(CheckpointError::TreeSizeMismatch {
+                actual: self.note.size,
+                expected: tree_size,
+            });
+        }
+
+        if self.note.hash[..] != *root_hash {
+            return Err(CheckpointError::RootHashMismatch {
+                actual: hex::encode(self.note.hash),
+                expected: hex::encode(root_hash),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl CheckpointNote {
+    /// Returns the note's text: the bytes covered by each signature.
+    pub fn marshal(&self) -> String {
+        let mut text = format!(
+            "{}\n{}\n{}\n",
+            self.origin,
+            self.size,
+            BASE64_STD_ENGINE.encode(self.hash)
+        );
+        for line in &self.other_content {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl FromStr for SignedCheckpoint {
+    type Err = CheckpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The note text and signatures are separated by a blank line. The note text keeps its
+        // final newline.
+        let (note, signatures) = s
+            .split_once("\n\n")
+            .ok_or(CheckpointError::Malformed("missing signature block"))?;
+        let note = CheckpointNote::from_str(&s[..=note.len()])?;
+
+        let signatures = signatures
+            .strip_suffix('\n')
+            .ok_or(CheckpointError::Malformed(
+                "signature block must end with a newline",
+            ))?
+            .split('\n')
+            .map(CheckpointSignature::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(SignedCheckpoint { note, signatures })
+    }
+}
+
+impl FromStr for CheckpointNote {
+    type Err = CheckpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .strip_suffix('\n')
+            .ok_or(CheckpointError::Malformed("note must end with a newline"))?
+            .split('\n');
+
+        let origin = lines
+            .next()
+            .filter(|origin| !origin.is_empty())
+            .ok_or(CheckpointError::Malformed("missing origin"))?
+            .to_owned();
+        let size = lines
+            .next()
+            .and_then(|size| size.parse().ok())
+            .ok_or(CheckpointError::Malformed("missing or invalid tree size"))?;
+        let hash = lines
+            .next()
+            .and_then(|hash| BASE64_STD_ENGINE.decode(hash).ok())
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or(CheckpointError::Malformed("missing or invalid root hash"))?;
+        let other_content = lines.map(str::to_owned).collect::<Vec<_>>();
+
+        if other_content.iter().any(String::is_empty) {
+            return Err(CheckpointError::Malformed("note contains an empty line"));
+        }
+
+        Ok(CheckpointNote {
+            origin,
+            size,
+            hash,
+            other_content,
+        })
+    }
+}
+
+impl FromStr for CheckpointSignature {
+    type Err = CheckpointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, signature) = s
+            .strip_prefix(SIGNATURE_LINE_PREFIX)
+            .and_then(|line| line.split_once(' '))
+            .ok_or(CheckpointError::Malformed("invalid signature line"))?;
+        if name.is_empty() {
+            return Err(CheckpointError::Malformed("signature has empty name"));
+        }
+
+        let signature = BASE64_STD_ENGINE
+            .decode(signature)
+            .or(Err(CheckpointError::Malformed(
+                "signature is not valid base64",
+            )))?;
+        if signature.len() <= KEY_HINT_LEN {
+            return Err(CheckpointError::Malformed("signature is too short"));
+        }
+        let (key_hint, signature) = signature.split_at(KEY_HINT_LEN);
+
+        Ok(CheckpointSignature {
+            name: name.to_owned(),
+            key_hint: key_hint.try_into().expect("key hint has fixed length"),
+            signature: signature.to_vec(),
+        })
+    }
+}
+
+impl Display for SignedCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.note.marshal())?;
+        for signature in &self.signatures {
+            writeln!(f, "{signature}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CheckpointSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut signature = self.key_hint.to_vec();
+        signature.extend_from_slice(&self.signature);
+
+        write!(
+            f,
+            "{SIGNATURE_LINE_PREFIX}{} {}",
+            self.name,
+            BASE64_STD_ENGINE.encode(signature)
+        )
+    }
+}
+
+impl Serialize for SignedCheckpoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SignedCheckpoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKPOINT: &str = "rekor.sigstore.dev - 2605736670972794746\n\
+        21428036\n\
+        rxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n\
+        Timestamp: 1688058656037355364\n\
+        \n\
+        \u{2014} rekor.sigstore.dev wNI9ajBFAiEAuDk7uu5Ae8Own/MjhSZNuVzbLt7eE+jW/ToBpGOmJ1sCIEv0nPgFJ4GIgv9tjT3HWOJaVbdaqDkxQrBm3mSmB31C\n";
+
+    #[test]
+    fn parse_checkpoint() {
+        let checkpoint: SignedCheckpoint = CHECKPOINT.parse().expect("failed to parse checkpoint");
+
+        assert_eq!(
+            checkpoint.note.origin,
+            "rekor.sigstore.dev - 2605736670972794746"
+        );
+        assert_eq!(checkpoint.note.size, 21428036);
+        assert_eq!(
+            hex::encode(checkpoint.note.hash),
+            "af19e82b2159949eff47a6cc87f77795cab02aa032e422f52dc34124fd7b9204"
+        );
+        assert_eq!(
+            checkpoint.note.other_content,
+            vec!["Timestamp: 1688058656037355364"]
+        );
+
+        assert_eq!(checkpoint.signatures.len(), 1);
+        assert_eq!(checkpoint.signatures[0].name, "rekor.sigstore.dev");
+        assert_eq!(checkpoint.signatures[0].key_hint, [0xc0, 0xd2, 0x3d, 0x6a]);
+    }
+
+    #[test]
+    fn checkpoint_roundtrip() {
+        let checkpoint: SignedCheckpoint = CHECKPOINT.parse().unwrap();
+        assert_eq!(checkpoint.to_string(), CHECKPOINT);
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let deserialized: SignedCheckpoint = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, checkpoint);
+    }
+
+    #[test]
+    fn checkpoint_signatures_for() {
+        let mut checkpoint: SignedCheckpoint = CHECKPOINT.parse().unwrap();
+        let log_id =
+            hex::decode("c0d23d6ad406973f9559f3ba2d1ca01f84147d8ffc5b8445c224f98b9591801d")
+                .unwrap();
+
+        assert_eq!(checkpoint.signatures_for(&log_id).unwrap().len(), 1);
+        assert_eq!(
+            checkpoint.signatures_for(&[0; 32]),
+            Err(CheckpointError::MissingSignature("00000000".into()))
+        );
+
+        // Signers whose key hints collide are all returned.
+        let mut colliding = checkpoint.signatures[0].clone();
+        colliding.name = "other.example.com".into();
+        checkpoint.signatures.insert(0, colliding);
+        let signatures = checkpoint.signatures_for(&log_id).unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[1].name, "rekor.sigstore.dev");
+    }
+
+    #[test]
+    fn checkpoint_is_valid_for_proof() {
+        let checkpoint: SignedCheckpoint = CHECKPOINT.parse().unwrap();
+        let root_hash = checkpoint.note.hash;
+
+        assert!(checkpoint.is_valid_for_proof(&root_hash, 21428036).is_ok());
+        assert!(matches!(
+            checkpoint.is_valid_for_proof(&root_hash, 21428037),
+            Err(CheckpointError::TreeSizeMismatch { .. })
+        ));
+        assert!(matches!(
+            checkpoint.is_valid_for_proof(&[0; 32], 21428036),
+            Err(CheckpointError::RootHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_malformed_checkpoint() {
+        for malformed in [
+            // No signatures.
+            "rekor.sigstore.dev - 2605736670972794746\n21428036\nrxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n",
+            // Invalid tree size.
+            "rekor.sigstore.dev\n-1\nrxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n\n\u{2014} rekor.sigstore.dev wNI9ajBF\n",
+            // Truncated root hash.
+            "rekor.sigstore.dev\n1\nrxnoKyFZlJ7/R6bMh/d3lcqw\n\n\u{2014} rekor.sigstore.dev wNI9ajBF\n",
+            // Signature line without the em dash.
+            "rekor.sigstore.dev\n1\nrxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n\n- rekor.sigstore.dev wNI9ajBF\n",
+            // Signature shorter than the key hint.
+            "rekor.sigstore.dev\n1\nrxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n\n\u{2014} rekor.sigstore.dev wNI9\n",
+            // Missing final newline.
+            "rekor.sigstore.dev\n1\nrxnoKyFZlJ7/R6bMh/d3lcqwKqAy5CL1LcNBJP17kgQ=\n\n\u{2014} rekor.sigstore.dev wNI9ajBF",
+        ] {
+            assert!(
+                matches!(
+                    malformed.parse::<SignedCheckpoint>(),
+                    Err(CheckpointError::Malformed(_))
+                ),
+                "{malformed:?} should not parse"
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// Stores the content of a proposed `dsse` (v0.0.1) entry
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Spec {
+    pub proposed_content: ProposedContent,
+}
+
+impl Spec {
+    pub fn new(proposed_content: ProposedContent) -> Spec {
+        Spec { proposed_content }
+    }
+}
+
+/// Stores a DSSE envelope, in its JSON encoding, and the base64-encoded PEM certificates or
+/// public keys that verify its signatures
+#[derive(Default, Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedContent {
+    pub envelope: String,
+    pub verifiers: Vec<String>,
+}
+
+impl ProposedContent {
+    pub fn new(envelope: String, verifiers: Vec<String>) -> ProposedContent {
+        ProposedContent {
+            envelope,
+            verifiers,
+        }
+    }
+}
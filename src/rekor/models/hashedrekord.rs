@@ -104,6 +104,8 @@ pub enum AlgorithmKind {
     #[default]
     sha256,
     sha1,
+    sha384,
+    sha512,
 }
 
 /// Stores the algorithm used to hash the artifact and the value of the hash
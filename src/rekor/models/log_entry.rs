@@ -23,8 +23,8 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use super::{
-    AlpineAllOf, HashedrekordAllOf, HelmAllOf, IntotoAllOf, JarAllOf, RekordAllOf, Rfc3161AllOf,
-    RpmAllOf, TufAllOf,
+    AlpineAllOf, DsseAllOf, HashedrekordAllOf, HelmAllOf, IntotoAllOf, JarAllOf, RekordAllOf,
+    Rfc3161AllOf, RpmAllOf, TufAllOf,
 };
 
 /// Stores the response returned by Rekor after making a new entry
@@ -69,6 +69,7 @@ pub enum Body {
     rpm(RpmAllOf),
     tuf(TufAllOf),
     intoto(IntotoAllOf),
+    dsse(DsseAllOf),
     hashedrekord(HashedrekordAllOf),
     rekord(RekordAllOf),
 }
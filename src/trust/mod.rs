@@ -13,17 +13,86 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono::{DateTime, Utc};
 use webpki::types::CertificateDer;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "sigstore-trust-root")))]
 #[cfg(feature = "sigstore-trust-root")]
 pub mod sigstore;
 
+/// The period during which a piece of trust material may be used. A missing bound leaves the
+/// period open on that side.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidityPeriod {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl ValidityPeriod {
+    /// Checks whether `time` falls within the period, bounds included.
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        !matches!(self.start, Some(start) if time < start)
+            && !matches!(self.end, Some(end) if end < time)
+    }
+}
+
+/// A trusted certificate authority: its certificate chain and the period during which it issued
+/// certificates.
+#[derive(Clone, Debug)]
+pub struct CertificateAuthority<'a> {
+    pub certs: Vec<CertificateDer<'a>>,
+    pub valid_for: ValidityPeriod,
+}
+
+/// A trusted log's DER-encoded SPKI public key and the period during which it signed entries.
+#[derive(Clone, Debug)]
+pub struct LogKey<'a> {
+    pub key: &'a [u8],
+    pub valid_for: ValidityPeriod,
+}
+
 /// A `TrustRoot` owns all key material necessary for establishing a root of trust.
 pub trait TrustRoot {
     fn fulcio_certs(&self) -> crate::errors::Result<Vec<CertificateDer>>;
     fn rekor_keys(&self) -> crate::errors::Result<Vec<&[u8]>>;
     fn ctfe_keys(&self) -> crate::errors::Result<Vec<&[u8]>>;
+
+    /// Certificate chains of trusted timestamp authorities. Trust roots without timestamp
+    /// authorities return an empty list.
+    fn tsa_certs(&self) -> crate::errors::Result<Vec<CertificateDer<'_>>> {
+        Ok(vec![])
+    }
+
+    /// Trusted Fulcio certificate authorities, including rotated-out ones, with their validity
+    /// periods. Defaults to a single authority holding [`TrustRoot::fulcio_certs`], valid at all
+    /// times.
+    fn fulcio_authorities(&self) -> crate::errors::Result<Vec<CertificateAuthority<'_>>> {
+        Ok(vec![CertificateAuthority {
+            certs: self.fulcio_certs()?,
+            valid_for: ValidityPeriod::default(),
+        }])
+    }
+
+    /// Trusted Rekor keys, including rotated-out ones, with their validity periods. Defaults to
+    /// [`TrustRoot::rekor_keys`], valid at all times.
+    fn rekor_log_keys(&self) -> crate::errors::Result<Vec<LogKey<'_>>> {
+        Ok(unbounded(self.rekor_keys()?))
+    }
+
+    /// Trusted CT log keys, including rotated-out ones, with their validity periods. Defaults to
+    /// [`TrustRoot::ctfe_keys`], valid at all times.
+    fn ctfe_log_keys(&self) -> crate::errors::Result<Vec<LogKey<'_>>> {
+        Ok(unbounded(self.ctfe_keys()?))
+    }
+}
+
+fn unbounded(keys: Vec<&[u8]>) -> Vec<LogKey<'_>> {
+    keys.into_iter()
+        .map(|key| LogKey {
+            key,
+            valid_for: ValidityPeriod::default(),
+        })
+        .collect()
 }
 
 /// A `ManualTrustRoot` is a [TrustRoot] with out-of-band trust materials.
@@ -33,6 +102,30 @@ pub struct ManualTrustRoot<'a> {
     pub fulcio_certs: Vec<CertificateDer<'a>>,
     pub rekor_keys: Vec<Vec<u8>>,
     pub ctfe_keys: Vec<Vec<u8>>,
+    pub tsa_certs: Vec<CertificateDer<'a>>,
+}
+
+impl<'a> ManualTrustRoot<'a> {
+    /// Creates a `ManualTrustRoot` from Fulcio certificates and DER encoded SPKI-format Rekor and
+    /// CT log keys. It trusts no timestamp authority until [`Self::with_tsa_certs`] is called.
+    pub fn new(
+        fulcio_certs: Vec<CertificateDer<'a>>,
+        rekor_keys: Vec<Vec<u8>>,
+        ctfe_keys: Vec<Vec<u8>>,
+    ) -> Self {
+        Self {
+            fulcio_certs,
+            rekor_keys,
+            ctfe_keys,
+            tsa_certs: Vec::new(),
+        }
+    }
+
+    /// Trusts the timestamp authorities issuing from the given certificate chains.
+    pub fn with_tsa_certs(mut self, tsa_certs: Vec<CertificateDer<'a>>) -> Self {
+        self.tsa_certs = tsa_certs;
+        self
+    }
 }
 
 impl TrustRoot for ManualTrustRoot<'_> {
@@ -47,4 +140,8 @@ impl TrustRoot for ManualTrustRoot<'_> {
     fn ctfe_keys(&self) -> crate::errors::Result<Vec<&[u8]>> {
         Ok(self.ctfe_keys.iter().map(|v| &v[..]).collect())
     }
+
+    fn tsa_certs(&self) -> crate::errors::Result<Vec<CertificateDer<'_>>> {
+        Ok(self.tsa_certs.clone())
+    }
 }
@@ -36,6 +36,7 @@ use webpki::types::CertificateDer;
 mod constants;
 
 use crate::errors::{Result, SigstoreError};
+use crate::trust::{self, LogKey, ValidityPeriod};
 pub use crate::trust::{ManualTrustRoot, TrustRoot};
 
 /// Securely fetches Rekor public key and Fulcio certificates from Sigstore's TUF repository.
@@ -155,6 +156,20 @@ impl SigstoreTrustRoot {
             .flat_map(|chain| chain.certificates.iter())
             .map(|cert| cert.raw_bytes.as_slice())
     }
+
+    #[inline]
+    fn tlog_log_keys(tlogs: &[TransparencyLogInstance]) -> Vec<LogKey<'_>> {
+        tlogs
+            .iter()
+            .filter_map(|tlog| tlog.public_key.as_ref())
+            .filter_map(|key| {
+                Some(LogKey {
+                    key: key.raw_bytes.as_ref()?,
+                    valid_for: validity_period(key.valid_for.as_ref()),
+                })
+            })
+            .collect()
+    }
 }
 
 impl crate::trust::TrustRoot for SigstoreTrustRoot {
@@ -210,6 +225,85 @@ impl crate::trust::TrustRoot for SigstoreTrustRoot {
             Ok(keys)
         }
     }
+
+    /// Fetch timestamp authority certificates from the given TUF repository or reuse
+    /// the local cache if it's not outdated.
+    ///
+    /// Unlike Fulcio certificates, an empty list is not an error: not every
+    /// trust root has timestamp authorities.
+    fn tsa_certs(&self) -> Result<Vec<CertificateDer<'_>>> {
+        // Allow expired certificates: they may have been active when the
+        // timestamp was issued.
+        Ok(
+            Self::ca_keys(&self.trusted_root.timestamp_authorities, true)
+                .map(|c| CertificateDer::from(c).into_owned())
+                .collect(),
+        )
+    }
+
+    /// Returns every Fulcio certificate authority in the trusted root, each with its validity
+    /// period, so that certificates issued by rotated-out authorities can still be verified.
+    fn fulcio_authorities(&self) -> Result<Vec<trust::CertificateAuthority<'_>>> {
+        let authorities: Vec<_> = self
+            .trusted_root
+            .certificate_authorities
+            .iter()
+            .map(|ca| trust::CertificateAuthority {
+                certs: ca
+                    .cert_chain
+                    .iter()
+                    .flat_map(|chain| chain.certificates.iter())
+                    .map(|cert| CertificateDer::from(cert.raw_bytes.as_slice()))
+                    .collect(),
+                valid_for: validity_period(ca.valid_for.as_ref()),
+            })
+            .collect();
+
+        if authorities.is_empty() {
+            Err(SigstoreError::TufMetadataError(
+                "Fulcio certificates not found".into(),
+            ))
+        } else {
+            Ok(authorities)
+        }
+    }
+
+    /// Returns every Rekor key in the trusted root, each with its validity period.
+    fn rekor_log_keys(&self) -> Result<Vec<LogKey<'_>>> {
+        let keys = Self::tlog_log_keys(&self.trusted_root.tlogs);
+
+        if keys.is_empty() {
+            Err(SigstoreError::TufMetadataError(
+                "Rekor keys not found".into(),
+            ))
+        } else {
+            Ok(keys)
+        }
+    }
+
+    /// Returns every CTFE key in the trusted root, each with its validity period.
+    fn ctfe_log_keys(&self) -> Result<Vec<LogKey<'_>>> {
+        let keys = Self::tlog_log_keys(&self.trusted_root.ctlogs);
+
+        if keys.is_empty() {
+            Err(SigstoreError::TufMetadataError(
+                "CTFE keys not found".into(),
+            ))
+        } else {
+            Ok(keys)
+        }
+    }
+}
+
+/// Converts a trusted root `TimeRange` into a [`ValidityPeriod`]. A missing range is unbounded.
+fn validity_period(range: Option<&TimeRange>) -> ValidityPeriod {
+    let start = range.and_then(|r| r.start.as_ref());
+    let end = range.and_then(|r| r.end.as_ref());
+
+    ValidityPeriod {
+        start: start.and_then(|t| chrono::DateTime::from_timestamp(t.seconds, t.nanos as u32)),
+        end: end.and_then(|t| chrono::DateTime::from_timestamp(t.seconds, t.nanos as u32)),
+    }
 }
 
 /// Given a `range`, checks that the the current time is not before `start`. If
@@ -332,4 +426,26 @@ mod tests {
         // Valid: 1 ago, 1 ago
         assert!(is_timerange_valid(Some(&range_from(-1, -1)), true))
     }
+
+    #[test]
+    fn test_validity_period() {
+        assert_eq!(validity_period(None), ValidityPeriod::default());
+
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let range = TimeRange {
+            start: Some(SystemTime::from(start).into()),
+            end: None,
+        };
+        let period = validity_period(Some(&range));
+        assert_eq!(
+            period,
+            ValidityPeriod {
+                start: Some(start),
+                end: None,
+            }
+        );
+        assert!(!period.contains(start - chrono::TimeDelta::seconds(1)));
+        assert!(period.contains(start));
+        assert!(period.contains(chrono::Utc::now()));
+    }
 }
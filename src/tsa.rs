@@ -0,0 +1,216 @@
+// Copyright 2024 The Sigstore Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client for [RFC 3161] timestamp authorities (TSAs).
+//!
+//! A TSA countersigns a digest of a Sigstore signature along with the current time. Signed
+//! timestamps attest to when the signature was produced, with or without a transparency log.
+//!
+//! [RFC 3161]: https://datatracker.ietf.org/doc/html/rfc3161
+
+use async_trait::async_trait;
+use cmpv2::status::PkiStatus;
+use cms::signed_data::SignedData;
+use const_oid::db::rfc5912::ID_SHA_256;
+use rand::RngCore;
+use reqwest::header;
+use sha2::{Digest, Sha256};
+use url::Url;
+use x509_cert::{
+    der::{
+        asn1::{Int, OctetString},
+        Decode, Encode,
+    },
+    spki::AlgorithmIdentifierOwned,
+};
+use x509_tsp::{MessageImprint, TimeStampReq, TimeStampResp, TspVersion, TstInfo};
+
+use crate::errors::{Result, SigstoreError};
+
+/// A source of RFC 3161 signed timestamps.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait TimestampAuthority: Send + Sync {
+    /// Requests a timestamp over `signature`, and returns the DER-encoded `TimeStampResp`.
+    async fn timestamp(&self, signature: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A client for a timestamp authority's HTTP endpoint, as described in [RFC 3161 section 3.4].
+///
+/// [RFC 3161 section 3.4]: https://datatracker.ietf.org/doc/html/rfc3161#section-3.4
+pub struct TimestampClient {
+    url: Url,
+    client: reqwest::Client,
+}
+
+impl TimestampClient {
+    /// Creates a client for the timestamp authority at `url`, e.g.
+    /// `https://timestamp.sigstore.dev/api/v1/timestamp`.
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TimestampAuthority for TimestampClient {
+    async fn timestamp(&self, signature: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        // Keep the nonce positive, so that its DER encoding is stable.
+        nonce[0] &= 0x7f;
+
+        let request = TimeStampReq {
+            version: TspVersion::V1,
+            message_imprint: message_imprint(signature)?,
+            req_policy: None,
+            nonce: Some(Int::new(&nonce)?),
+            cert_req: true,
+            extensions: None,
+        };
+
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header(header::CONTENT_TYPE, "application/timestamp-query")
+            .header(header::ACCEPT, "application/timestamp-reply")
+            .body(request.to_der()?)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| SigstoreError::TimestampAuthorityError(err.to_string()))?
+            .bytes()
+            .await
+            .map_err(|err| SigstoreError::TimestampAuthorityError(err.to_string()))?;
+
+        check_response(&response, &request)?;
+        Ok(response.to_vec())
+    }
+}
+
+/// Returns the message imprint of a timestamp over `signature`: its SHA-256 digest.
+fn message_imprint(signature: &[u8]) -> Result<MessageImprint> {
+    Ok(MessageImprint {
+        hash_algorithm: AlgorithmIdentifierOwned {
+            oid: ID_SHA_256,
+            parameters: None,
+        },
+        hashed_message: OctetString::new(Sha256::digest(signature).to_vec())?,
+    })
+}
+
+/// Checks that a DER-encoded `TimeStampResp` grants a timestamp matching `request`.
+///
+/// The timestamp's signature is not verified: that is the job of the bundle's verifier, which
+/// holds the trusted timestamp authorities.
+fn check_response(response: &[u8], request: &TimeStampReq) -> Result<()> {
+    let malformed = |err: x509_cert::der::Error| {
+        SigstoreError::TimestampAuthorityError(format!("malformed timestamp response: {err}"))
+    };
+
+    let response = TimeStampResp::from_der(response).map_err(malformed)?;
+    let token = match response.status.status {
+        PkiStatus::Accepted | PkiStatus::GrantedWithMods => response.time_stamp_token,
+        _ => None,
+    }
+    .ok_or_else(|| {
+        SigstoreError::TimestampAuthorityError(format!(
+            "timestamp request was not granted: {:?}",
+            response.status.status
+        ))
+    })?;
+
+    let signed_data: SignedData = token.content.decode_as().map_err(malformed)?;
+    let tst_info = signed_data
+        .encap_content_info
+        .econtent
+        .as_ref()
+        .map(|econtent| TstInfo::from_der(econtent.value()))
+        .transpose()
+        .map_err(malformed)?
+        .ok_or_else(|| SigstoreError::TimestampAuthorityError("timestamp has no content".into()))?;
+
+    if tst_info.message_imprint != request.message_imprint || tst_info.nonce != request.nonce {
+        return Err(SigstoreError::TimestampAuthorityError(
+            "timestamp does not match the request".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(feature = "verify")]
+mod tests {
+    use super::*;
+    use crate::crypto::timestamp::tests::TestTsa;
+    use cmpv2::status::PkiStatusInfo;
+
+    fn request(signature: &[u8]) -> TimeStampReq {
+        TimeStampReq {
+            version: TspVersion::V1,
+            message_imprint: message_imprint(signature).unwrap(),
+            req_policy: None,
+            nonce: None,
+            cert_req: true,
+            extensions: None,
+        }
+    }
+
+    #[test]
+    fn check_response_granted() {
+        let tsa = TestTsa::new();
+        let response = tsa.response(b"signature");
+
+        assert!(check_response(&response, &request(b"signature")).is_ok());
+        assert!(matches!(
+            check_response(&response, &request(b"other signature")),
+            Err(SigstoreError::TimestampAuthorityError(_))
+        ));
+
+        // The request's nonce must be echoed.
+        let mut with_nonce = request(b"signature");
+        with_nonce.nonce = Some(Int::new(&[1]).unwrap());
+        assert!(matches!(
+            check_response(&response, &with_nonce),
+            Err(SigstoreError::TimestampAuthorityError(_))
+        ));
+    }
+
+    #[test]
+    fn check_response_rejected() {
+        let response = TimeStampResp {
+            status: PkiStatusInfo {
+                status: PkiStatus::Rejection,
+                status_string: None,
+                fail_info: None,
+            },
+            time_stamp_token: None,
+        }
+        .to_der()
+        .unwrap();
+
+        assert!(matches!(
+            check_response(&response, &request(b"signature")),
+            Err(SigstoreError::TimestampAuthorityError(_))
+        ));
+        assert!(matches!(
+            check_response(b"not a response", &request(b"signature")),
+            Err(SigstoreError::TimestampAuthorityError(_))
+        ));
+    }
+}